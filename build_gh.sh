@@ -5,8 +5,19 @@ REPO=london_classical
 GIT_DIR="$(git rev-parse --show-toplevel)"
 FILENAME="concerts.json"
 
+# Check the data before uploading it, so that a broken scraper doesn't get published
+cd "${GIT_DIR}/rust"
+if ! cargo run --quiet -- validate; then
+    echo "Validation of ${FILENAME} failed; not uploading" >&2
+    exit 1
+fi
+
 cd "${GIT_DIR}/src/assets"
-gh release upload json ${FILENAME} --clobber
+gh release upload json ${FILENAME} "${GIT_DIR}/rust/concerts.schema.json" --clobber
+
+# The feeds are built into the website by the workflow, like concerts.json
+tar -czf /tmp/feeds.tar.gz -C "${GIT_DIR}/public" feeds
+gh release upload json /tmp/feeds.tar.gz --clobber
 
 cd ${GIT_DIR}
 WORKFLOW=$(ls .github/workflows)
@@ -0,0 +1,479 @@
+use crate::core::{self, Availability, Category, Concert};
+use crate::query::{ConcertQuery, Discount};
+use chrono_tz::Europe::London;
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+/// Configuration for saved-search alerts, read from a TOML file. See `alerts.example.toml` for an
+/// example.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    /// If not given, digests are generated but not sent
+    pub smtp: Option<SmtpConfig>,
+    #[serde(default)]
+    pub subscribers: Vec<Subscriber>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to the standard port for the chosen security
+    pub port: Option<u16>,
+    /// Sender address, e.g. "London Classical <alerts@example.com>"
+    pub from: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Unencrypted: only useful for a local SMTP server
+    None,
+    #[default]
+    StartTls,
+    Tls,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscriber {
+    pub name: String,
+    pub email: String,
+    #[serde(default)]
+    pub searches: Vec<SavedSearch>,
+}
+
+/// A saved search. All the criteria which are given have to be satisfied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub composer: Option<String>,
+    pub performer: Option<String>,
+    pub venue: Option<String>,
+    pub category: Option<Category>,
+    /// Maximum price in pennies
+    pub max_price: Option<u32>,
+    pub availability: Option<Availability>,
+    /// Only match concerts with Wigmore Hall under-35 tickets
+    #[serde(default)]
+    pub wigmore_u35: bool,
+}
+
+impl SavedSearch {
+    fn to_query(&self) -> ConcertQuery {
+        ConcertQuery {
+            composer: self.composer.clone(),
+            performer: self.performer.clone(),
+            venue: self.venue.clone(),
+            category: self.category,
+            max_price: self.max_price,
+            availability: self.availability,
+            discount: self.wigmore_u35.then_some(Discount::WigmoreU35),
+            ..Default::default()
+        }
+    }
+}
+
+impl AlertsConfig {
+    pub fn read(path: &Path) -> AlertsConfig {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        toml::from_str(&text)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e))
+    }
+}
+
+/// New concerts matching a subscriber's saved searches
+#[derive(Debug, Clone)]
+pub struct Digest<'a> {
+    pub subscriber: &'a Subscriber,
+    /// One entry for each saved search which had any matches
+    pub matches: Vec<(&'a SavedSearch, Vec<&'a Concert>)>,
+}
+
+/// Build the digests for all subscribers. Subscribers with no matches don't get a digest.
+pub fn digests<'a>(config: &'a AlertsConfig, new_concerts: &[&'a Concert]) -> Vec<Digest<'a>> {
+    config
+        .subscribers
+        .iter()
+        .map(|subscriber| {
+            let matches = subscriber
+                .searches
+                .iter()
+                .map(|search| {
+                    let query = search.to_query();
+                    let matching = new_concerts
+                        .iter()
+                        .filter(|c| query.matches(c))
+                        .copied()
+                        .collect::<Vec<&Concert>>();
+                    (search, matching)
+                })
+                .filter(|(_, matching)| !matching.is_empty())
+                .collect();
+            Digest {
+                subscriber,
+                matches,
+            }
+        })
+        .filter(|digest| !digest.matches.is_empty())
+        .collect()
+}
+
+fn describe_datetime(c: &Concert) -> String {
+    c.concert
+        .datetime
+        .with_timezone(&London)
+        .format("%a %-d %b %Y, %H:%M")
+        .to_string()
+}
+
+impl Digest<'_> {
+    /// Number of distinct concerts in the digest (a concert can match several saved searches)
+    pub fn n_concerts(&self) -> usize {
+        self.matches
+            .iter()
+            .flat_map(|(_, concerts)| concerts.iter().map(|c| c.id.as_str()))
+            .collect::<HashSet<&str>>()
+            .len()
+    }
+
+    pub fn subject(&self) -> String {
+        match self.n_concerts() {
+            1 => "1 new concert matching your saved searches".to_string(),
+            n => format!("{} new concerts matching your saved searches", n),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("Hi {},\n\n{}.\n", self.subscriber.name, self.subject());
+        for (search, concerts) in &self.matches {
+            text.push_str(&format!("\n== {} ==\n", search.name));
+            for c in concerts {
+                text.push_str(&format!(
+                    "\n{}\n  {} | {}\n  {}\n  {}\n",
+                    c.concert.title,
+                    describe_datetime(c),
+                    c.concert.venue,
+                    core::price_string(&c.concert),
+                    c.concert.url
+                ));
+            }
+        }
+        text
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = format!(
+            "<html><body><p>Hi {},</p><p>{}.</p>",
+            encode_text(&self.subscriber.name),
+            encode_text(&self.subject())
+        );
+        for (search, concerts) in &self.matches {
+            html.push_str(&format!("<h2>{}</h2><ul>", encode_text(&search.name)));
+            for c in concerts {
+                html.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a><br/>{} | {}<br/>{}</li>",
+                    encode_double_quoted_attribute(&c.concert.url),
+                    encode_text(&c.concert.title),
+                    encode_text(&describe_datetime(c)),
+                    encode_text(&c.concert.venue),
+                    encode_text(&core::price_string(&c.concert))
+                ));
+            }
+            html.push_str("</ul>");
+        }
+        html.push_str("</body></html>");
+        html
+    }
+
+    /// Send the digest by email
+    pub async fn send(&self, smtp: &SmtpConfig) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let to = Mailbox::new(
+            Some(self.subscriber.name.clone()),
+            self.subscriber.email.parse()?,
+        );
+        let email = Message::builder()
+            .from(smtp.from.parse()?)
+            .to(to)
+            .subject(self.subject())
+            .multipart(MultiPart::alternative_plain_html(
+                self.to_text(),
+                self.to_html(),
+            ))?;
+
+        let mut builder = match smtp.security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&smtp.host)
+            }
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host)?,
+        };
+        if let Some(port) = smtp.port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        builder.build().send(email).await?;
+        Ok(())
+    }
+}
+
+/// Generate digests of new concerts for all subscribers, write them to `digests_dir` (as
+/// `<email>.txt` and `<email>.html`), and send them if SMTP is configured. Failures to send are
+/// logged, but don't stop the other digests from being sent.
+pub async fn process(config: &AlertsConfig, new_concerts: &[&Concert], digests_dir: &Path) {
+    let digests = digests(config, new_concerts);
+    info!("Generated {} alert digests", digests.len());
+    create_dir_all(digests_dir).unwrap();
+    for digest in &digests {
+        let slug = core::slugify(&digest.subscriber.email);
+        write(digests_dir.join(format!("{}.txt", slug)), digest.to_text()).unwrap();
+        write(digests_dir.join(format!("{}.html", slug)), digest.to_html()).unwrap();
+
+        if let Some(smtp) = &config.smtp {
+            match digest.send(smtp).await {
+                Ok(()) => info!("Sent alert digest to {}", digest.subscriber.email),
+                Err(e) => warn!(
+                    "Failed to send alert digest to {}: {}",
+                    digest.subscriber.email, e
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{add_id_to_concert, ConcertData, Piece};
+    use chrono::{TimeZone, Utc};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    fn concert(title: &str, composer: &str, min_price: u32) -> Concert {
+        add_id_to_concert(ConcertData {
+            datetime: Utc.with_ymd_and_hms(2026, 5, 1, 18, 30, 0).unwrap(),
+            url: format!("https://example.com/{}", core::slugify(title)),
+            title: title.to_string(),
+            venue: "Wigmore Hall".to_string(),
+            min_price: Some(min_price),
+            pieces: vec![Piece {
+                composer: composer.to_string(),
+                title: "Sonata".to_string(),
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn search(name: &str, composer: &str, max_price: Option<u32>) -> SavedSearch {
+        SavedSearch {
+            name: name.to_string(),
+            composer: Some(composer.to_string()),
+            performer: None,
+            venue: None,
+            category: None,
+            max_price,
+            availability: None,
+            wigmore_u35: false,
+        }
+    }
+
+    fn config(smtp: Option<SmtpConfig>) -> AlertsConfig {
+        AlertsConfig {
+            smtp,
+            subscribers: vec![
+                Subscriber {
+                    name: "Alex".to_string(),
+                    email: "alex@example.com".to_string(),
+                    searches: vec![
+                        search("Cheap Schubert", "schubert", Some(2000)),
+                        search("Any Schubert", "schubert", None),
+                        search("Ligeti", "ligeti", None),
+                    ],
+                },
+                Subscriber {
+                    name: "Sam".to_string(),
+                    email: "sam@example.com".to_string(),
+                    searches: vec![search("Bach", "bach", None)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn digests_only_include_matches() {
+        let concerts = [
+            concert("Winterreise", "Franz Schubert", 1500),
+            concert("Lieder", "Schubert", 4000),
+        ];
+        let new_concerts: Vec<&Concert> = concerts.iter().collect();
+        let config = config(None);
+        let digests = digests(&config, &new_concerts);
+
+        // Sam's search has no matches, so there is no digest for them
+        assert_eq!(digests.len(), 1);
+        let digest = &digests[0];
+        assert_eq!(digest.subscriber.name, "Alex");
+        let matches: Vec<(&str, usize)> = digest
+            .matches
+            .iter()
+            .map(|(search, concerts)| (search.name.as_str(), concerts.len()))
+            .collect();
+        assert_eq!(matches, vec![("Cheap Schubert", 1), ("Any Schubert", 2)]);
+        // Concerts matching several searches only count once
+        assert_eq!(digest.n_concerts(), 2);
+        assert_eq!(
+            digest.subject(),
+            "2 new concerts matching your saved searches"
+        );
+        assert!(digest
+            .to_text()
+            .contains("\nWinterreise\n  Fri 1 May 2026, 19:30 | Wigmore Hall\n"));
+    }
+
+    /// A message received by `start_smtp_server`
+    #[derive(Debug, Default)]
+    struct ReceivedMail {
+        recipients: Vec<String>,
+        data: String,
+    }
+
+    /// Start a minimal SMTP server (without TLS or authentication) on a free port, which accepts
+    /// every message and records it
+    async fn start_smtp_server() -> (u16, Arc<Mutex<Vec<ReceivedMail>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = Arc::new(Mutex::new(vec![]));
+        let received_ = received.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let received = received_.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    let mut mail = ReceivedMail::default();
+                    writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+                    while let Some(line) = lines.next_line().await.unwrap() {
+                        let command = line.to_ascii_uppercase();
+                        let reply: &[u8] = if command.starts_with("EHLO") {
+                            b"250 localhost\r\n"
+                        } else if command.starts_with("RCPT TO:") {
+                            mail.recipients.push(line[8..].to_string());
+                            b"250 OK\r\n"
+                        } else if command == "DATA" {
+                            writer.write_all(b"354 Go ahead\r\n").await.unwrap();
+                            while let Some(line) = lines.next_line().await.unwrap() {
+                                if line == "." {
+                                    break;
+                                }
+                                mail.data.push_str(&line);
+                                mail.data.push('\n');
+                            }
+                            received.lock().unwrap().push(std::mem::take(&mut mail));
+                            b"250 Queued\r\n"
+                        } else if command == "QUIT" {
+                            writer.write_all(b"221 Bye\r\n").await.unwrap();
+                            break;
+                        } else {
+                            b"250 OK\r\n"
+                        };
+                        writer.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+        (port, received)
+    }
+
+    #[tokio::test]
+    async fn digests_are_sent() {
+        let (port, received) = start_smtp_server().await;
+        let smtp = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            from: "Alerts <alerts@example.com>".to_string(),
+            username: None,
+            password: None,
+            security: SmtpSecurity::None,
+        };
+        let concerts = [
+            concert("Winterreise", "Schubert", 1500),
+            concert("Goldberg Variations", "Bach", 1500),
+        ];
+        let new_concerts: Vec<&Concert> = concerts.iter().collect();
+        let dir = std::env::temp_dir().join(format!("ldncls-sent-digests-{}", std::process::id()));
+        process(&config(Some(smtp)), &new_concerts, &dir).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        for (mail, email, title) in [
+            (&received[0], "alex@example.com", "Winterreise"),
+            (&received[1], "sam@example.com", "Goldberg Variations"),
+        ] {
+            assert_eq!(mail.recipients, vec![format!("<{}>", email)]);
+            assert!(mail
+                .data
+                .contains("Subject: 1 new concert matching your saved searches"));
+            assert!(mail.data.contains("Content-Type: multipart/alternative"));
+            assert!(mail.data.contains("Content-Type: text/plain"));
+            assert!(mail.data.contains("Content-Type: text/html"));
+            assert!(mail.data.contains(title), "{} not in {}", title, mail.data);
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_to_send_does_not_stop_other_digests() {
+        // Nothing is listening on this port once the listener is dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let smtp = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            from: "Alerts <alerts@example.com>".to_string(),
+            username: None,
+            password: None,
+            security: SmtpSecurity::None,
+        };
+        let concerts = [
+            concert("Winterreise", "Schubert", 1500),
+            concert("Goldberg Variations", "Bach", 1500),
+        ];
+        let new_concerts: Vec<&Concert> = concerts.iter().collect();
+        let config = config(Some(smtp.clone()));
+
+        let digests = digests(&config, &new_concerts);
+        assert!(digests[0].send(&smtp).await.is_err());
+
+        let dir = std::env::temp_dir().join(format!("ldncls-digests-{}", std::process::id()));
+        process(&config, &new_concerts, &dir).await;
+        for name in [
+            "alex-example-com.txt",
+            "alex-example-com.html",
+            "sam-example-com.txt",
+            "sam-example-com.html",
+        ] {
+            assert!(dir.join(name).exists(), "{} was not written", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,751 @@
+use crate::core::{BroadcastMedium, Concert};
+use chrono::{DateTime, Utc};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Historical archive of all concerts that have ever been scraped, stored in a SQLite database.
+///
+/// Unlike `concerts.json`, which only ever contains the latest run, the archive keeps every
+/// concert it has seen, together with the run in which it was first seen, the run in which it was
+/// last seen, and (if applicable) the run in which it disappeared from the listings. Every change
+/// in price or time is also recorded.
+pub struct Archive {
+    conn: Connection,
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY,
+    scraped_at TEXT NOT NULL,
+    n_concerts INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS venues (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS concerts (
+    id TEXT PRIMARY KEY,
+    venue_id INTEGER NOT NULL REFERENCES venues(id),
+    datetime TEXT NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT NOT NULL,
+    subtitle TEXT,
+    description TEXT,
+    programme_pdf_url TEXT,
+    category TEXT NOT NULL,
+    availability TEXT NOT NULL,
+    is_wigmore_u35 INTEGER NOT NULL,
+    livestream_url TEXT,
+    on_demand_from TEXT,
+    on_demand_until TEXT,
+    production_id INTEGER REFERENCES productions(id),
+    conductor TEXT,
+    first_seen_snapshot INTEGER NOT NULL REFERENCES snapshots(id),
+    last_seen_snapshot INTEGER NOT NULL REFERENCES snapshots(id),
+    disappeared_snapshot INTEGER REFERENCES snapshots(id)
+);
+
+CREATE TABLE IF NOT EXISTS snapshot_concerts (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    PRIMARY KEY (snapshot_id, concert_id)
+);
+
+CREATE TABLE IF NOT EXISTS pieces (
+    id INTEGER PRIMARY KEY,
+    composer TEXT NOT NULL,
+    title TEXT NOT NULL,
+    UNIQUE (composer, title)
+);
+
+CREATE TABLE IF NOT EXISTS concert_pieces (
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    position INTEGER NOT NULL,
+    piece_id INTEGER NOT NULL REFERENCES pieces(id),
+    PRIMARY KEY (concert_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS performers (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS concert_performers (
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    position INTEGER NOT NULL,
+    performer_id INTEGER NOT NULL REFERENCES performers(id),
+    instrument TEXT,
+    PRIMARY KEY (concert_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS broadcasts (
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    position INTEGER NOT NULL,
+    channel TEXT NOT NULL,
+    medium TEXT NOT NULL,
+    is_live INTEGER NOT NULL,
+    PRIMARY KEY (concert_id, position)
+);
+
+-- Everything about a production except the conductor and cast, which are per performance
+CREATE TABLE IF NOT EXISTS productions (
+    id INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    composer TEXT NOT NULL,
+    director TEXT,
+    running_time_minutes INTEGER,
+    n_intervals INTEGER,
+    language TEXT,
+    surtitles TEXT
+);
+
+-- Singers are in the performers table too
+CREATE TABLE IF NOT EXISTS concert_cast (
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    position INTEGER NOT NULL,
+    performer_id INTEGER NOT NULL REFERENCES performers(id),
+    role TEXT NOT NULL,
+    PRIMARY KEY (concert_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS series (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    organiser TEXT NOT NULL,
+    start_date TEXT,
+    end_date TEXT,
+    url TEXT,
+    UNIQUE (name, organiser)
+);
+
+CREATE TABLE IF NOT EXISTS concert_series (
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    series_id INTEGER NOT NULL REFERENCES series(id),
+    PRIMARY KEY (concert_id, series_id)
+);
+
+-- One row per price change, i.e. a new row is only added when the price differs from the most
+-- recently recorded one
+CREATE TABLE IF NOT EXISTS prices (
+    id INTEGER PRIMARY KEY,
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+    min_price INTEGER,
+    max_price INTEGER
+);
+
+-- One row per change of a concert's time. The concert's ID contains its time, so it changes too;
+-- `old_id` is the ID it had before.
+CREATE TABLE IF NOT EXISTS reschedules (
+    id INTEGER PRIMARY KEY,
+    concert_id TEXT NOT NULL REFERENCES concerts(id) ON UPDATE CASCADE,
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+    old_id TEXT NOT NULL,
+    old_datetime TEXT NOT NULL
+);
+";
+
+impl Archive {
+    /// Open (or create) the archive database at the given path
+    pub fn open(path: &Path) -> rusqlite::Result<Archive> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Archive { conn })
+    }
+
+    /// Record the results of a scraping run. Returns the ID of the newly created snapshot.
+    ///
+    /// A concert which isn't in the archive, but has the same URL, venue, and title as an upcoming
+    /// concert which is no longer listed, is taken to have been rescheduled (in the same way as in
+    /// `diff::diff`): the archived concert gets the new ID, and the change is recorded in
+    /// `reschedules`.
+    ///
+    /// Concerts which were in the archive but are not in `concerts` are marked as having
+    /// disappeared, but only if their venue was present in this run: otherwise, disabling a
+    /// scraper for a single run would make all its concerts look like they had been removed.
+    /// Concerts which have already happened aren't listed any more either, so they aren't marked
+    /// as having disappeared.
+    pub fn record_snapshot(
+        &mut self,
+        concerts: &[Concert],
+        scraped_at: DateTime<Utc>,
+    ) -> rusqlite::Result<i64> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO snapshots (scraped_at, n_concerts) VALUES (?1, ?2)",
+            params![scraped_at, concerts.len()],
+        )?;
+        let snapshot_id = tx.last_insert_rowid();
+
+        let current_ids: HashSet<&str> = concerts.iter().map(|c| c.id.as_str()).collect();
+        let mut n_new = 0;
+        let mut n_rescheduled = 0;
+        for concert in concerts {
+            if let Some(old_id) = find_rescheduled(&tx, concert, &current_ids, scraped_at)? {
+                reschedule(&tx, &old_id, &concert.id, snapshot_id)?;
+                n_rescheduled += 1;
+            }
+            if upsert_concert(&tx, concert, snapshot_id)? {
+                n_new += 1;
+            }
+        }
+
+        let venues: HashSet<&str> = concerts.iter().map(|c| c.concert.venue.as_str()).collect();
+        let mut n_disappeared = 0;
+        for venue in venues {
+            n_disappeared += tx.execute(
+                "UPDATE concerts SET disappeared_snapshot = ?1
+                 WHERE disappeared_snapshot IS NULL
+                   AND last_seen_snapshot < ?1
+                   AND datetime >= ?3
+                   AND venue_id = (SELECT id FROM venues WHERE name = ?2)",
+                params![snapshot_id, venue, scraped_at],
+            )?;
+        }
+
+        tx.commit()?;
+        info!(
+            "Archived snapshot {}: {} concerts ({} new, {} rescheduled, {} disappeared)",
+            snapshot_id,
+            concerts.len(),
+            n_new,
+            n_rescheduled,
+            n_disappeared
+        );
+        Ok(snapshot_id)
+    }
+}
+
+/// If `concert` isn't in the archive, find the archived concert which it is a rescheduling of, if
+/// any: an upcoming one which hasn't disappeared and isn't in `current_ids`, with the same URL,
+/// venue, and title. If there are several, the earliest is used.
+fn find_rescheduled(
+    tx: &Transaction,
+    concert: &Concert,
+    current_ids: &HashSet<&str>,
+    scraped_at: DateTime<Utc>,
+) -> rusqlite::Result<Option<String>> {
+    let is_archived: bool = tx.query_row(
+        "SELECT COUNT(*) > 0 FROM concerts WHERE id = ?1",
+        params![concert.id],
+        |row| row.get(0),
+    )?;
+    if is_archived {
+        return Ok(None);
+    }
+    let c = &concert.concert;
+    let mut stmt = tx.prepare(
+        "SELECT id FROM concerts
+         WHERE url = ?1 AND title = ?2
+           AND venue_id = (SELECT id FROM venues WHERE name = ?3)
+           AND disappeared_snapshot IS NULL
+           AND datetime >= ?4
+         ORDER BY datetime",
+    )?;
+    let candidates = stmt
+        .query_map(params![c.url, c.title, c.venue, scraped_at], |row| {
+            row.get::<_, String>(0)
+        })?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(candidates
+        .into_iter()
+        .find(|id| !current_ids.contains(id.as_str())))
+}
+
+/// Move an archived concert over to its new ID (which everything referring to it follows), and
+/// record the change
+fn reschedule(
+    tx: &Transaction,
+    old_id: &str,
+    new_id: &str,
+    snapshot_id: i64,
+) -> rusqlite::Result<()> {
+    let old_datetime: DateTime<Utc> = tx.query_row(
+        "SELECT datetime FROM concerts WHERE id = ?1",
+        params![old_id],
+        |row| row.get(0),
+    )?;
+    tx.execute(
+        "UPDATE concerts SET id = ?2 WHERE id = ?1",
+        params![old_id, new_id],
+    )?;
+    tx.execute(
+        "INSERT INTO reschedules (concert_id, snapshot_id, old_id, old_datetime)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![new_id, snapshot_id, old_id, old_datetime],
+    )?;
+    Ok(())
+}
+
+/// Insert or update a single concert in the archive. Returns true if the concert was not
+/// previously in the archive.
+fn upsert_concert(tx: &Transaction, concert: &Concert, snapshot_id: i64) -> rusqlite::Result<bool> {
+    let c = &concert.concert;
+    let venue_id = get_or_insert_id(
+        tx,
+        "SELECT id FROM venues WHERE name = ?1",
+        "INSERT INTO venues (name) VALUES (?1)",
+        &[&c.venue],
+    )?;
+
+    let production_id = match &c.production {
+        Some(production) => {
+            let production_id = get_or_insert_id(
+                tx,
+                "SELECT id FROM productions
+                 WHERE title = ?1 AND composer = ?2 AND director IS ?3",
+                "INSERT INTO productions (title, composer, director) VALUES (?1, ?2, ?3)",
+                &[
+                    &production.title,
+                    &production.composer,
+                    &production.director,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE productions SET running_time_minutes = ?2, n_intervals = ?3,
+                     language = ?4, surtitles = ?5
+                 WHERE id = ?1",
+                params![
+                    production_id,
+                    production.running_time_minutes,
+                    production.n_intervals,
+                    production.language,
+                    production.surtitles,
+                ],
+            )?;
+            Some(production_id)
+        }
+        None => None,
+    };
+
+    let existing: Option<i64> = tx
+        .query_row(
+            "SELECT first_seen_snapshot FROM concerts WHERE id = ?1",
+            params![concert.id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let is_new = existing.is_none();
+
+    // A concert that reappears after having disappeared is no longer considered to have
+    // disappeared
+    tx.execute(
+        "INSERT INTO concerts (id, venue_id, datetime, url, title, subtitle, description,
+             programme_pdf_url, category, availability, is_wigmore_u35, livestream_url,
+             on_demand_from, on_demand_until, production_id, conductor, first_seen_snapshot,
+             last_seen_snapshot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?17)
+         ON CONFLICT (id) DO UPDATE SET venue_id = excluded.venue_id,
+             datetime = excluded.datetime, url = excluded.url, title = excluded.title,
+             subtitle = excluded.subtitle, description = excluded.description,
+             programme_pdf_url = excluded.programme_pdf_url, category = excluded.category,
+             availability = excluded.availability, is_wigmore_u35 = excluded.is_wigmore_u35,
+             livestream_url = excluded.livestream_url, on_demand_from = excluded.on_demand_from,
+             on_demand_until = excluded.on_demand_until, production_id = excluded.production_id,
+             conductor = excluded.conductor, last_seen_snapshot = excluded.last_seen_snapshot,
+             disappeared_snapshot = NULL",
+        params![
+            concert.id,
+            venue_id,
+            c.datetime,
+            c.url,
+            c.title,
+            c.subtitle,
+            c.description,
+            c.programme_pdf_url,
+            c.category.name(),
+            c.availability.name(),
+            c.is_wigmore_u35,
+            c.media.livestream_url,
+            c.media.on_demand.as_ref().map(|on_demand| on_demand.from),
+            c.media
+                .on_demand
+                .as_ref()
+                .and_then(|on_demand| on_demand.until),
+            production_id,
+            c.production.as_ref().and_then(|p| p.conductor.as_ref()),
+            snapshot_id,
+        ],
+    )?;
+    tx.execute(
+        "INSERT INTO snapshot_concerts (snapshot_id, concert_id) VALUES (?1, ?2)",
+        params![snapshot_id, concert.id],
+    )?;
+
+    // Repertoire, performers, cast, broadcasts, and series are replaced wholesale
+    tx.execute(
+        "DELETE FROM concert_pieces WHERE concert_id = ?1",
+        params![concert.id],
+    )?;
+    for (position, piece) in c.pieces.iter().enumerate() {
+        let piece_id = get_or_insert_id(
+            tx,
+            "SELECT id FROM pieces WHERE composer = ?1 AND title = ?2",
+            "INSERT INTO pieces (composer, title) VALUES (?1, ?2)",
+            &[&piece.composer, &piece.title],
+        )?;
+        tx.execute(
+            "INSERT INTO concert_pieces (concert_id, position, piece_id) VALUES (?1, ?2, ?3)",
+            params![concert.id, position, piece_id],
+        )?;
+    }
+    tx.execute(
+        "DELETE FROM concert_performers WHERE concert_id = ?1",
+        params![concert.id],
+    )?;
+    for (position, performer) in c.performers.iter().enumerate() {
+        let performer_id = get_or_insert_id(
+            tx,
+            "SELECT id FROM performers WHERE name = ?1",
+            "INSERT INTO performers (name) VALUES (?1)",
+            &[&performer.name],
+        )?;
+        tx.execute(
+            "INSERT INTO concert_performers (concert_id, position, performer_id, instrument)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![concert.id, position, performer_id, performer.instrument],
+        )?;
+    }
+
+    tx.execute(
+        "DELETE FROM concert_cast WHERE concert_id = ?1",
+        params![concert.id],
+    )?;
+    let cast = c.production.iter().flat_map(|p| &p.cast);
+    for (position, member) in cast.enumerate() {
+        let performer_id = get_or_insert_id(
+            tx,
+            "SELECT id FROM performers WHERE name = ?1",
+            "INSERT INTO performers (name) VALUES (?1)",
+            &[&member.name],
+        )?;
+        tx.execute(
+            "INSERT INTO concert_cast (concert_id, position, performer_id, role)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![concert.id, position, performer_id, member.role],
+        )?;
+    }
+
+    tx.execute(
+        "DELETE FROM broadcasts WHERE concert_id = ?1",
+        params![concert.id],
+    )?;
+    for (position, broadcast) in c.media.broadcasts.iter().enumerate() {
+        let medium = match broadcast.medium {
+            BroadcastMedium::Radio => "radio",
+            BroadcastMedium::Tv => "tv",
+        };
+        tx.execute(
+            "INSERT INTO broadcasts (concert_id, position, channel, medium, is_live)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                concert.id,
+                position,
+                broadcast.channel,
+                medium,
+                broadcast.is_live
+            ],
+        )?;
+    }
+
+    tx.execute(
+        "DELETE FROM concert_series WHERE concert_id = ?1",
+        params![concert.id],
+    )?;
+    for series in &c.series {
+        let series_id = get_or_insert_id(
+            tx,
+            "SELECT id FROM series WHERE name = ?1 AND organiser = ?2",
+            "INSERT INTO series (name, organiser) VALUES (?1, ?2)",
+            &[&series.name, &series.organiser],
+        )?;
+        tx.execute(
+            "UPDATE series SET start_date = ?2, end_date = ?3, url = ?4 WHERE id = ?1",
+            params![series_id, series.start_date, series.end_date, series.url],
+        )?;
+        tx.execute(
+            "INSERT INTO concert_series (concert_id, series_id) VALUES (?1, ?2)",
+            params![concert.id, series_id],
+        )?;
+    }
+
+    // Only record the price if it changed
+    let last_price: Option<(Option<u32>, Option<u32>)> = tx
+        .query_row(
+            "SELECT min_price, max_price FROM prices WHERE concert_id = ?1
+             ORDER BY snapshot_id DESC LIMIT 1",
+            params![concert.id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    if last_price != Some((c.min_price, c.max_price)) {
+        tx.execute(
+            "INSERT INTO prices (concert_id, snapshot_id, min_price, max_price)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![concert.id, snapshot_id, c.min_price, c.max_price],
+        )?;
+    }
+
+    Ok(is_new)
+}
+
+/// Look up the ID of a row using `select_sql`, inserting it with `insert_sql` if it doesn't exist
+fn get_or_insert_id(
+    tx: &Transaction,
+    select_sql: &str,
+    insert_sql: &str,
+    values: &[&dyn rusqlite::ToSql],
+) -> rusqlite::Result<i64> {
+    let existing: Option<i64> = tx
+        .query_row(select_sql, values, |row| row.get(0))
+        .optional()?;
+    match existing {
+        Some(id) => Ok(id),
+        None => {
+            tx.execute(insert_sql, values)?;
+            Ok(tx.last_insert_rowid())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        Availability, Broadcast, CastMember, Category, ConcertData, Media, Production, Series,
+    };
+    use chrono::{NaiveDate, TimeZone};
+
+    fn concert(id: &str, day: u32, min_price: Option<u32>) -> Concert {
+        Concert {
+            id: id.to_string(),
+            concert: ConcertData {
+                datetime: Utc.with_ymd_and_hms(2026, 3, day, 19, 30, 0).unwrap(),
+                url: format!("https://example.com/{}", id),
+                title: format!("Concert {}", id),
+                venue: "Wigmore Hall".to_string(),
+                min_price,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 6, 0, 0).unwrap()
+    }
+
+    /// (first seen, last seen, disappeared) snapshots of a concert
+    fn snapshots(archive: &Archive, id: &str) -> (i64, i64, Option<i64>) {
+        archive
+            .conn
+            .query_row(
+                "SELECT first_seen_snapshot, last_seen_snapshot, disappeared_snapshot
+                 FROM concerts WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+    }
+
+    fn n_prices(archive: &Archive, id: &str) -> i64 {
+        archive
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM prices WHERE concert_id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn concerts_are_tracked_across_snapshots() {
+        let mut archive = Archive::open(Path::new(":memory:")).unwrap();
+        let s1 = archive
+            .record_snapshot(
+                &[concert("a", 10, Some(1500)), concert("b", 11, None)],
+                at(1),
+            )
+            .unwrap();
+        let s2 = archive
+            .record_snapshot(&[concert("a", 10, Some(2000))], at(2))
+            .unwrap();
+        assert_eq!(snapshots(&archive, "a"), (s1, s2, None));
+        assert_eq!(snapshots(&archive, "b"), (s1, s1, Some(s2)));
+        assert_eq!(n_prices(&archive, "a"), 2);
+
+        // It's back (e.g. it was taken down by mistake)
+        let s3 = archive
+            .record_snapshot(
+                &[concert("a", 10, Some(2000)), concert("b", 11, None)],
+                at(3),
+            )
+            .unwrap();
+        assert_eq!(snapshots(&archive, "b"), (s1, s3, None));
+        assert_eq!(n_prices(&archive, "a"), 2);
+    }
+
+    #[test]
+    fn past_concerts_have_not_disappeared() {
+        let mut archive = Archive::open(Path::new(":memory:")).unwrap();
+        let s1 = archive
+            .record_snapshot(&[concert("a", 10, None), concert("b", 20, None)], at(9))
+            .unwrap();
+        // "a" has happened by the time of the next run, so it isn't listed any more
+        archive
+            .record_snapshot(&[concert("b", 20, None)], at(11))
+            .unwrap();
+        assert_eq!(snapshots(&archive, "a"), (s1, s1, None));
+    }
+
+    #[test]
+    fn series_are_shared_between_concerts() {
+        let mut archive = Archive::open(Path::new(":memory:")).unwrap();
+        let series = |end_day: u32| Series {
+            name: "BBC Proms 2026".to_string(),
+            organiser: "BBC".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2026, 7, 17),
+            end_date: NaiveDate::from_ymd_opt(2026, 9, end_day),
+            url: None,
+        };
+        let mut a = concert("a", 10, None);
+        a.concert.series = vec![series(12)];
+        let mut b = concert("b", 11, None);
+        b.concert.series = vec![series(13)];
+        archive.record_snapshot(&[a, b], at(1)).unwrap();
+
+        let (n_series, end_date): (i64, String) = archive
+            .conn
+            .query_row("SELECT COUNT(*), MAX(end_date) FROM series", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((n_series, end_date.as_str()), (1, "2026-09-13"));
+        let n_links: i64 = archive
+            .conn
+            .query_row("SELECT COUNT(*) FROM concert_series", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(n_links, 2);
+    }
+
+    fn count(archive: &Archive, sql: &str) -> i64 {
+        archive.conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn rescheduled_concerts_keep_their_history() {
+        let mut archive = Archive::open(Path::new(":memory:")).unwrap();
+        let s1 = archive
+            .record_snapshot(
+                &[concert("a", 10, Some(1500)), concert("b", 11, None)],
+                at(1),
+            )
+            .unwrap();
+        let mut moved = concert("a", 12, Some(2000));
+        moved.id = "a-moved".to_string();
+        let s2 = archive
+            .record_snapshot(&[moved, concert("b", 11, None)], at(2))
+            .unwrap();
+
+        assert_eq!(snapshots(&archive, "a-moved"), (s1, s2, None));
+        assert_eq!(count(&archive, "SELECT COUNT(*) FROM concerts"), 2);
+        assert_eq!(n_prices(&archive, "a-moved"), 2);
+        assert_eq!(
+            count(
+                &archive,
+                "SELECT COUNT(*) FROM snapshot_concerts WHERE concert_id = 'a-moved'"
+            ),
+            2
+        );
+        let (old_id, snapshot_id): (String, i64) = archive
+            .conn
+            .query_row(
+                "SELECT old_id, snapshot_id FROM reschedules WHERE concert_id = 'a-moved'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((old_id.as_str(), snapshot_id), ("a", s2));
+    }
+
+    #[test]
+    fn productions_and_media_are_recorded() {
+        let mut archive = Archive::open(Path::new(":memory:")).unwrap();
+        let performance = |id: &str, day: u32, singer: &str| {
+            let mut c = concert(id, day, Some(5000));
+            c.concert.category = Category::Opera;
+            c.concert.availability = Availability::Ticketed;
+            c.concert.production = Some(Production {
+                title: "La bohème".to_string(),
+                composer: "Giacomo Puccini".to_string(),
+                conductor: Some("Antonio Pappano".to_string()),
+                director: Some("Richard Jones".to_string()),
+                cast: vec![CastMember {
+                    role: "Mimì".to_string(),
+                    name: singer.to_string(),
+                }],
+                running_time_minutes: Some(150),
+                n_intervals: Some(1),
+                language: Some("Italian".to_string()),
+                surtitles: Some("English".to_string()),
+            });
+            c
+        };
+        let mut a = performance("a", 10, "Anna Netrebko");
+        a.concert.media = Media {
+            livestream_url: None,
+            on_demand: None,
+            broadcasts: vec![Broadcast {
+                channel: "BBC Radio 3".to_string(),
+                medium: BroadcastMedium::Radio,
+                is_live: true,
+            }],
+        };
+        let b = performance("b", 12, "Angela Gheorghiu");
+        archive.record_snapshot(&[a, b], at(1)).unwrap();
+
+        assert_eq!(count(&archive, "SELECT COUNT(*) FROM productions"), 1);
+        assert_eq!(
+            count(
+                &archive,
+                "SELECT COUNT(DISTINCT production_id) FROM concerts"
+            ),
+            1
+        );
+        let (category, conductor): (String, String) = archive
+            .conn
+            .query_row(
+                "SELECT category, conductor FROM concerts WHERE id = 'a'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (category.as_str(), conductor.as_str()),
+            ("opera", "Antonio Pappano")
+        );
+        let singer: String = archive
+            .conn
+            .query_row(
+                "SELECT performers.name FROM concert_cast
+                 JOIN performers ON performers.id = concert_cast.performer_id
+                 WHERE concert_id = 'b' AND role = 'Mimì'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(singer, "Angela Gheorghiu");
+        assert_eq!(
+            count(
+                &archive,
+                "SELECT COUNT(*) FROM broadcasts WHERE concert_id = 'a' AND medium = 'radio'"
+            ),
+            1
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::classify;
+use crate::core;
+use crate::media;
+use crate::schema_org;
+use itertools::Itertools;
+use log::warn;
+use regex::Regex;
+use scraper::{Html, Selector};
+
+pub mod smith_square;
+pub mod southwark_cathedral;
+pub mod st_pauls;
+pub mod westminster_abbey;
+
+/// Where to find each event's details on a church's calendar page. Churches don't usually have a
+/// page for each recital, so everything is taken from the calendar itself: each event is one
+/// `item`, and the other selectors are matched inside it.
+#[derive(Debug, Clone)]
+pub struct CalendarPage {
+    pub item: &'static str,
+    pub title: &'static str,
+    /// Element with a `datetime` attribute, e.g. `<time datetime="2026-02-02T13:10">`
+    pub datetime: &'static str,
+    /// Link to more details, if there is one (otherwise the calendar page itself is used)
+    pub link: &'static str,
+    pub description: &'static str,
+    /// Text about getting in, e.g. "Free, retiring collection" or "£10"
+    pub admission: &'static str,
+    /// Churches which list their concerts in several buildings say which one each event is in
+    pub venue: Option<&'static str>,
+    /// Labels the church puts on the event, e.g. "Worship" or "Music"
+    pub tags: Option<&'static str>,
+}
+
+/// Parse (and finish, see `finish`) every event on a calendar page. `page_url` is the URL of the
+/// page, which relative links are resolved against, and `default_venue` is the church itself.
+/// Entries without a title or datetime are skipped.
+pub fn parse_calendar(
+    html: &str,
+    page_url: &str,
+    page: &CalendarPage,
+    default_venue: &str,
+) -> Vec<core::ConcertData> {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let slc_item = Selector::parse(page.item).unwrap();
+    let slc_title = Selector::parse(page.title).unwrap();
+    let slc_datetime = Selector::parse(page.datetime).unwrap();
+    let slc_link = Selector::parse(page.link).unwrap();
+    let slc_description = Selector::parse(page.description).unwrap();
+    let slc_admission = Selector::parse(page.admission).unwrap();
+    let slc_venue = page.venue.map(|venue| Selector::parse(venue).unwrap());
+    let slc_tags = page.tags.map(|tags| Selector::parse(tags).unwrap());
+
+    doc.select(&slc_item)
+        .filter_map(|item| {
+            let Some(title) = item.select(&slc_title).next().map(core::element_text) else {
+                warn!("Skipping calendar entry without a title on {}", page_url);
+                return None;
+            };
+            let Some(datetime_str) = item
+                .select(&slc_datetime)
+                .next()
+                .and_then(|elem| elem.value().attr("datetime"))
+            else {
+                warn!("Skipping {} on {}, which has no datetime", title, page_url);
+                return None;
+            };
+            let datetime = schema_org::parse_datetime(datetime_str)
+                .inspect_err(|e| warn!("Skipping {} on {}: {}", title, page_url, e))
+                .ok()?;
+            let url = item
+                .select(&slc_link)
+                .next()
+                .and_then(|elem| elem.value().attr("href"))
+                .and_then(|href| base.join(href).ok())
+                .map_or(page_url.to_string(), |url| url.to_string());
+            let description = item
+                .select(&slc_description)
+                .map(core::element_text)
+                .join("\n");
+            let admission = item
+                .select(&slc_admission)
+                .next()
+                .map(core::element_text)
+                .unwrap_or_default();
+            let prices = core::parse_prices(&admission);
+            let min_price = prices.iter().min().copied();
+            let max_price = prices.iter().max().copied();
+            let venue = match slc_venue
+                .as_ref()
+                .and_then(|slc| item.select(slc).next())
+                .map(core::element_text)
+            {
+                Some(building) if !building.is_empty() && building != default_venue => {
+                    format!("{} ({})", default_venue, building)
+                }
+                _ => default_venue.to_string(),
+            };
+            let tags: Vec<String> = slc_tags
+                .as_ref()
+                .map(|slc| item.select(slc).map(core::element_text).collect())
+                .unwrap_or_default();
+
+            let concert = core::ConcertData {
+                datetime,
+                url: url.clone(),
+                performers: vec![],
+                title,
+                subtitle: None,
+                description: Some(description.clone()),
+                programme_pdf_url: None,
+                programme_text: None,
+                pieces: vec![],
+                venue,
+                category: core::Category::Other,
+                min_price,
+                max_price,
+                availability: core::parse_availability(&admission, min_price, max_price),
+                media: media::find_media(&description, datetime, &url),
+                is_wigmore_u35: false,
+                series: vec![],
+                production: None,
+            };
+            Some(finish(concert, &tags))
+        })
+        .collect()
+}
+
+/// Finish off an event from a church's listings. Organ recitals are usually titled with just the
+/// organist's name, e.g. "Organ Recital: Katherine Dienes-Williams", so they become the performer
+/// if there isn't one already. Then the event is classified, so that services (which churches list
+/// alongside their concerts) can be told apart from recitals.
+pub fn finish(mut concert: core::ConcertData, tags: &[String]) -> core::ConcertData {
+    let organ_recital_re = Regex::new(r"(?i)^organ recital\s*[:\-–—]\s*(.+)$").unwrap();
+    if concert.performers.is_empty() {
+        if let Some(cap) = organ_recital_re.captures(&concert.title) {
+            concert.performers.push(core::Performer {
+                name: cap[1].trim().to_string(),
+                instrument: Some("organ".to_string()),
+            });
+        }
+    }
+    concert.category = classify::classify(&concert, tags);
+    concert
+}
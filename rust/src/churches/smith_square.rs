@@ -0,0 +1,42 @@
+use crate::churches;
+use crate::core;
+use crate::http;
+use crate::schema_org;
+use chrono::Utc;
+use log::info;
+
+pub const SJSS_URL: &str = "https://www.sjss.org.uk/whats-on";
+
+pub const VENUE: &str = "St John's Smith Square";
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping St John's Smith Square from URL: {}", SJSS_URL);
+    let Some(html) = client.try_get_text(SJSS_URL).await else {
+        return vec![];
+    };
+
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = parse_listing(&html, SJSS_URL)
+        .into_iter()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!(
+        "Scraped {} concerts from St John's Smith Square",
+        concerts.len()
+    );
+    concerts
+}
+
+/// The listing has every event on it as schema.org JSON-LD, so there's no need to fetch each
+/// event's page
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<core::ConcertData> {
+    schema_org::find_events(html)
+        .iter()
+        .filter_map(|event| {
+            let concert = schema_org::parse_event(event, page_url, VENUE)?;
+            Some(churches::finish(concert, &schema_org::tags(event)))
+        })
+        .collect()
+}
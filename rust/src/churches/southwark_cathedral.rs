@@ -0,0 +1,44 @@
+use crate::churches::{self, CalendarPage};
+use crate::core;
+use crate::http;
+use chrono::Utc;
+use log::info;
+
+pub const SOUTHWARK_URL: &str =
+    "https://cathedral.southwark.anglican.org/worship-and-music/music/music-list/";
+
+pub const VENUE: &str = "Southwark Cathedral";
+
+/// The music list has the Monday organ recitals, the Tuesday lunchtime recitals, and the choral
+/// services
+pub const CALENDAR: CalendarPage = CalendarPage {
+    item: "div.music-list__entry",
+    title: "h3.music-list__title",
+    datetime: "time",
+    link: "a.music-list__link",
+    description: "div.music-list__details p",
+    admission: "p.music-list__admission",
+    venue: None,
+    tags: None,
+};
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping Southwark Cathedral from URL: {}", SOUTHWARK_URL);
+    let Some(html) = client.try_get_text(SOUTHWARK_URL).await else {
+        return vec![];
+    };
+
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = parse_listing(&html, SOUTHWARK_URL)
+        .into_iter()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!("Scraped {} events from Southwark Cathedral", concerts.len());
+    concerts
+}
+
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<core::ConcertData> {
+    churches::parse_calendar(html, page_url, &CALENDAR, VENUE)
+}
@@ -0,0 +1,43 @@
+use crate::churches::{self, CalendarPage};
+use crate::core;
+use crate::http;
+use chrono::Utc;
+use log::info;
+
+pub const ST_PAULS_URL: &str = "https://www.stpauls.co.uk/whats-on?type=music";
+
+pub const VENUE: &str = "St Paul's Cathedral";
+
+/// Most events are in the Cathedral itself, but a few are in the Crypt or one of the chapels. Each
+/// event is labelled e.g. "Music" or "Worship".
+pub const CALENDAR: CalendarPage = CalendarPage {
+    item: "article.event",
+    title: "h2.event__title",
+    datetime: "time.event__date",
+    link: "a.event__link",
+    description: "p.event__summary",
+    admission: "span.event__price",
+    venue: Some("span.event__location"),
+    tags: Some("span.event__type"),
+};
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping St Paul's Cathedral from URL: {}", ST_PAULS_URL);
+    let Some(html) = client.try_get_text(ST_PAULS_URL).await else {
+        return vec![];
+    };
+
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = parse_listing(&html, ST_PAULS_URL)
+        .into_iter()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!("Scraped {} events from St Paul's Cathedral", concerts.len());
+    concerts
+}
+
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<core::ConcertData> {
+    churches::parse_calendar(html, page_url, &CALENDAR, VENUE)
+}
@@ -0,0 +1,40 @@
+use crate::churches;
+use crate::core;
+use crate::http;
+use crate::schema_org;
+use chrono::Utc;
+use log::info;
+
+pub const ABBEY_URL: &str = "https://www.westminster-abbey.org/whats-on/music";
+
+pub const VENUE: &str = "Westminster Abbey";
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping Westminster Abbey from URL: {}", ABBEY_URL);
+    let Some(html) = client.try_get_text(ABBEY_URL).await else {
+        return vec![];
+    };
+
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = parse_listing(&html, ABBEY_URL)
+        .into_iter()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!("Scraped {} events from Westminster Abbey", concerts.len());
+    concerts
+}
+
+/// The music listing has the organ recitals and the sung services as schema.org JSON-LD (in a
+/// `@graph` with the Abbey itself). The genre of each event, e.g. "Organ recital" or "Choral
+/// worship", is used to classify it.
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<core::ConcertData> {
+    schema_org::find_events(html)
+        .iter()
+        .filter_map(|event| {
+            let concert = schema_org::parse_event(event, page_url, VENUE)?;
+            Some(churches::finish(concert, &schema_org::tags(event)))
+        })
+        .collect()
+}
@@ -0,0 +1,260 @@
+use crate::core::{Category, ConcertData};
+use deunicode::deunicode;
+
+/// Keywords which identify a category, checked in this order. They are matched against the title,
+/// subtitle, and any tags the source gives the event (e.g. Southbank's artforms or Wigmore Hall's
+/// series), so they need to be fairly specific. Services come first, because e.g. a family
+/// eucharist or a candlelit carol service is still a service.
+const KEYWORDS: [(Category, &[&str]); 8] = [
+    (
+        Category::Service,
+        &[
+            "evensong",
+            "eucharist",
+            "sung mass",
+            "high mass",
+            "mattins",
+            "matins",
+            "compline",
+            "service",
+            "services",
+            "worship",
+        ],
+    ),
+    (
+        Category::Talk,
+        &[
+            "talk",
+            "in conversation",
+            "lecture",
+            "q&a",
+            "study day",
+            "proms plus",
+            "debate",
+        ],
+    ),
+    (
+        Category::Family,
+        &[
+            "family",
+            "families",
+            "for children",
+            "children's",
+            "kids",
+            "under 5s",
+            "baby",
+            "babies",
+            "toddler",
+            "relaxed",
+        ],
+    ),
+    (
+        Category::Light,
+        &[
+            "christmas classics",
+            "film music",
+            "music from the films",
+            "movie",
+            "musical theatre",
+            "candlelight",
+            "singalong",
+            "sing-along",
+        ],
+    ),
+    (
+        Category::Opera,
+        &["opera", "in concert staging", "semi-staged", "zarzuela"],
+    ),
+    (
+        Category::Choral,
+        &[
+            "choral", "carols", "messiah", "requiem", "oratorio", "vespers",
+        ],
+    ),
+    (Category::Chamber, &["chamber music", "string quartet"]),
+    (Category::Recital, &["recital", "song recital", "lieder"]),
+];
+
+/// Words in a performer's name which mean it's an orchestra, choir, or chamber ensemble rather
+/// than an individual
+const ORCHESTRA_WORDS: [&str; 7] = [
+    "orchestra",
+    "philharmonic",
+    "philharmonia",
+    "sinfonia",
+    "sinfonietta",
+    "symphony",
+    "camerata",
+];
+const CHOIR_WORDS: [&str; 6] = [
+    "choir",
+    "chorus",
+    "singers",
+    "voices",
+    "chorale",
+    "choristers",
+];
+const ENSEMBLE_WORDS: [&str; 8] = [
+    "quartet", "quintet", "trio", "sextet", "octet", "ensemble", "consort", "players",
+];
+
+/// Words in a piece title which suggest what kind of forces it's for
+const ORCHESTRAL_PIECE_WORDS: [&str; 3] = ["symphony", "concerto", "overture"];
+const CHORAL_PIECE_WORDS: [&str; 4] = ["mass", "requiem", "oratorio", "magnificat"];
+
+/// Lowercase ASCII version of a string, so that keywords can be matched easily
+fn normalise(s: &str) -> String {
+    deunicode(s).to_lowercase()
+}
+
+/// Whether `text` contains `keyword` as a whole word (or words)
+fn contains_word(text: &str, keyword: &str) -> bool {
+    text.match_indices(keyword).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + keyword.len()..].chars().next();
+        before.is_none_or(|c| !c.is_ascii_alphanumeric())
+            && after.is_none_or(|c| !c.is_ascii_alphanumeric())
+    })
+}
+
+fn contains_any(text: &str, keywords: &[&str]) -> bool {
+    keywords.iter().any(|keyword| contains_word(text, keyword))
+}
+
+/// Work out what kind of event a concert is. `source_tags` are any categories or series that the
+/// source itself puts the event in. These and the title are checked for keywords first; if there
+/// aren't any, the performers and pieces are used instead (e.g. an orchestra means an orchestral
+/// concert, a singer and a pianist a recital).
+pub fn classify(concert: &ConcertData, source_tags: &[String]) -> Category {
+    let mut texts = vec![normalise(&concert.title)];
+    if let Some(subtitle) = &concert.subtitle {
+        texts.push(normalise(subtitle));
+    }
+    texts.extend(source_tags.iter().map(|tag| normalise(tag)));
+    for (category, keywords) in KEYWORDS {
+        if texts.iter().any(|text| contains_any(text, keywords)) {
+            return category;
+        }
+    }
+
+    let performer_names: Vec<String> = concert
+        .performers
+        .iter()
+        .map(|p| normalise(&p.name))
+        .collect();
+    let has_choir = performer_names
+        .iter()
+        .any(|name| contains_any(name, &CHOIR_WORDS));
+    let has_orchestra = performer_names
+        .iter()
+        .any(|name| contains_any(name, &ORCHESTRA_WORDS));
+    let has_ensemble = performer_names
+        .iter()
+        .any(|name| contains_any(name, &ENSEMBLE_WORDS));
+    let piece_titles: Vec<String> = concert.pieces.iter().map(|p| normalise(&p.title)).collect();
+
+    if has_choir {
+        // A choir with an orchestra is still mostly about the choir
+        Category::Choral
+    } else if has_orchestra {
+        Category::Orchestral
+    } else if has_ensemble {
+        Category::Chamber
+    } else {
+        match concert.performers.len() {
+            // Without performers, the only thing left to go on is the repertoire
+            0 if piece_titles
+                .iter()
+                .any(|t| contains_any(t, &CHORAL_PIECE_WORDS)) =>
+            {
+                Category::Choral
+            }
+            0 if piece_titles
+                .iter()
+                .any(|t| contains_any(t, &ORCHESTRAL_PIECE_WORDS)) =>
+            {
+                Category::Orchestral
+            }
+            0 => Category::Other,
+            // e.g. a pianist, or a singer and their accompanist
+            1 | 2 => Category::Recital,
+            _ => Category::Chamber,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Performer, Piece};
+
+    fn concert(title: &str, performers: &[&str], pieces: &[&str]) -> ConcertData {
+        ConcertData {
+            title: title.to_string(),
+            performers: performers
+                .iter()
+                .map(|name| Performer {
+                    name: name.to_string(),
+                    instrument: None,
+                })
+                .collect(),
+            pieces: pieces
+                .iter()
+                .map(|title| Piece {
+                    composer: "".to_string(),
+                    title: title.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn category(title: &str, tags: &[&str]) -> Category {
+        let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+        classify(&concert(title, &[], &[]), &tags)
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(category("Choral Evensong", &[]), Category::Service);
+        // Services come before anything else
+        assert_eq!(category("Family Eucharist", &[]), Category::Service);
+        assert_eq!(
+            category("Proms Plus: Introducing Mahler", &[]),
+            Category::Talk
+        );
+        assert_eq!(category("Carmen", &["Opera"]), Category::Opera);
+        assert_eq!(category("Messiah", &[]), Category::Choral);
+        assert_eq!(category("Schöne Müllerin", &["Lieder"]), Category::Recital);
+        // Keywords have to be whole words
+        assert_eq!(category("Italk", &[]), Category::Other);
+        assert_eq!(category("Talk: Mahler's Symphonies", &[]), Category::Talk);
+    }
+
+    #[test]
+    fn performers_and_pieces() {
+        let classify = |performers: &[&str], pieces: &[&str]| {
+            classify(&concert("An evening with", performers, pieces), &[])
+        };
+        assert_eq!(
+            classify(&["Monteverdi Choir", "English Baroque Soloists"], &[]),
+            Category::Choral
+        );
+        assert_eq!(
+            classify(&["London Symphony Orchestra", "Sir Simon Rattle"], &[]),
+            Category::Orchestral
+        );
+        assert_eq!(classify(&["Takács Quartet"], &[]), Category::Chamber);
+        assert_eq!(
+            classify(&["Ian Bostridge", "Julius Drake"], &[]),
+            Category::Recital
+        );
+        assert_eq!(classify(&["A", "B", "C"], &[]), Category::Chamber);
+        assert_eq!(classify(&[], &["Requiem in D minor"]), Category::Choral);
+        assert_eq!(
+            classify(&[], &["Piano Concerto No. 2"]),
+            Category::Orchestral
+        );
+        assert_eq!(classify(&[], &["Sonata"]), Category::Other);
+    }
+}
@@ -1,26 +1,219 @@
-use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use crate::datetime;
+use crate::output;
+use chrono::{DateTime, NaiveDate, Utc};
 use chrono_tz::Europe::London;
 use deunicode::deunicode;
+use itertools::Itertools;
 use log::info;
 use regex::Regex;
+use schemars::JsonSchema;
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct Piece {
     pub composer: String,
     pub title: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct Performer {
     pub name: String,
     pub instrument: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub enum BroadcastMedium {
+    Radio,
+    Tv,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct Broadcast {
+    /// e.g. "BBC Radio 3"
+    pub channel: String,
+    pub medium: BroadcastMedium,
+    /// Whether the concert is broadcast as it happens (as opposed to being recorded for later)
+    pub is_live: bool,
+}
+
+/// Period in which a recording of the concert can be watched or listened to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct OnDemand {
+    pub from: DateTime<Utc>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Ways of following a concert without being there
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct Media {
+    pub livestream_url: Option<String>,
+    pub on_demand: Option<OnDemand>,
+    pub broadcasts: Vec<Broadcast>,
+}
+
+/// What kind of event a concert is (see `classify::classify`)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    TS,
+    JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Category {
+    Recital,
+    Chamber,
+    Orchestral,
+    Opera,
+    Choral,
+    Family,
+    Talk,
+    /// Film music, Christmas pops, and the like
+    Light,
+    /// Sung services, e.g. choral evensong, which are acts of worship rather than concerts
+    Service,
+    #[default]
+    Other,
+}
+
+impl Category {
+    pub const ALL: [Category; 10] = [
+        Category::Recital,
+        Category::Chamber,
+        Category::Orchestral,
+        Category::Opera,
+        Category::Choral,
+        Category::Family,
+        Category::Talk,
+        Category::Light,
+        Category::Service,
+        Category::Other,
+    ];
+
+    /// The name used in JSON and in configuration, e.g. "orchestral"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Recital => "recital",
+            Category::Chamber => "chamber",
+            Category::Orchestral => "orchestral",
+            Category::Opera => "opera",
+            Category::Choral => "choral",
+            Category::Family => "family",
+            Category::Talk => "talk",
+            Category::Light => "light",
+            Category::Service => "service",
+            Category::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Category> {
+        Category::ALL.into_iter().find(|c| c.name() == name)
+    }
+}
+
+/// How people get in to a concert
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, TS, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum Availability {
+    /// Tickets have to be bought
+    #[default]
+    Ticketed,
+    /// Free, but a ticket has to be booked (or the venue doesn't say whether one has to be)
+    Free,
+    /// Free, and anyone can just turn up
+    FreeNoBooking,
+    /// Free to get in, with a collection at the end for anyone who wants to give something (as
+    /// at most church recitals)
+    RetiringCollection,
+}
+
+impl Availability {
+    pub const ALL: [Availability; 4] = [
+        Availability::Ticketed,
+        Availability::Free,
+        Availability::FreeNoBooking,
+        Availability::RetiringCollection,
+    ];
+
+    /// The name used in JSON and in configuration, e.g. "free_no_booking"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Availability::Ticketed => "ticketed",
+            Availability::Free => "free",
+            Availability::FreeNoBooking => "free_no_booking",
+            Availability::RetiringCollection => "retiring_collection",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Availability> {
+        Availability::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+/// A named group of concerts, e.g. a festival or an artist's residency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct Series {
+    pub name: String,
+    /// e.g. "BBC" or "Wigmore Hall"
+    pub organiser: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub url: Option<String>,
+}
+
+/// A singer and the role they sing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct CastMember {
+    pub role: String,
+    pub name: String,
+}
+
+/// Details of a staged production, e.g. of an opera. Everything except the cast and conductor is
+/// the same for all performances of the production.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct Production {
+    /// Title of the work, e.g. "La bohème"
+    pub title: String,
+    pub composer: String,
+    /// Conductor of this performance
+    pub conductor: Option<String>,
+    pub director: Option<String>,
+    /// Cast of this performance
+    pub cast: Vec<CastMember>,
+    /// Total length in minutes, including intervals
+    pub running_time_minutes: Option<u32>,
+    pub n_intervals: Option<u32>,
+    /// Language that the work is sung in
+    pub language: Option<String>,
+    /// Language of the surtitles, if there are any
+    pub surtitles: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 pub struct ConcertData {
     pub datetime: DateTime<Utc>,
     pub url: String,
@@ -29,16 +222,36 @@ pub struct ConcertData {
     pub subtitle: Option<String>,
     pub description: Option<String>,
     pub programme_pdf_url: Option<String>,
+    /// Text extracted from the programme PDF, if programmes were downloaded. This is far too long
+    /// to go in concerts.json, so it is written to a separate file (see `programmes::write_texts`).
+    #[serde(default, skip_serializing)]
+    #[ts(skip)]
+    #[schemars(skip)]
+    pub programme_text: Option<String>,
     pub pieces: Vec<Piece>,
     pub venue: String,
+    // Older output doesn't have this
+    #[serde(default)]
+    pub category: Category,
     pub min_price: Option<u32>, // pennies
     pub max_price: Option<u32>, // pennies
+    // Older output doesn't have this
+    #[serde(default)]
+    pub availability: Availability,
+    // Older output doesn't have this
+    #[serde(default)]
+    pub media: Media,
 
     pub is_wigmore_u35: bool,
-    pub is_prom: bool,
+    // Older output doesn't have this
+    #[serde(default)]
+    pub series: Vec<Series>,
+    /// Only for staged productions, e.g. operas (and older output doesn't have this)
+    #[serde(default)]
+    pub production: Option<Production>,
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 #[ts(export)]
 pub struct Concert {
     pub id: String,
@@ -71,22 +284,149 @@ pub fn add_id_to_concert(c: ConcertData) -> Concert {
     Concert { id, concert: c }
 }
 
+/// Read concerts from a JSON file previously written by this program, with or without the
+/// envelope (see `output::read`). Returns `None` if the file does not exist.
+pub fn read_concerts(path: &Path) -> Option<Vec<Concert>> {
+    output::read(path).map(output::ConcertsFile::into_concerts)
+}
+
+/// Turn a name into something that can be used as a filename, e.g. "Queen Elizabeth Hall" becomes
+/// "queen-elizabeth-hall"
+pub fn slugify(name: &str) -> String {
+    deunicode(name)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// All the text inside an element, with the whitespace between text nodes collapsed to single
+/// spaces
+pub fn element_text(elem: ElementRef) -> String {
+    elem.text()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .join(" ")
+}
+
+/// The text of an HTML fragment (e.g. a description given as HTML in a JSON API), without tags
+pub fn text_of_html(html: &str) -> String {
+    element_text(Html::parse_fragment(html).root_element())
+}
+
+/// Format a price in pennies, e.g. 1550 becomes "£15.50" and 1500 becomes "£15"
+pub fn format_pennies(price: u32) -> String {
+    if price.is_multiple_of(100) {
+        format!("£{}", price / 100)
+    } else {
+        format!("£{}.{:02}", price / 100, price % 100)
+    }
+}
+
+/// All the prices mentioned in a piece of text, in pennies. Prices can be written as e.g. £15.50 or
+/// £15. If there are no prices but the text says the event is free (or that there is a retiring
+/// collection), the price is zero (text like "£10, under 18s free" is about concessions, so doesn't
+/// count).
+pub fn parse_prices(text: &str) -> Vec<u32> {
+    let price_re = Regex::new(r"£(\d+)(?:\.(\d{2}))?").unwrap();
+    let mut prices: Vec<u32> = price_re
+        .captures_iter(text)
+        .map(|cap| {
+            let pounds = cap[1].parse::<u32>().unwrap();
+            let pence = cap.get(2).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
+            pounds * 100 + pence
+        })
+        .collect();
+    if prices.is_empty()
+        && Regex::new(r"(?i)\b(free|retiring collection)\b")
+            .unwrap()
+            .is_match(text)
+    {
+        prices.push(0);
+    }
+    prices
+}
+
+/// Work out how people get in to a concert, from the text about its tickets (e.g. "Free, no booking
+/// required") and its prices. Saying that there's no need to book only means that people can just
+/// turn up if the concert is free, as opposed to e.g. "£10 on the door, no booking required". Free
+/// concerts with a retiring collection never need booking.
+pub fn parse_availability(
+    text: &str,
+    min_price: Option<u32>,
+    max_price: Option<u32>,
+) -> Availability {
+    let no_booking_re = Regex::new(
+        r"(?i)\b(no (booking|tickets?|reservations?)( is| are)? (required|needed|necessary)|no booking|no need to book|without booking|unticketed|just turn up|drop[ -]in)\b",
+    )
+    .unwrap();
+    let retiring_collection_re = Regex::new(r"(?i)\bretiring collection\b").unwrap();
+    match (min_price, max_price) {
+        (Some(0), Some(0)) if retiring_collection_re.is_match(text) => {
+            Availability::RetiringCollection
+        }
+        (Some(0), Some(0)) if no_booking_re.is_match(text) => Availability::FreeNoBooking,
+        (Some(0), Some(0)) => Availability::Free,
+        _ => Availability::Ticketed,
+    }
+}
+
+/// Human-readable description of a concert's prices (this matches `getPriceString` in the
+/// frontend)
+pub fn price_string(c: &ConcertData) -> String {
+    match c.availability {
+        Availability::FreeNoBooking => return "Free entry, no booking required".to_string(),
+        Availability::RetiringCollection => return "Free entry, retiring collection".to_string(),
+        _ => {}
+    }
+    match (c.min_price, c.max_price) {
+        (Some(0), Some(0)) => "Free entry".to_string(),
+        (Some(min_price), Some(max_price)) if min_price == max_price => format_pennies(min_price),
+        (Some(min_price), Some(max_price)) => {
+            format!(
+                "{}–{}",
+                format_pennies(min_price),
+                format_pennies(max_price)
+            )
+        }
+        (Some(min_price), None) => format!("from {}", format_pennies(min_price)),
+        _ => "Price not available".to_string(),
+    }
+}
+
 pub fn report_concert(c: &ConcertData) {
     let london_datetime = c.datetime.with_timezone(&London);
     info!("Found {}: {}", london_datetime, c.title);
 }
 
-pub fn ymd_hm_to_utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
-    London
-        .with_ymd_and_hms(year, month, day, hour, minute, 0)
-        .unwrap()
-        .with_timezone(&Utc)
+/// Convert a London date and time to UTC (see `datetime::london_to_utc` for what happens when the
+/// clocks change). Gives an error if the date or time doesn't exist.
+pub fn ymd_hm_to_utc(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+) -> Result<DateTime<Utc>, datetime::DatetimeError> {
+    let text = format!("{}-{:02}-{:02} {}:{:02}", year, month, day, hour, minute);
+    let naive_datetime = NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|date| date.and_hms_opt(hour, minute, 0))
+        .ok_or(datetime::DatetimeError::Invalid(text))?;
+    Ok(datetime::london_to_utc(naive_datetime))
 }
 
-pub fn naivedt_to_utc(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Utc> {
-    let naive_datetime = date.and_hms_opt(hour, minute, 0).unwrap();
-    London
-        .from_local_datetime(&naive_datetime)
-        .unwrap()
-        .with_timezone(&Utc)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Selector;
+
+    #[test]
+    fn text_of_elements() {
+        let html = "<p>Schubert:\n  <em>Winterreise</em> </p><p> D911</p>";
+        assert_eq!(text_of_html(html), "Schubert: Winterreise D911");
+        let doc = Html::parse_fragment(html);
+        let p = doc.select(&Selector::parse("p").unwrap()).next().unwrap();
+        assert_eq!(element_text(p), "Schubert: Winterreise");
+    }
 }
@@ -0,0 +1,376 @@
+use crate::core::{Concert, ConcertData};
+use crate::datetime;
+use crate::output::SourceMetadata;
+use crate::source::Source;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Europe::London;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How long to wait before trying again after a source fails to scrape
+pub const RETRY_DELAY: Duration = Duration::minutes(15);
+
+/// How often a source is scraped in daemon mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// At a fixed interval after the previous run
+    Every(Duration),
+    /// Once a day at the given time (in London), but only in the months between `first_month`
+    /// and `last_month` (inclusive)
+    Daily {
+        at: NaiveTime,
+        first_month: u32,
+        last_month: u32,
+    },
+}
+
+/// Wigmore Hall adds concerts all the time, so it is checked hourly. The other venues are scraped
+/// overnight, and the Proms only between the programme announcement in April and the end of the
+/// season in September.
+pub fn schedule(source: Source) -> Schedule {
+    match source {
+        Source::Wigmore => Schedule::Every(Duration::hours(1)),
+        Source::Southbank => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::KingsPlace => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(3, 0, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::LsoStLukes => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(3, 30, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::RoyalOperaHouse => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::Eno => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(4, 30, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::Ram => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(5, 0, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::Rcm => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(5, 15, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::Guildhall => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(5, 30, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::SmithSquare => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(5, 45, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::SouthwarkCathedral => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(6, 15, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::StPauls => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(6, 30, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::WestminsterAbbey => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(6, 45, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::CadoganHall => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::StMartinInTheFields => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(7, 15, 0).unwrap(),
+            first_month: 1,
+            last_month: 12,
+        },
+        Source::Proms => Schedule::Daily {
+            at: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            first_month: 4,
+            last_month: 9,
+        },
+    }
+}
+
+fn london_to_utc(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    datetime::london_to_utc(date.and_time(time))
+}
+
+impl Schedule {
+    /// When the next run should happen, given the time of the last successful run (if there was
+    /// one). If the returned time is not after `now`, a run is due.
+    pub fn next_run(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> DateTime<Utc> {
+        match *self {
+            Schedule::Every(interval) => last_run.map_or(now, |t| t + interval),
+            Schedule::Daily {
+                at,
+                first_month,
+                last_month,
+            } => {
+                let today = now.with_timezone(&London).date_naive();
+                let slots = (-1..=366)
+                    .map(|offset| today + Duration::days(offset))
+                    .filter(|date| (first_month..=last_month).contains(&date.month()))
+                    .map(|date| london_to_utc(date, at));
+                let (past_slots, future_slots): (Vec<_>, Vec<_>) =
+                    slots.partition(|slot| *slot <= now);
+                // If the most recent slot (within the last day) was missed, e.g. because the
+                // daemon wasn't running at the time, catch up now
+                if let Some(last_slot) = past_slots.last() {
+                    if last_run.is_none_or(|t| t < *last_slot) {
+                        return *last_slot;
+                    }
+                }
+                future_slots[0]
+            }
+        }
+    }
+}
+
+/// The result of the last successful run for a source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceState {
+    pub last_run: DateTime<Utc>,
+    pub concerts: Vec<ConcertData>,
+}
+
+/// State of the daemon, which is saved after every run so that restarting the daemon doesn't
+/// cause sources to be scraped again before they are due
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaemonState {
+    pub sources: BTreeMap<Source, SourceState>,
+}
+
+impl DaemonState {
+    /// Read the state from a file, or start with an empty state if the file doesn't exist
+    pub fn read(path: &Path) -> DaemonState {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => DaemonState::default(),
+            Err(e) => panic!("Failed to read {}: {}", path.display(), e),
+        }
+    }
+
+    /// Write the state to a file. The state is first written to a temporary file which is then
+    /// renamed, so that the file is never left half-written.
+    pub fn write(&self, path: &Path) {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(self).unwrap()).unwrap();
+        std::fs::rename(&tmp_path, path).unwrap();
+    }
+
+    pub fn next_run(&self, source: Source, now: DateTime<Utc>) -> DateTime<Utc> {
+        let last_run = self.sources.get(&source).map(|s| s.last_run);
+        schedule(source).next_run(last_run, now)
+    }
+
+    pub fn record(&mut self, source: Source, run_at: DateTime<Utc>, concerts: Vec<ConcertData>) {
+        self.sources.insert(
+            source,
+            SourceState {
+                last_run: run_at,
+                concerts,
+            },
+        );
+    }
+
+    /// When each of the given sources was last scraped: by the daemon if it has scraped it, and
+    /// otherwise (as in `merged_concerts`) according to the last concerts.json written, whose
+    /// sources are given by `previous`.
+    pub fn scraped_at(
+        &self,
+        sources: &[Source],
+        previous: &[SourceMetadata],
+    ) -> BTreeMap<Source, DateTime<Utc>> {
+        sources
+            .iter()
+            .filter_map(|source| {
+                let scraped_at = match self.sources.get(source) {
+                    Some(state) => Some(state.last_run),
+                    None => previous
+                        .iter()
+                        .find(|metadata| metadata.source == *source)
+                        .map(|metadata| metadata.scraped_at),
+                };
+                scraped_at.map(|scraped_at| (*source, scraped_at))
+            })
+            .collect()
+    }
+
+    /// The latest concerts from each of the given sources, leaving out those which have already
+    /// started. Sources which the daemon hasn't scraped (yet, or at all because they are
+    /// disabled) fall back to their concerts in `previous` (i.e. the last concerts.json written),
+    /// so that they don't disappear from the output until they are next scraped.
+    pub fn merged_concerts(
+        &self,
+        sources: &[Source],
+        previous: &[Concert],
+        now: DateTime<Utc>,
+    ) -> Vec<ConcertData> {
+        sources
+            .iter()
+            .flat_map(|source| match self.sources.get(source) {
+                Some(state) => state.concerts.clone(),
+                None => previous
+                    .iter()
+                    .filter(|c| Source::from_url(&c.concert.url) == Some(*source))
+                    .map(|c| c.concert.clone())
+                    .collect(),
+            })
+            .filter(|c| c.datetime > now)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, min, 0)
+            .unwrap()
+    }
+
+    fn concert(url: &str, datetime: DateTime<Utc>) -> ConcertData {
+        ConcertData {
+            datetime,
+            url: url.to_string(),
+            title: "Recital".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn every() {
+        let schedule = Schedule::Every(Duration::hours(1));
+        let now = utc(1, 10, 12, 0);
+        assert_eq!(schedule.next_run(None, now), now);
+        assert_eq!(
+            schedule.next_run(Some(utc(1, 10, 11, 30)), now),
+            utc(1, 10, 12, 30)
+        );
+    }
+
+    #[test]
+    fn daily() {
+        let schedule = schedule(Source::Southbank);
+        // 02:00 in London is 02:00 UTC in winter
+        assert_eq!(
+            schedule.next_run(Some(utc(1, 10, 2, 0)), utc(1, 10, 12, 0)),
+            utc(1, 11, 2, 0)
+        );
+        // A missed run is caught up straight away
+        assert_eq!(
+            schedule.next_run(Some(utc(1, 9, 2, 0)), utc(1, 10, 12, 0)),
+            utc(1, 10, 2, 0)
+        );
+        assert_eq!(schedule.next_run(None, utc(1, 10, 12, 0)), utc(1, 10, 2, 0));
+        // ...and 01:00 UTC in summer
+        assert_eq!(
+            schedule.next_run(Some(utc(7, 10, 1, 0)), utc(7, 10, 12, 0)),
+            utc(7, 11, 1, 0)
+        );
+        // The clocks go forward at 01:00, so 02:00 on that day is already BST
+        assert_eq!(
+            schedule.next_run(Some(utc(3, 28, 2, 0)), utc(3, 28, 12, 0)),
+            utc(3, 29, 1, 0)
+        );
+    }
+
+    #[test]
+    fn daily_only_in_season() {
+        let schedule = schedule(Source::Proms);
+        assert_eq!(
+            schedule.next_run(Some(utc(9, 30, 5, 0)), utc(10, 15, 12, 0)),
+            Utc.with_ymd_and_hms(2027, 4, 1, 5, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn unscraped_sources_fall_back_to_previous_output() {
+        let now = utc(2, 1, 12, 0);
+        let wigmore_url = "https://www.wigmore-hall.org.uk/whats-on/1";
+        let kings_place_url = "https://www.kingsplace.co.uk/whats-on/1";
+        let mut state = DaemonState::default();
+        state.record(
+            Source::Wigmore,
+            utc(2, 1, 11, 0),
+            vec![concert(wigmore_url, utc(2, 2, 19, 30))],
+        );
+
+        let previous = vec![
+            Concert {
+                id: "old-wigmore".to_string(),
+                concert: concert(wigmore_url, utc(2, 3, 19, 30)),
+            },
+            Concert {
+                id: "kings-place".to_string(),
+                concert: concert(kings_place_url, utc(2, 4, 19, 30)),
+            },
+            Concert {
+                id: "past".to_string(),
+                concert: concert(kings_place_url, utc(2, 1, 11, 0)),
+            },
+        ];
+        let sources = [Source::Wigmore, Source::KingsPlace, Source::Rcm];
+        let merged = state.merged_concerts(&sources, &previous, now);
+        assert_eq!(
+            merged,
+            vec![
+                concert(wigmore_url, utc(2, 2, 19, 30)),
+                concert(kings_place_url, utc(2, 4, 19, 30)),
+            ]
+        );
+
+        let metadata = SourceMetadata {
+            source: Source::KingsPlace,
+            name: Source::KingsPlace.name().to_string(),
+            scraped_at: utc(1, 31, 3, 0),
+            n_concerts: 2,
+        };
+        assert_eq!(
+            state.scraped_at(&sources, &[metadata]),
+            BTreeMap::from([
+                (Source::Wigmore, utc(2, 1, 11, 0)),
+                (Source::KingsPlace, utc(1, 31, 3, 0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn state_is_saved() {
+        let path = std::env::temp_dir().join(format!("ldncls-daemon-{}.json", std::process::id()));
+        assert!(DaemonState::read(&path).sources.is_empty());
+        let mut state = DaemonState::default();
+        state.record(Source::Rcm, utc(2, 1, 5, 15), vec![]);
+        state.write(&path);
+        let read = DaemonState::read(&path);
+        assert_eq!(
+            read.next_run(Source::Rcm, utc(2, 1, 12, 0)),
+            utc(2, 2, 5, 15)
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::format::{parse, Parsed, StrftimeItems};
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Europe::London;
+use regex::{Captures, Regex};
+use std::fmt;
+use std::ops::Range;
+
+/// Why some text couldn't be parsed as a date or time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatetimeError {
+    /// The text doesn't look like a date (or time) in any of the formats we know
+    Unrecognised(String),
+    /// The text looks like a date or time, but not one that exists, e.g. "Monday 30 February 2026"
+    /// or "25:00"
+    Invalid(String),
+}
+
+impl fmt::Display for DatetimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DatetimeError::Unrecognised(text) => write!(f, "unrecognised date or time: {}", text),
+            DatetimeError::Invalid(text) => write!(f, "date or time doesn't exist: {}", text),
+        }
+    }
+}
+
+impl std::error::Error for DatetimeError {}
+
+/// Convert a date and time in London to UTC. Twice a year, the clocks make this awkward:
+///
+/// - When they go forward (at 1am on the last Sunday in March), times from 1am to 2am don't exist.
+///   These are taken to be an hour later, i.e. as if the clocks hadn't gone forward yet, which is
+///   what anyone who gets it wrong would be going by.
+/// - When they go back (at 2am on the last Sunday in October), times from 1am to 2am happen
+///   twice. The first one (in British Summer Time) is used, since that's when people would turn up.
+///
+/// Neither is likely for a concert, but these are handled so that one odd listing can't panic.
+pub fn london_to_utc(naive: NaiveDateTime) -> DateTime<Utc> {
+    let local = match London.from_local_datetime(&naive) {
+        LocalResult::Single(local) => local,
+        LocalResult::Ambiguous(earliest, _latest) => earliest,
+        LocalResult::None => match London.from_local_datetime(&(naive + Duration::hours(1))) {
+            LocalResult::Single(local) | LocalResult::Ambiguous(local, _) => local,
+            // London's clocks only ever change by an hour
+            LocalResult::None => unreachable!("No time in London near {}", naive),
+        },
+    };
+    local.with_timezone(&Utc)
+}
+
+/// Times of day, e.g. "7.30pm", "7:30 p.m.", "7pm", "19:30", "19.30", "noon", or "12 noon". Which
+/// alternative matched is told apart by the capture groups' names.
+fn time_re() -> Regex {
+    Regex::new(
+        r"(?ix)
+        \b(?:
+            (?P<word>(?:12\s*)?noon|midday|midnight)
+          | (?P<h12>\d{1,2})(?:[.:](?P<m12>\d{2}))?\s*(?P<ampm>[ap])\.?\s*m\b\.?
+          | (?P<h24>\d{1,2})[.:h](?P<m24>\d{2})\b
+        )",
+    )
+    .unwrap()
+}
+
+/// A time range with am or pm only given at the end, e.g. "7–9pm" or "7.30 to 9.30pm", whose start
+/// is in the same half of the day as its end (unless that's impossible, e.g. "11–1pm")
+fn bare_range_re() -> Regex {
+    Regex::new(
+        r"(?i)\b(\d{1,2})(?:[.:](\d{2}))?\s*(?:-|–|—|to)\s*(\d{1,2})(?:[.:]\d{2})?\s*([ap])\.?\s*m\b",
+    )
+    .unwrap()
+}
+
+fn time_from_captures(cap: &Captures) -> Result<NaiveTime, DatetimeError> {
+    let invalid = || DatetimeError::Invalid(cap[0].trim().to_string());
+    if let Some(word) = cap.name("word") {
+        let time = match word.as_str().to_lowercase().as_str() {
+            "midnight" => NaiveTime::MIN,
+            _ => NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+        };
+        return Ok(time);
+    }
+    let (hour, minute, ampm) = match cap.name("h12") {
+        Some(hour) => (hour, cap.name("m12"), cap.name("ampm")),
+        None => (cap.name("h24").unwrap(), cap.name("m24"), None),
+    };
+    let hour: u32 = hour.as_str().parse().unwrap();
+    let minute: u32 = minute.map_or(0, |m| m.as_str().parse().unwrap());
+    let hour = match ampm.map(|m| m.as_str().to_lowercase()) {
+        None => hour,
+        Some(_) if hour == 0 || hour > 12 => return Err(invalid()),
+        Some(ampm) if ampm == "a" => hour % 12,
+        Some(_) => hour % 12 + 12,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(invalid)
+}
+
+/// What a time is the time of, judging by the words just before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeLabel {
+    Start,
+    /// e.g. "Doors 7pm", which is before the start
+    Doors,
+    /// e.g. "ends 9.30pm", or the end of a range like "7.30pm–9.30pm"
+    End,
+}
+
+fn label_before(text_before: &str) -> TimeLabel {
+    let before = text_before.to_lowercase();
+    let before = before.trim_end();
+    // Only the last few words matter, e.g. in "Doors 6.30pm, concert 7.30pm" the second time has
+    // "concert" just before it
+    let recent: String = before
+        .rsplit([',', ';', '|', '('])
+        .next()
+        .unwrap_or("")
+        .to_string();
+    if before.ends_with(['-', '–', '—']) || before.ends_with(" to") || before.ends_with("until")
+    {
+        TimeLabel::End
+    } else if recent.contains("doors") {
+        TimeLabel::Doors
+    } else if ["ends", "finishes", "finish", "end"]
+        .iter()
+        .any(|word| recent.split_whitespace().any(|w| w == *word))
+    {
+        TimeLabel::End
+    } else {
+        TimeLabel::Start
+    }
+}
+
+/// A time found in some text
+struct FoundTime {
+    /// Where it is in the text, in bytes
+    range: Range<usize>,
+    time: Result<NaiveTime, DatetimeError>,
+    label: TimeLabel,
+}
+
+/// All the times in some text, in order
+fn find_times(text: &str) -> Vec<FoundTime> {
+    // The start of a range like "7–9pm" doesn't have am or pm, so it gets it from the end
+    let mut times: Vec<FoundTime> = bare_range_re()
+        .captures_iter(text)
+        .map(|cap| {
+            let hour: u32 = cap[1].parse().unwrap();
+            let minute: u32 = cap.get(2).map_or(0, |m| m.as_str().parse().unwrap());
+            let end_hour: u32 = cap[3].parse().unwrap();
+            let is_pm = cap[4].eq_ignore_ascii_case("p") && hour % 12 <= end_hour % 12;
+            let hour = if is_pm { hour % 12 + 12 } else { hour % 12 };
+            let start = cap.get(1).unwrap().start();
+            let end = cap.get(2).unwrap_or(cap.get(1).unwrap()).end();
+            FoundTime {
+                range: start..end,
+                time: NaiveTime::from_hms_opt(hour, minute, 0)
+                    .ok_or_else(|| DatetimeError::Invalid(cap[0].to_string())),
+                label: label_before(&text[..start]),
+            }
+        })
+        .collect();
+
+    for cap in time_re().captures_iter(text) {
+        let m = cap.get(0).unwrap();
+        // Prices (e.g. "£12.50") and dates (e.g. "4.2.2026") aren't times
+        let before = text[..m.start()].chars().last();
+        let after = text[m.end()..].chars().next();
+        let is_price_or_date = matches!(before, Some('£' | '$' | '€' | '.' | '/'))
+            || (cap.name("h24").is_some() && matches!(after, Some('.' | '/')));
+        let is_range_start = times.iter().any(|found| found.range.contains(&m.start()));
+        if !is_price_or_date && !is_range_start {
+            times.push(FoundTime {
+                range: m.range(),
+                time: time_from_captures(&cap),
+                label: label_before(&text[..m.start()]),
+            });
+        }
+    }
+    times.sort_by_key(|found| found.range.start);
+    times
+}
+
+/// When something starts, from text about its time, e.g. "7.30pm", "19:30", "12 noon", "7.30pm –
+/// 9.30pm", "7–9pm", or "Doors 6.30pm, concert 7.30pm". Doors times are only used if there's no
+/// other time.
+pub fn parse_time(text: &str) -> Result<NaiveTime, DatetimeError> {
+    let times = find_times(text);
+    times
+        .iter()
+        .find(|found| found.label == TimeLabel::Start)
+        .or_else(|| times.iter().find(|found| found.label == TimeLabel::Doors))
+        .map(|found| found.time.clone())
+        .unwrap_or_else(|| Err(DatetimeError::Unrecognised(text.trim().to_string())))
+}
+
+/// Date formats, tried in order. chrono accepts both short and long names of days and months for
+/// `%a` and `%b`, so e.g. "Wed 4 Feb 2026" and "Wednesday 4 February 2026" are both covered by the
+/// first.
+const DATE_FORMATS: [&str; 6] = [
+    "%a %e %b %Y",
+    "%e %b %Y",
+    "%a %b %e %Y",
+    "%b %e %Y",
+    "%Y-%m-%d",
+    "%d/%m/%Y",
+];
+
+/// Parse a date, e.g. "Wednesday 4 February 2026", "Wed 4th Feb 2026", "4 Feb 2026", "Feb 4,
+/// 2026", "2026-02-04", or "04/02/2026". Anything that isn't part of the date (e.g. "at") should
+/// be removed first.
+pub fn parse_date(text: &str) -> Result<NaiveDate, DatetimeError> {
+    let ordinal_re = Regex::new(r"(?i)\b(\d{1,2})(?:st|nd|rd|th)\b").unwrap();
+    let cleaned = ordinal_re.replace_all(text, "$1");
+    // Day and month names are shortened, e.g. "Wednesday" to "Wed" and "Sept" to "Sep"
+    let cleaned = Regex::new(
+        r"(?i)\b(mon|tue|wed|thu|fri|sat|sun|jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\b",
+    )
+    .unwrap()
+    .replace_all(&cleaned, "$1");
+    let cleaned = cleaned
+        .replace(',', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut invalid = false;
+    for format in DATE_FORMATS {
+        let mut parsed = Parsed::new();
+        if parse(&mut parsed, &cleaned, StrftimeItems::new(format)).is_err() {
+            continue;
+        }
+        match parsed.to_naive_date() {
+            Ok(date) => return Ok(date),
+            // e.g. the wrong day of the week, or 30 February
+            Err(_) => invalid = true,
+        }
+    }
+    if invalid {
+        Err(DatetimeError::Invalid(text.trim().to_string()))
+    } else {
+        Err(DatetimeError::Unrecognised(text.trim().to_string()))
+    }
+}
+
+/// Parse a date and time from a listing, e.g. "Wednesday 4 February 2026, 7.30pm", "Sat 7 Feb
+/// 2026 at 12 noon", "Fri 6 Mar 2026, doors 7pm", or "2026-02-04T19:30". Times are in London
+/// (see `london_to_utc`) unless there's a UTC offset, e.g. "2026-02-04T19:30:00+00:00". If there
+/// isn't a time, the event is taken to be at midnight.
+pub fn parse_london(text: &str) -> Result<DateTime<Utc>, DatetimeError> {
+    let text = text.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return Ok(london_to_utc(naive));
+        }
+    }
+
+    let times = find_times(text);
+    let time = if times.is_empty() {
+        NaiveTime::MIN
+    } else {
+        parse_time(text)?
+    };
+    // Whatever is left once the times (and the words about them) are taken out is the date
+    let mut date_text = text.to_string();
+    for found in times.iter().rev() {
+        date_text.replace_range(found.range.clone(), " ");
+    }
+    let filler_re = Regex::new(
+        r"(?i)\b(at|from|doors|open|starts?|concert|performance|until|to|ends?)\b|\s[-–—]\s|[|()]",
+    )
+    .unwrap();
+    let date_text = filler_re.replace_all(&date_text, " ");
+    let date = parse_date(&date_text).map_err(|e| match e {
+        DatetimeError::Unrecognised(_) => DatetimeError::Unrecognised(text.to_string()),
+        DatetimeError::Invalid(_) => DatetimeError::Invalid(text.to_string()),
+    })?;
+    Ok(london_to_utc(date.and_time(time)))
+}
+
+/// Parse a date and time in London with the first of some chrono formats (see
+/// `chrono::format::strftime`) that fits it, for sites whose dates `parse_london` can't make sense
+/// of. Unlike `NaiveDateTime::parse_from_str`, formats don't need to have minutes or a time in
+/// them: "%l%P" fits "7pm", and a date on its own means midnight.
+pub fn parse_with_formats(text: &str, formats: &[String]) -> Result<DateTime<Utc>, DatetimeError> {
+    let parse_with = |format: &String| -> Option<NaiveDateTime> {
+        let mut parsed = Parsed::new();
+        parse(&mut parsed, text, StrftimeItems::new(format)).ok()?;
+        let date = parsed.to_naive_date().ok()?;
+        if parsed.hour_mod_12().is_none() {
+            return Some(date.and_time(NaiveTime::MIN));
+        }
+        if parsed.minute().is_none() {
+            parsed.set_minute(0).ok()?;
+        }
+        Some(date.and_time(parsed.to_naive_time().ok()?))
+    };
+    formats
+        .iter()
+        .find_map(parse_with)
+        .map(london_to_utc)
+        .ok_or_else(|| DatetimeError::Unrecognised(text.trim().to_string()))
+}
@@ -0,0 +1,571 @@
+use crate::classify;
+use crate::core;
+use crate::datetime;
+use crate::http;
+use crate::media;
+use chrono::Utc;
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{debug, info, warn};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// A scraper written as a TOML file instead of in Rust: which links to follow from the venue's
+/// listing, and where on each event page the fields of `ConcertData` are. Definitions are read
+/// when the scraper runs, so fixing a selector after a venue changes its website doesn't need a
+/// rebuild. See `scrapers/southbank.toml` for an example.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Definition {
+    pub name: String,
+    /// Used when the event page doesn't say where the event is (or there's no venue selector)
+    pub venue: Option<String>,
+    pub listing: ListingSpec,
+    pub event: EventSpec,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListingSpec {
+    /// URL of the listing. If it contains `{page}`, pages 1, 2, 3, ... are fetched until one has no
+    /// new links.
+    pub url: String,
+    /// Links to event pages
+    pub link: String,
+    /// Link to the next page of the listing, if it's paginated that way instead
+    pub next_page: Option<String>,
+}
+
+/// Where to find a piece of text: either just a selector (the text of the first element matching
+/// it), or a `FieldSpec`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Field {
+    Selector(String),
+    Spec(FieldSpec),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSpec {
+    pub selector: String,
+    /// Take this attribute instead of the element's text, e.g. "datetime" or "href"
+    pub attr: Option<String>,
+    /// Take only the last piece of text in the element, e.g. to skip a visually hidden label
+    #[serde(default)]
+    pub last_text: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventSpec {
+    pub title: Field,
+    pub subtitle: Option<Field>,
+    /// Every element matching this is a paragraph of the description
+    pub description: Option<Field>,
+    pub venue: Option<Field>,
+    pub datetime: DatetimeSpec,
+    pub price: Option<PriceSpec>,
+    pub performers: Option<PerformersSpec>,
+    pub pieces: Option<PiecesSpec>,
+    pub series: Option<SeriesSpec>,
+    /// Labels the venue gives the event (e.g. "Family"), which are used to classify it
+    pub tags: Option<Field>,
+    /// Also use the last part of the event's URL to classify it, for venues where some series can
+    /// only be identified that way
+    #[serde(default)]
+    pub tags_from_url: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DatetimeSpec {
+    pub field: Field,
+    /// chrono formats (see `chrono::format::strftime`), tried in order, in London time. The
+    /// minutes can be left out (e.g. for "7pm"), and so can the time altogether, in which case the
+    /// event is at midnight. Without any, the text is parsed with `datetime::parse_london`, which
+    /// copes with most of the ways that venues write dates and times.
+    #[serde(default)]
+    pub formats: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriceSpec {
+    pub selector: String,
+    /// The first group is pounds and the optional second group is pence
+    #[serde(default = "default_price_regex")]
+    pub regex: String,
+    /// The venue only gives a "from" price, so there is no maximum
+    #[serde(default)]
+    pub min_only: bool,
+    /// An element which is only on the pages of free events, e.g. a "free, no ticket" button
+    pub free_selector: Option<String>,
+    /// How to get in to events with `free_selector` on their page
+    #[serde(default = "default_free_availability")]
+    pub free_availability: core::Availability,
+}
+
+fn default_price_regex() -> String {
+    r"£(\d+)(?:\.(\d{2}))?".to_string()
+}
+
+fn default_free_availability() -> core::Availability {
+    core::Availability::Free
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PerformersSpec {
+    /// One element per performer
+    pub item: String,
+    /// Inside the item. If not given, the whole item is the name.
+    pub name: Option<String>,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PiecesSpec {
+    /// One element per composer
+    pub item: String,
+    pub composer: String,
+    pub title: String,
+    /// Several works by the same composer can be in one item, separated by this
+    pub split: Option<String>,
+    /// Items which aren't works, e.g. "Interval"
+    #[serde(default)]
+    pub skip_composers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeriesSpec {
+    /// Links to the series (or festivals) the event is part of
+    pub selector: String,
+    pub organiser: String,
+}
+
+fn parse_selector(selector: &str) -> Selector {
+    Selector::parse(selector).unwrap_or_else(|_| panic!("Invalid selector: {}", selector))
+}
+
+impl FieldSpec {
+    fn extract(&self, elem: ElementRef) -> Option<String> {
+        let text = match &self.attr {
+            Some(attr) => elem.value().attr(attr)?.trim().to_string(),
+            None if self.last_text => elem
+                .text()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .last()?
+                .to_string(),
+            None => core::element_text(elem),
+        };
+        (!text.is_empty()).then_some(text)
+    }
+
+    /// Text of every element matching the selector (which have any)
+    pub fn all(&self, scope: ElementRef) -> Vec<String> {
+        scope
+            .select(&parse_selector(&self.selector))
+            .filter_map(|elem| self.extract(elem))
+            .collect()
+    }
+
+    /// Text of the first element matching the selector
+    pub fn first(&self, scope: ElementRef) -> Option<String> {
+        self.all(scope).into_iter().next()
+    }
+}
+
+impl Field {
+    fn spec(&self) -> FieldSpec {
+        match self {
+            Field::Selector(selector) => FieldSpec {
+                selector: selector.clone(),
+                attr: None,
+                last_text: false,
+            },
+            Field::Spec(spec) => spec.clone(),
+        }
+    }
+
+    pub fn selector(&self) -> &str {
+        match self {
+            Field::Selector(selector) => selector,
+            Field::Spec(spec) => &spec.selector,
+        }
+    }
+
+    pub fn all(&self, scope: ElementRef) -> Vec<String> {
+        self.spec().all(scope)
+    }
+
+    pub fn first(&self, scope: ElementRef) -> Option<String> {
+        self.spec().first(scope)
+    }
+}
+
+/// Directory that definitions are read from: `$LDNCLS_SCRAPERS_DIR`, or `scrapers` next to
+/// Cargo.toml
+pub fn definitions_dir() -> PathBuf {
+    match std::env::var("LDNCLS_SCRAPERS_DIR") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => Path::new(env!("CARGO_MANIFEST_DIR")).join("scrapers"),
+    }
+}
+
+impl Definition {
+    pub fn from_toml(text: &str) -> Definition {
+        let definition: Definition =
+            toml::from_str(text).unwrap_or_else(|e| panic!("Invalid scraper definition: {}", e));
+        definition.check_selectors();
+        definition
+    }
+
+    /// Read a definition from `definitions_dir()`, e.g. `load("southbank")` reads southbank.toml
+    pub fn load(name: &str) -> Definition {
+        let path = definitions_dir().join(format!("{}.toml", name));
+        debug!("Reading scraper definition from {}", path.display());
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to read scraper definition {}: {}",
+                path.display(),
+                e
+            )
+        });
+        Definition::from_toml(&text)
+    }
+
+    /// Every CSS selector in the definition
+    pub fn selectors(&self) -> Vec<&str> {
+        let event = &self.event;
+        let mut selectors = vec![self.listing.link.as_str()];
+        selectors.extend(self.listing.next_page.as_deref());
+        selectors.push(event.title.selector());
+        selectors.extend(
+            [
+                &event.subtitle,
+                &event.description,
+                &event.venue,
+                &event.tags,
+            ]
+            .into_iter()
+            .flatten()
+            .map(Field::selector),
+        );
+        selectors.push(event.datetime.field.selector());
+        if let Some(price) = &event.price {
+            selectors.push(&price.selector);
+            selectors.extend(price.free_selector.as_deref());
+        }
+        if let Some(performers) = &event.performers {
+            selectors.push(&performers.item);
+            selectors.extend(performers.name.as_deref());
+            selectors.extend(performers.role.as_deref());
+        }
+        if let Some(pieces) = &event.pieces {
+            selectors.extend([
+                pieces.item.as_str(),
+                pieces.composer.as_str(),
+                pieces.title.as_str(),
+            ]);
+        }
+        if let Some(series) = &event.series {
+            selectors.push(&series.selector);
+        }
+        selectors
+    }
+
+    /// Panic straight away if any selector is invalid, rather than halfway through scraping
+    fn check_selectors(&self) {
+        for selector in self.selectors() {
+            parse_selector(selector);
+        }
+        if let Some(price) = &self.event.price {
+            Regex::new(&price.regex)
+                .unwrap_or_else(|e| panic!("Invalid price regex {}: {}", price.regex, e));
+        }
+    }
+}
+
+pub async fn scrape(client: &http::Client, definition: &Definition) -> Vec<core::ConcertData> {
+    let mut concerts: Vec<core::ConcertData> = vec![];
+    let mut seen_urls: HashSet<String> = HashSet::new();
+
+    let paginated = definition.listing.url.contains("{page}");
+    let mut page = 1;
+    let mut next_url = Some(definition.listing.url.replace("{page}", "1"));
+    while let Some(url) = next_url {
+        debug!("Scraping {} listing from URL {}", definition.name, url);
+        let Some(html) = client.try_get_text(&url).await else {
+            break;
+        };
+        let listing = parse_listing(definition, &html, &url);
+        let event_urls: Vec<String> = listing
+            .event_urls
+            .into_iter()
+            .filter(|event_url| seen_urls.insert(event_url.clone()))
+            .collect();
+
+        let futures = event_urls.iter().map(|event_url| async move {
+            let html = client.try_get_text(event_url).await?;
+            parse_event(definition, event_url, &html)
+        });
+        concerts.extend(join_all(futures).await.into_iter().flatten());
+
+        next_url = if paginated {
+            // Stop at the first page without any new events
+            page += 1;
+            (!event_urls.is_empty())
+                .then(|| definition.listing.url.replace("{page}", &page.to_string()))
+        } else {
+            listing.next_page_url
+        };
+    }
+
+    let now = Utc::now();
+    concerts.retain(|concert| concert.datetime > now);
+    concerts.iter().for_each(core::report_concert);
+    info!(
+        "Scraped {} concerts from {}",
+        concerts.len(),
+        definition.name
+    );
+    concerts
+}
+
+/// Links found on one page of a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing {
+    pub event_urls: Vec<String>,
+    pub next_page_url: Option<String>,
+}
+
+/// Parse a page of a listing. `page_url` is the URL of the page, which relative links are resolved
+/// against.
+pub fn parse_listing(definition: &Definition, html: &str, page_url: &str) -> Listing {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let resolve = |href: &str| base.join(href).ok().map(|url| url.to_string());
+
+    let event_urls = doc
+        .select(&parse_selector(&definition.listing.link))
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(resolve)
+        .unique()
+        .collect();
+    let next_page_url = definition.listing.next_page.as_ref().and_then(|next_page| {
+        doc.select(&parse_selector(next_page))
+            .next()
+            .and_then(|elem| elem.value().attr("href"))
+            .and_then(resolve)
+    });
+
+    Listing {
+        event_urls,
+        next_page_url,
+    }
+}
+
+fn parse_prices(doc: &Html, spec: &PriceSpec) -> (Option<u32>, Option<u32>, core::Availability) {
+    let is_free = spec
+        .free_selector
+        .as_ref()
+        .is_some_and(|free| doc.select(&parse_selector(free)).next().is_some());
+    if is_free {
+        return (Some(0), Some(0), spec.free_availability);
+    }
+
+    let text = doc
+        .select(&parse_selector(&spec.selector))
+        .map(core::element_text)
+        .join("\n");
+    let price_re = Regex::new(&spec.regex).unwrap();
+    let mut prices: Vec<u32> = price_re
+        .captures_iter(&text)
+        .filter_map(|cap| {
+            let pounds = cap.get(1)?.as_str().parse::<u32>().ok()?;
+            let pence = cap.get(2).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
+            Some(pounds * 100 + pence)
+        })
+        .collect();
+    if prices.is_empty() {
+        // Free events which only say so in words
+        prices = core::parse_prices(&text);
+    }
+    let min_price = prices.iter().min().copied();
+    let max_price = match spec.min_only {
+        true if min_price != Some(0) => None,
+        _ => prices.iter().max().copied(),
+    };
+    let availability = core::parse_availability(&text, min_price, max_price);
+    (min_price, max_price, availability)
+}
+
+fn parse_performers(root: ElementRef, spec: &PerformersSpec) -> Vec<core::Performer> {
+    root.select(&parse_selector(&spec.item))
+        .filter_map(|item| {
+            let name = match &spec.name {
+                Some(name) => core::element_text(item.select(&parse_selector(name)).next()?),
+                None => core::element_text(item),
+            };
+            let instrument = spec.role.as_ref().and_then(|role| {
+                item.select(&parse_selector(role))
+                    .next()
+                    .map(core::element_text)
+                    .filter(|s| !s.is_empty())
+            });
+            (!name.is_empty()).then_some(core::Performer { name, instrument })
+        })
+        .collect()
+}
+
+fn parse_pieces(root: ElementRef, spec: &PiecesSpec) -> Vec<core::Piece> {
+    let slc_composer = parse_selector(&spec.composer);
+    let slc_title = parse_selector(&spec.title);
+    root.select(&parse_selector(&spec.item))
+        .flat_map(|item| {
+            let Some(composer) = item.select(&slc_composer).next().map(core::element_text) else {
+                return vec![];
+            };
+            if spec.skip_composers.contains(&composer) {
+                return vec![];
+            }
+            let Some(titles) = item.select(&slc_title).next().map(core::element_text) else {
+                return vec![];
+            };
+            let titles: Vec<&str> = match &spec.split {
+                Some(separator) => titles.split(separator.as_str()).collect(),
+                None => vec![titles.as_str()],
+            };
+            titles
+                .into_iter()
+                .map(|title| title.trim())
+                .filter(|title| !title.is_empty())
+                .map(|title| core::Piece {
+                    composer: composer.clone(),
+                    title: title.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Parse an event page using a definition. Events without a title or venue, or whose date and
+/// time can't be read, are skipped.
+pub fn parse_event(
+    definition: &Definition,
+    event_url: &str,
+    html: &str,
+) -> Option<core::ConcertData> {
+    let doc = Html::parse_document(html);
+    let root = doc.root_element();
+    let spec = &definition.event;
+
+    let Some(title) = spec.title.first(root) else {
+        warn!("Skipping {}, which has no title", event_url);
+        return None;
+    };
+    let subtitle = spec.subtitle.as_ref().and_then(|field| field.first(root));
+    let description = spec
+        .description
+        .as_ref()
+        .map(|field| field.all(root).join("\n"))
+        .unwrap_or_default();
+
+    let Some(datetime_text) = spec.datetime.field.first(root) else {
+        warn!("Skipping {}, which has no datetime", event_url);
+        return None;
+    };
+    let datetime = if spec.datetime.formats.is_empty() {
+        datetime::parse_london(&datetime_text)
+    } else {
+        datetime::parse_with_formats(&datetime_text, &spec.datetime.formats)
+    }
+    .inspect_err(|e| warn!("Skipping {}: {}", event_url, e))
+    .ok()?;
+
+    let Some(venue) = spec
+        .venue
+        .as_ref()
+        .and_then(|field| field.first(root))
+        .or(definition.venue.clone())
+    else {
+        warn!("Skipping {}, which has no venue", event_url);
+        return None;
+    };
+
+    let (min_price, max_price, availability) = match &spec.price {
+        Some(price_spec) => parse_prices(&doc, price_spec),
+        None => (None, None, core::Availability::Ticketed),
+    };
+
+    let performers = spec
+        .performers
+        .as_ref()
+        .map(|performers| parse_performers(root, performers))
+        .unwrap_or_default();
+    let pieces = spec
+        .pieces
+        .as_ref()
+        .map(|pieces| parse_pieces(root, pieces))
+        .unwrap_or_default();
+
+    let series: Vec<core::Series> = match &spec.series {
+        None => vec![],
+        Some(series_spec) => root
+            .select(&parse_selector(&series_spec.selector))
+            .map(|elem| core::Series {
+                name: core::element_text(elem),
+                organiser: series_spec.organiser.clone(),
+                start_date: None,
+                end_date: None,
+                url: elem
+                    .value()
+                    .attr("href")
+                    .and_then(|href| reqwest::Url::parse(event_url).ok()?.join(href).ok())
+                    .map(|url| url.to_string()),
+            })
+            .collect(),
+    };
+
+    let mut tags: Vec<String> = spec
+        .tags
+        .as_ref()
+        .map(|field| field.all(root))
+        .unwrap_or_default();
+    if spec.tags_from_url {
+        if let Some(slug) = event_url.trim_end_matches('/').rsplit('/').next() {
+            tags.push(slug.replace('-', " "));
+        }
+    }
+    tags.extend(series.iter().map(|s| s.name.clone()));
+
+    let mut concert = core::ConcertData {
+        datetime,
+        url: event_url.to_string(),
+        performers,
+        title,
+        subtitle,
+        description: Some(description.clone()),
+        programme_pdf_url: None,
+        programme_text: None,
+        pieces,
+        venue,
+        category: core::Category::Other,
+        min_price,
+        max_price,
+        availability,
+        media: media::find_media(&description, datetime, event_url),
+        is_wigmore_u35: false,
+        series,
+        production: None,
+    };
+    concert.category = classify::classify(&concert, &tags);
+    Some(concert)
+}
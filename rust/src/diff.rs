@@ -0,0 +1,324 @@
+use crate::core::Concert;
+use chrono_tz::Europe::London;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Differences between two sets of concerts
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcertsDiff {
+    pub added: Vec<Concert>,
+    pub removed: Vec<Concert>,
+    pub changed: Vec<ConcertChange>,
+}
+
+/// A concert which is present in both the old and new sets of concerts, but with some fields
+/// changed
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcertChange {
+    pub old_id: String,
+    pub new_id: String,
+    pub title: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// A single changed field of a concert. The old and new values are given as JSON values, i.e. the
+/// same way they appear in `concerts.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// The parts of a concert which stay the same when it's moved to a different time, unlike its ID,
+/// which contains the timestamp. Concerts with the same listing are taken to be the same concert.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Listing {
+    pub url: String,
+    pub venue: String,
+    pub title: String,
+}
+
+impl Listing {
+    pub fn of(concert: &Concert) -> Listing {
+        Listing {
+            url: concert.concert.url.clone(),
+            venue: concert.concert.venue.clone(),
+            title: concert.concert.title.clone(),
+        }
+    }
+}
+
+impl ConcertsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two sets of concerts.
+///
+/// Concerts are primarily matched up using their IDs. However, the ID contains the concert's
+/// timestamp, so a concert which has been moved to a different time gets a new ID. To catch these,
+/// any leftover concerts which have the same URL, venue, and title are also matched up (in
+/// chronological order), and reported as changed rather than as removed + added.
+pub fn diff(old: &[Concert], new: &[Concert]) -> ConcertsDiff {
+    let old_by_id: HashMap<&str, &Concert> = old.iter().map(|c| (c.id.as_str(), c)).collect();
+    let new_ids: HashSet<&str> = new.iter().map(|c| c.id.as_str()).collect();
+
+    let mut changed = vec![];
+    let mut unmatched_new = vec![];
+    for new_concert in new {
+        match old_by_id.get(new_concert.id.as_str()) {
+            Some(old_concert) => {
+                let changes = diff_fields(old_concert, new_concert);
+                if !changes.is_empty() {
+                    changed.push(ConcertChange {
+                        old_id: old_concert.id.clone(),
+                        new_id: new_concert.id.clone(),
+                        title: new_concert.concert.title.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => unmatched_new.push(new_concert),
+        }
+    }
+    let mut unmatched_old = old
+        .iter()
+        .filter(|c| !new_ids.contains(c.id.as_str()))
+        .collect::<Vec<&Concert>>();
+
+    // Try to match up rescheduled concerts
+    let mut added = vec![];
+    for new_concert in unmatched_new {
+        let listing = Listing::of(new_concert);
+        let position = unmatched_old
+            .iter()
+            .position(|old_concert| Listing::of(old_concert) == listing);
+        match position {
+            Some(i) => {
+                let old_concert = unmatched_old.remove(i);
+                changed.push(ConcertChange {
+                    old_id: old_concert.id.clone(),
+                    new_id: new_concert.id.clone(),
+                    title: new_concert.concert.title.clone(),
+                    changes: diff_fields(old_concert, new_concert),
+                });
+            }
+            None => added.push(new_concert.clone()),
+        }
+    }
+    let removed = unmatched_old.into_iter().cloned().collect();
+
+    ConcertsDiff {
+        added,
+        removed,
+        changed,
+    }
+}
+
+/// Compare the fields of two concerts. Fields which aren't serialised (i.e. the programme text)
+/// aren't compared, but if the programme changes, so does `programme_pdf_url`.
+fn diff_fields(old: &Concert, new: &Concert) -> Vec<FieldChange> {
+    let old_value = serde_json::to_value(&old.concert).unwrap();
+    let new_value = serde_json::to_value(&new.concert).unwrap();
+    let (Value::Object(old_fields), Value::Object(new_fields)) = (old_value, new_value) else {
+        panic!("ConcertData did not serialise to a JSON object");
+    };
+
+    let mut changes = vec![];
+    for (field, new_field_value) in new_fields {
+        let old_field_value = old_fields.get(&field).cloned().unwrap_or(Value::Null);
+        if old_field_value != new_field_value {
+            changes.push(FieldChange {
+                field,
+                old: old_field_value,
+                new: new_field_value,
+            });
+        }
+    }
+    changes
+}
+
+fn describe_concert(c: &Concert) -> String {
+    format!(
+        "{} | {} | {} ({})",
+        c.concert
+            .datetime
+            .with_timezone(&London)
+            .format("%Y-%m-%d %H:%M"),
+        c.concert.venue,
+        c.concert.title,
+        c.id
+    )
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "(none)".to_string(),
+        // Pieces and performers
+        Value::Object(obj) => match (obj.get("composer"), obj.get("title"), obj.get("name")) {
+            (Some(composer), Some(title), _) => {
+                format!("{}: {}", describe_value(composer), describe_value(title))
+            }
+            (_, _, Some(name)) => match obj.get("instrument") {
+                Some(Value::String(instrument)) => {
+                    format!("{} ({})", describe_value(name), instrument)
+                }
+                _ => describe_value(name),
+            },
+            _ => value.to_string(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.old, &self.new) {
+            // For lists (performers, pieces) it's more useful to see which elements changed
+            (Value::Array(old_items), Value::Array(new_items)) => {
+                writeln!(f, "    {}:", self.field)?;
+                for item in old_items.iter().filter(|item| !new_items.contains(item)) {
+                    writeln!(f, "      - {}", describe_value(item))?;
+                }
+                for item in new_items.iter().filter(|item| !old_items.contains(item)) {
+                    writeln!(f, "      + {}", describe_value(item))?;
+                }
+                Ok(())
+            }
+            _ => writeln!(
+                f,
+                "    {}: {} -> {}",
+                self.field,
+                describe_value(&self.old),
+                describe_value(&self.new)
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ConcertsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} added, {} removed, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )?;
+        if !self.added.is_empty() {
+            writeln!(f, "\nAdded:")?;
+            for c in &self.added {
+                writeln!(f, "  + {}", describe_concert(c))?;
+            }
+        }
+        if !self.removed.is_empty() {
+            writeln!(f, "\nRemoved:")?;
+            for c in &self.removed {
+                writeln!(f, "  - {}", describe_concert(c))?;
+            }
+        }
+        if !self.changed.is_empty() {
+            writeln!(f, "\nChanged:")?;
+            for change in &self.changed {
+                if change.old_id == change.new_id {
+                    writeln!(f, "  ~ {} ({})", change.title, change.new_id)?;
+                } else {
+                    writeln!(
+                        f,
+                        "  ~ {} ({} -> {})",
+                        change.title, change.old_id, change.new_id
+                    )?;
+                }
+                for field_change in &change.changes {
+                    write!(f, "{}", field_change)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{add_id_to_concert, ConcertData};
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn concert(title: &str, day: u32, min_price: Option<u32>) -> Concert {
+        add_id_to_concert(ConcertData {
+            datetime: Utc.with_ymd_and_hms(2026, 3, day, 19, 30, 0).unwrap(),
+            url: format!("https://example.com/{}", title.to_lowercase()),
+            title: title.to_string(),
+            venue: "Wigmore Hall".to_string(),
+            min_price,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn added_removed_and_changed() {
+        let old = vec![
+            concert("Schubert", 10, Some(1500)),
+            concert("Brahms", 11, None),
+        ];
+        let new = vec![
+            concert("Schubert", 10, Some(2000)),
+            concert("Ravel", 12, None),
+        ];
+        let d = diff(&old, &new);
+        assert_eq!(d.added, vec![new[1].clone()]);
+        assert_eq!(d.removed, vec![old[1].clone()]);
+        assert_eq!(d.changed.len(), 1);
+        assert_eq!(d.changed[0].old_id, d.changed[0].new_id);
+        let change = &d.changed[0].changes[0];
+        assert_eq!(change.field, "min_price");
+        assert_eq!((&change.old, &change.new), (&json!(1500), &json!(2000)));
+        assert_eq!(change.to_string(), "    min_price: 1500 -> 2000\n");
+    }
+
+    #[test]
+    fn rescheduled_concerts_are_changed() {
+        let old = vec![concert("Schubert", 10, None)];
+        let new = vec![concert("Schubert", 17, None)];
+        let d = diff(&old, &new);
+        assert!(d.added.is_empty() && d.removed.is_empty());
+        assert_eq!(d.changed[0].old_id, old[0].id);
+        assert_eq!(d.changed[0].new_id, new[0].id);
+        assert_eq!(d.changed[0].changes[0].field, "datetime");
+    }
+
+    #[test]
+    fn programme_text_is_ignored() {
+        let old = vec![concert("Schubert", 10, None)];
+        let mut new = old.clone();
+        new[0].concert.programme_text = Some("Notes".to_string());
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn removals_do_not_depend_on_the_current_time() {
+        // e.g. comparing two snapshots from last year
+        let old = vec![concert("Schubert", 10, None), concert("Brahms", 11, None)];
+        let new = vec![concert("Brahms", 11, None)];
+        assert_eq!(diff(&old, &new).removed, vec![old[0].clone()]);
+    }
+
+    #[test]
+    fn list_changes_show_elements() {
+        let change = FieldChange {
+            field: "performers".to_string(),
+            old: json!([{"name": "A", "instrument": "piano"}]),
+            new: json!([{"name": "B", "instrument": null}]),
+        };
+        assert_eq!(
+            change.to_string(),
+            "    performers:\n      - A (piano)\n      + B\n"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::core;
+use crate::http;
+use crate::media;
+use crate::opera;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Europe::London;
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{info, warn};
+use scraper::{ElementRef, Html, Selector};
+
+pub const ENO_URL: &str = "https://www.eno.org/whats-on/";
+
+/// ENO performs everything at the London Coliseum
+pub const VENUE: &str = "London Coliseum";
+
+/// Selectors for the listing and production pages, which the healthcheck probes as well
+pub mod selectors {
+    pub const PRODUCTION_LINK: &str = "a.production-card__link";
+    pub const TITLE: &str = "h1.production-hero__title";
+    pub const COMPOSER: &str = "p.production-hero__composer";
+    pub const SUBTITLE: &str = "p.production-hero__strapline";
+    pub const DESCRIPTION: &str = "div.production-intro p";
+    pub const PRICE: &str = "p.production-hero__price";
+    pub const INFO_TERM: &str = "dl.production-info > dt";
+    pub const CAST_MEMBER: &str = "ul.cast > li";
+    pub const CAST_ROLE: &str = "span.cast__role";
+    pub const CAST_NAME: &str = "span.cast__name";
+    pub const PERFORMANCE: &str = "table.performances tbody tr time";
+}
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping English National Opera from URL: {}", ENO_URL);
+    let Some(html) = client.try_get_text(ENO_URL).await else {
+        return vec![];
+    };
+
+    let futures = parse_listing(&html, ENO_URL)
+        .into_iter()
+        .map(|url| async move {
+            match client.try_get_text(&url).await {
+                Some(html) => parse_production(&url, &html),
+                None => vec![],
+            }
+        });
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = join_all(futures)
+        .await
+        .into_iter()
+        .flatten()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!("Scraped {} performances from ENO", concerts.len());
+    concerts
+}
+
+/// URLs of all the productions in the listing. `page_url` is the URL of the listing, which
+/// relative links are resolved against.
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<String> {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let slc_link = Selector::parse(selectors::PRODUCTION_LINK).unwrap();
+    // Links without an href can't be followed, so they are left out
+    doc.select(&slc_link)
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(|href| base.join(href).ok())
+        .map(|url| url.to_string())
+        .unique()
+        .collect()
+}
+
+/// A member of the cast (or a conductor) as listed on the production page. When roles are shared
+/// between singers, each of them has a `data-dates` attribute listing the dates they sing on,
+/// e.g. "2026-02-01 2026-02-07"; otherwise they sing at every performance.
+struct ListedCastMember {
+    role: String,
+    name: String,
+    dates: Option<Vec<NaiveDate>>,
+}
+
+impl ListedCastMember {
+    fn performs_on(&self, date: NaiveDate) -> bool {
+        self.dates
+            .as_ref()
+            .is_none_or(|dates| dates.contains(&date))
+    }
+}
+
+/// Cast members without a role or a name are left out
+fn parse_cast_member(li: ElementRef) -> Option<ListedCastMember> {
+    let slc_role = Selector::parse(selectors::CAST_ROLE).unwrap();
+    let slc_name = Selector::parse(selectors::CAST_NAME).unwrap();
+    Some(ListedCastMember {
+        role: core::element_text(li.select(&slc_role).next()?),
+        name: core::element_text(li.select(&slc_name).next()?),
+        dates: li.value().attr("data-dates").map(|dates| {
+            dates
+                .split_whitespace()
+                .filter_map(|date| {
+                    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .inspect_err(|e| warn!("Skipping unexpected cast date {}: {}", date, e))
+                        .ok()
+                })
+                .collect()
+        }),
+    })
+}
+
+/// Parse a production page into one concert for every performance, each with the cast and
+/// conductor for that date. Productions without a title or composer are skipped.
+pub fn parse_production(url: &str, html: &str) -> Vec<core::ConcertData> {
+    let doc = Html::parse_document(html);
+
+    let slc_title = Selector::parse(selectors::TITLE).unwrap();
+    let slc_composer = Selector::parse(selectors::COMPOSER).unwrap();
+    let (Some(title), Some(composer)) = (
+        doc.select(&slc_title).next().map(core::element_text),
+        doc.select(&slc_composer).next().map(core::element_text),
+    ) else {
+        warn!("Skipping {}, which has no title or composer", url);
+        return vec![];
+    };
+    let slc_subtitle = Selector::parse(selectors::SUBTITLE).unwrap();
+    let subtitle = doc
+        .select(&slc_subtitle)
+        .next()
+        .map(core::element_text)
+        .filter(|s| !s.is_empty());
+
+    let slc_description = Selector::parse(selectors::DESCRIPTION).unwrap();
+    let description = doc
+        .select(&slc_description)
+        .map(core::element_text)
+        .join("\n");
+
+    // ENO advertises "Tickets from £10", so there is usually only a minimum price
+    let slc_price = Selector::parse(selectors::PRICE).unwrap();
+    let prices = doc
+        .select(&slc_price)
+        .next()
+        .map(|elem| core::parse_prices(&core::element_text(elem)))
+        .unwrap_or_default();
+    let min_price = prices.iter().min().copied();
+    let max_price = match prices.len() {
+        0 | 1 => None,
+        _ => prices.iter().max().copied(),
+    };
+
+    // Facts about the production are given as a definition list, e.g. "Running time" followed by
+    // "3 hours, including one interval"
+    let slc_info_term = Selector::parse(selectors::INFO_TERM).unwrap();
+    let info: Vec<(String, String)> = doc
+        .select(&slc_info_term)
+        .filter_map(|dt| {
+            let dd = dt
+                .next_siblings()
+                .filter_map(ElementRef::wrap)
+                .find(|elem| elem.value().name() == "dd")?;
+            Some((
+                core::element_text(dt).to_lowercase(),
+                core::element_text(dd),
+            ))
+        })
+        .collect();
+    let get_info = |term: &str| {
+        info.iter()
+            .find(|(t, _)| t == term)
+            .map(|(_, value)| value.clone())
+    };
+    let (running_time_minutes, n_intervals) = get_info("running time")
+        .map(|text| opera::parse_running_time(&text))
+        .unwrap_or_default();
+    let (language, surtitles) = get_info("language")
+        .map(|text| opera::parse_language(&text))
+        .unwrap_or_default();
+    let director = get_info("director");
+    let default_conductor = get_info("conductor");
+
+    let slc_cast = Selector::parse(selectors::CAST_MEMBER).unwrap();
+    let listed_cast: Vec<ListedCastMember> = doc
+        .select(&slc_cast)
+        .filter_map(parse_cast_member)
+        .collect();
+
+    let slc_performance = Selector::parse(selectors::PERFORMANCE).unwrap();
+    doc.select(&slc_performance)
+        .filter_map(|elem| {
+            let Some(datetime_str) = elem.value().attr("datetime") else {
+                warn!("Skipping performance of {} without a datetime", url);
+                return None;
+            };
+            let datetime = match DateTime::parse_from_rfc3339(datetime_str) {
+                Ok(datetime) => datetime.with_timezone(&Utc),
+                Err(e) => {
+                    warn!(
+                        "Skipping performance of {} at unexpected datetime {}: {}",
+                        url, datetime_str, e
+                    );
+                    return None;
+                }
+            };
+            let date = datetime.with_timezone(&London).date_naive();
+
+            // Conductors are listed with the cast when they only conduct some performances
+            let (conductors, cast): (Vec<&ListedCastMember>, Vec<&ListedCastMember>) = listed_cast
+                .iter()
+                .filter(|member| member.performs_on(date))
+                .partition(|member| member.role.eq_ignore_ascii_case("conductor"));
+            let production_details = core::Production {
+                title: title.clone(),
+                composer: composer.clone(),
+                conductor: conductors
+                    .first()
+                    .map(|member| member.name.clone())
+                    .or(default_conductor.clone()),
+                director: director.clone(),
+                cast: cast
+                    .into_iter()
+                    .map(|member| core::CastMember {
+                        role: member.role.clone(),
+                        name: member.name.clone(),
+                    })
+                    .collect(),
+                running_time_minutes,
+                n_intervals,
+                language: language.clone(),
+                surtitles: surtitles.clone(),
+            };
+
+            Some(core::ConcertData {
+                datetime,
+                url: url.to_string(),
+                performers: opera::performers(&production_details),
+                title: title.clone(),
+                subtitle: subtitle.clone(),
+                description: Some(description.clone()),
+                programme_pdf_url: None,
+                programme_text: None,
+                pieces: vec![core::Piece {
+                    composer: composer.clone(),
+                    title: title.clone(),
+                }],
+                venue: VENUE.to_string(),
+                category: core::Category::Opera,
+                min_price,
+                max_price,
+                media: media::find_media(&description, datetime, url),
+                availability: core::Availability::Ticketed,
+                is_wigmore_u35: false,
+                series: vec![],
+                production: Some(production_details),
+            })
+        })
+        .collect()
+}
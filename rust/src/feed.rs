@@ -0,0 +1,401 @@
+use crate::core::{slugify, Concert};
+use crate::diff::Listing;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use chrono_tz::Europe::London;
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use itertools::Itertools;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::path::Path;
+
+const SITE_URL: &str = "https://penelopeysm.github.io/london_classical/";
+
+/// How long a concert stays in the feeds after it was first seen, so that someone whose feed
+/// reader doesn't check after every run still sees it
+pub const FEED_WINDOW: Duration = Duration::days(14);
+
+/// When each upcoming concert was first seen, keyed by concert ID. This is kept between runs (it
+/// isn't part of concerts.json), so that the feeds can contain everything announced recently
+/// rather than only what was new in the latest run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FirstSeen {
+    pub concerts: BTreeMap<String, Seen>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Seen {
+    pub first_seen: DateTime<Utc>,
+    /// When the concert takes place
+    pub datetime: DateTime<Utc>,
+    #[serde(flatten)]
+    pub listing: Listing,
+}
+
+impl FirstSeen {
+    /// Returns `None` if the file does not exist
+    pub fn read(path: &Path) -> Option<FirstSeen> {
+        let text = std::fs::read_to_string(path).ok()?;
+        Some(
+            serde_json::from_str(&text)
+                .unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e)),
+        )
+    }
+
+    pub fn write(&self, path: &Path) {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir).unwrap();
+        }
+        write_file(path, &serde_json::to_string_pretty(self).unwrap());
+    }
+
+    /// Start from concerts which were already listed, e.g. in the previous concerts.json. When
+    /// they were first seen isn't known, so they're taken to be too old to be in the feeds.
+    pub fn already_seen(concerts: &[Concert]) -> FirstSeen {
+        FirstSeen {
+            concerts: concerts
+                .iter()
+                .map(|c| (c.id.clone(), Seen::new(c, DateTime::<Utc>::MIN_UTC)))
+                .collect(),
+        }
+    }
+
+    /// Record the concerts in `current` which haven't been seen before as being first seen at
+    /// `now`, and forget about those which are over.
+    ///
+    /// Concerts which aren't listed in `current` are otherwise remembered, because they may only
+    /// be missing for a while (e.g. because their source failed or was disabled), and shouldn't
+    /// be announced again when they come back. A concert which has been moved to a different time
+    /// (which gives it a new ID) is matched up by its URL, venue, and title, the same way as in
+    /// `diff::diff`, and keeps the time it was first seen.
+    pub fn update(&mut self, current: &[Concert], now: DateTime<Utc>) {
+        let current_ids: HashSet<&str> = current.iter().map(|c| c.id.as_str()).collect();
+        self.concerts
+            .retain(|id, seen| current_ids.contains(id.as_str()) || seen.datetime >= now);
+        for concert in current {
+            if self.concerts.contains_key(&concert.id) {
+                continue;
+            }
+            let listing = Listing::of(concert);
+            let rescheduled = self
+                .concerts
+                .iter()
+                .filter(|(id, seen)| !current_ids.contains(id.as_str()) && seen.listing == listing)
+                .min_by_key(|(_, seen)| seen.datetime)
+                .map(|(id, _)| id.clone());
+            let first_seen = match rescheduled {
+                Some(old_id) => self.concerts.remove(&old_id).unwrap().first_seen,
+                None => now,
+            };
+            self.concerts
+                .insert(concert.id.clone(), Seen::new(concert, first_seen));
+        }
+    }
+
+    /// The concerts in `current` which were first seen at `now`, i.e. which are new in the run
+    /// which `update` was last called for
+    pub fn new_at<'a>(&self, current: &'a [Concert], now: DateTime<Utc>) -> Vec<&'a Concert> {
+        current
+            .iter()
+            .filter(|c| {
+                self.concerts
+                    .get(&c.id)
+                    .is_some_and(|seen| seen.first_seen == now)
+            })
+            .collect()
+    }
+
+    /// The concerts in `current` which were first seen within `FEED_WINDOW` of `now`, most
+    /// recently seen first, together with when they were first seen
+    pub fn recent<'a>(
+        &self,
+        current: &'a [Concert],
+        now: DateTime<Utc>,
+    ) -> Vec<(&'a Concert, DateTime<Utc>)> {
+        let mut recent: Vec<(&Concert, DateTime<Utc>)> = current
+            .iter()
+            .filter_map(|c| self.concerts.get(&c.id).map(|seen| (c, seen.first_seen)))
+            .filter(|(_, seen)| *seen > now - FEED_WINDOW)
+            .collect();
+        recent.sort_by(|(a, a_seen), (b, b_seen)| {
+            b_seen
+                .cmp(a_seen)
+                .then(a.concert.datetime.cmp(&b.concert.datetime))
+        });
+        recent
+    }
+}
+
+impl Seen {
+    fn new(concert: &Concert, first_seen: DateTime<Utc>) -> Seen {
+        Seen {
+            first_seen,
+            datetime: concert.concert.datetime,
+            listing: Listing::of(concert),
+        }
+    }
+}
+
+/// Generate an Atom feed containing the given concerts, each with the time it was first seen.
+/// `feed_name` is used to build the feed's ID and self link, so it should be unique across all
+/// feeds.
+pub fn atom_feed(
+    title: &str,
+    feed_name: &str,
+    updated: DateTime<Utc>,
+    concerts: &[(&Concert, DateTime<Utc>)],
+) -> String {
+    let updated_str = updated.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", encode_text(title)));
+    xml.push_str(&format!(
+        "  <id>tag:penelopeysm.github.io,2024:london_classical/feeds/{}</id>\n",
+        encode_text(feed_name)
+    ));
+    xml.push_str(&format!(
+        "  <link href=\"{}\"/>\n",
+        encode_double_quoted_attribute(SITE_URL)
+    ));
+    xml.push_str(&format!(
+        "  <link rel=\"self\" href=\"{}feeds/{}.xml\"/>\n",
+        encode_double_quoted_attribute(SITE_URL),
+        encode_double_quoted_attribute(feed_name)
+    ));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated_str));
+    xml.push_str("  <author><name>london_classical</name></author>\n");
+    for (concert, first_seen) in concerts {
+        xml.push_str(&atom_entry(
+            concert,
+            &first_seen.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ));
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// Generate a single Atom entry for a concert
+fn atom_entry(concert: &Concert, updated_str: &str) -> String {
+    let c = &concert.concert;
+    let london_datetime = c.datetime.with_timezone(&London);
+    let date_str = london_datetime.format("%A %-d %B %Y, %H:%M").to_string();
+
+    // The content is HTML, which then itself has to be escaped to go inside the XML
+    let mut content = String::new();
+    if let Some(subtitle) = &c.subtitle {
+        content.push_str(&format!("<p><em>{}</em></p>", encode_text(subtitle)));
+    }
+    content.push_str(&format!(
+        "<p>{}<br/>{}</p>",
+        encode_text(&date_str),
+        encode_text(&c.venue)
+    ));
+    if !c.performers.is_empty() {
+        content.push_str("<h3>Performers</h3><ul>");
+        for performer in &c.performers {
+            match &performer.instrument {
+                Some(instrument) => content.push_str(&format!(
+                    "<li>{} ({})</li>",
+                    encode_text(&performer.name),
+                    encode_text(instrument)
+                )),
+                None => content.push_str(&format!("<li>{}</li>", encode_text(&performer.name))),
+            }
+        }
+        content.push_str("</ul>");
+    }
+    if !c.pieces.is_empty() {
+        content.push_str("<h3>Programme</h3><ul>");
+        for piece in &c.pieces {
+            if piece.composer.is_empty() {
+                content.push_str(&format!("<li>{}</li>", encode_text(&piece.title)));
+            } else {
+                content.push_str(&format!(
+                    "<li>{}: {}</li>",
+                    encode_text(&piece.composer),
+                    encode_text(&piece.title)
+                ));
+            }
+        }
+        content.push_str("</ul>");
+    }
+    content.push_str(&format!(
+        "<p><a href=\"{}\">More information</a></p>",
+        encode_double_quoted_attribute(&c.url)
+    ));
+
+    let mut entry = String::new();
+    entry.push_str("  <entry>\n");
+    entry.push_str(&format!(
+        "    <title>{}: {}</title>\n",
+        encode_text(&london_datetime.format("%-d %b %Y").to_string()),
+        encode_text(&c.title)
+    ));
+    entry.push_str(&format!(
+        "    <id>tag:penelopeysm.github.io,2024:london_classical/concerts/{}</id>\n",
+        encode_text(&concert.id)
+    ));
+    entry.push_str(&format!(
+        "    <link href=\"{}\"/>\n",
+        encode_double_quoted_attribute(&c.url)
+    ));
+    entry.push_str(&format!("    <updated>{}</updated>\n", updated_str));
+    entry.push_str(&format!(
+        "    <category term=\"{}\" label=\"{}\"/>\n",
+        encode_double_quoted_attribute(&slugify(&c.venue)),
+        encode_double_quoted_attribute(&c.venue)
+    ));
+    entry.push_str(&format!(
+        "    <content type=\"html\">{}</content>\n",
+        encode_text(&content)
+    ));
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+/// Write an Atom feed of all concerts in `current` which were first seen recently (see
+/// `FirstSeen::recent`) to `<dir>/all.xml`, plus one feed per venue to `<dir>/<venue-slug>.xml`.
+pub fn write_feeds(dir: &Path, first_seen: &FirstSeen, current: &[Concert], now: DateTime<Utc>) {
+    create_dir_all(dir).unwrap();
+    let added = first_seen.recent(current, now);
+    info!(
+        "Found {} concerts announced in the last {} days",
+        added.len(),
+        FEED_WINDOW.num_days()
+    );
+
+    let all_feed = atom_feed("New classical concerts in London", "all", now, &added);
+    write_file(&dir.join("all.xml"), &all_feed);
+
+    // Generate a feed for every venue we know about, even if there aren't any new concerts there,
+    // so that feed readers don't see a 404
+    let venues = current.iter().map(|c| c.concert.venue.as_str()).unique();
+    for venue in venues {
+        let slug = slugify(venue);
+        let venue_added = added
+            .iter()
+            .filter(|(c, _)| c.concert.venue == venue)
+            .copied()
+            .collect::<Vec<(&Concert, DateTime<Utc>)>>();
+        let feed = atom_feed(
+            &format!("New concerts at {}", venue),
+            &slug,
+            now,
+            &venue_added,
+        );
+        write_file(&dir.join(format!("{}.xml", slug)), &feed);
+    }
+}
+
+fn write_file(path: &Path, contents: &str) {
+    let mut file = File::create(path).unwrap();
+    file.write_all(contents.as_bytes()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ConcertData;
+    use chrono::TimeZone;
+
+    fn concert(id: &str, venue: &str) -> Concert {
+        Concert {
+            id: id.to_string(),
+            concert: ConcertData {
+                datetime: Utc.with_ymd_and_hms(2026, 3, 1, 19, 30, 0).unwrap(),
+                url: format!("https://example.com/{}", id),
+                title: format!("Concert {} & friends", id),
+                venue: venue.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn day(n: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, n, 6, 0, 0).unwrap()
+    }
+
+    fn ids(recent: &[(&Concert, DateTime<Utc>)]) -> Vec<String> {
+        recent.iter().map(|(c, _)| c.id.clone()).collect()
+    }
+
+    #[test]
+    fn concerts_stay_in_the_feed_for_the_window() {
+        let old = vec![concert("a", "Wigmore Hall")];
+        let mut first_seen = FirstSeen::already_seen(&old);
+
+        let run1 = vec![concert("a", "Wigmore Hall"), concert("b", "Wigmore Hall")];
+        first_seen.update(&run1, day(1));
+        assert_eq!(ids(&first_seen.recent(&run1, day(1))), vec!["b"]);
+        assert_eq!(first_seen.new_at(&run1, day(1)), vec![&run1[1]]);
+
+        // Someone who missed this run still sees "b" after the next one
+        let run2 = vec![
+            concert("a", "Wigmore Hall"),
+            concert("b", "Wigmore Hall"),
+            concert("c", "Kings Place"),
+        ];
+        first_seen.update(&run2, day(3));
+        assert_eq!(ids(&first_seen.recent(&run2, day(3))), vec!["c", "b"]);
+        assert_eq!(first_seen.concerts["b"].first_seen, day(1));
+
+        // Until it's too old
+        assert_eq!(ids(&first_seen.recent(&run2, day(16))), vec!["c"]);
+    }
+
+    #[test]
+    fn unlisted_concerts_are_remembered_until_they_are_over() {
+        let mut first_seen = FirstSeen::default();
+        first_seen.update(&[concert("a", "Wigmore Hall")], day(1));
+
+        // "a" is missing for a run, e.g. because its source failed, but isn't new when it's back
+        first_seen.update(&[concert("b", "Wigmore Hall")], day(2));
+        let run3 = vec![concert("a", "Wigmore Hall"), concert("b", "Wigmore Hall")];
+        first_seen.update(&run3, day(3));
+        assert_eq!(first_seen.concerts["a"].first_seen, day(1));
+        assert!(first_seen.new_at(&run3, day(3)).is_empty());
+
+        let after = Utc.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap();
+        first_seen.update(&[], after);
+        assert!(first_seen.concerts.is_empty());
+    }
+
+    #[test]
+    fn rescheduled_concerts_are_not_new() {
+        let mut first_seen = FirstSeen::default();
+        first_seen.update(&[concert("a", "Wigmore Hall")], day(1));
+
+        let mut moved = concert("a", "Wigmore Hall");
+        moved.id = "a-moved".to_string();
+        moved.concert.datetime = Utc.with_ymd_and_hms(2026, 3, 8, 19, 30, 0).unwrap();
+        let run2 = vec![moved];
+        first_seen.update(&run2, day(20));
+        assert_eq!(
+            first_seen.concerts.keys().collect::<Vec<_>>(),
+            vec!["a-moved"]
+        );
+        assert_eq!(first_seen.concerts["a-moved"].first_seen, day(1));
+        assert!(first_seen.recent(&run2, day(20)).is_empty());
+    }
+
+    #[test]
+    fn feeds_are_written_for_every_venue() {
+        let dir = std::env::temp_dir().join(format!("ldncls_feeds_{}", std::process::id()));
+        let current = vec![concert("a", "Wigmore Hall"), concert("b", "Kings Place")];
+        let mut first_seen = FirstSeen::already_seen(&current[..1]);
+        first_seen.update(&current, day(1));
+        write_feeds(&dir, &first_seen, &current, day(1));
+
+        let all = std::fs::read_to_string(dir.join("all.xml")).unwrap();
+        assert!(all.contains("Concert b &amp; friends"));
+        assert!(!all.contains("Concert a"));
+        assert!(all.contains("<updated>2026-01-01T06:00:00Z</updated>"));
+        let wigmore = std::fs::read_to_string(dir.join("wigmore-hall.xml")).unwrap();
+        assert!(!wigmore.contains("<entry>"));
+        assert!(wigmore.contains("feeds/wigmore-hall.xml"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A page which was fetched during a recorded run. Each one is saved as its own file, named after
+/// its URL, so that scrapers fetching pages concurrently don't get in each other's way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub url: String,
+    pub fetched_at: DateTime<Utc>,
+    pub body: String,
+}
+
+impl Recording {
+    fn file_name(url: &str) -> String {
+        let hash: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("{}.json", hash)
+    }
+
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(
+            dir.join(Recording::file_name(&self.url)),
+            serde_json::to_string(self).unwrap(),
+        )
+    }
+}
+
+/// Read every page recorded in a directory, keyed by URL
+pub fn read_recordings(dir: &Path) -> HashMap<String, Recording> {
+    let entries = std::fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read recordings in {}: {}", dir.display(), e));
+    entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(&path).unwrap();
+            serde_json::from_str::<Recording>(&text).ok()
+        })
+        .map(|recording| (recording.url.clone(), recording))
+        .collect()
+}
+
+/// A saved page which the tests parse. These are listed in `tests/fixtures/fixtures.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    /// Relative to the fixtures directory, e.g. "kingsplace/event.html"
+    pub path: String,
+    /// URL the page was fetched from, which is passed to the parser
+    pub url: String,
+    /// Which parser the page is for (see `tests/snapshots.rs`)
+    pub parser: String,
+    /// For pages which there are lots of (e.g. individual events), any page whose URL starts with
+    /// this can be used if `url` itself wasn't recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    fixture: Vec<Fixture>,
+}
+
+/// Directory with the saved pages for tests
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+pub fn read_manifest(fixtures_dir: &Path) -> Vec<Fixture> {
+    let path = fixtures_dir.join("fixtures.toml");
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+    toml::from_str::<Manifest>(&text)
+        .unwrap_or_else(|e| panic!("Invalid fixture manifest {}: {}", path.display(), e))
+        .fixture
+}
+
+pub fn write_manifest(fixtures_dir: &Path, fixtures: &[Fixture]) {
+    let manifest = Manifest {
+        fixture: fixtures.to_vec(),
+    };
+    std::fs::write(
+        fixtures_dir.join("fixtures.toml"),
+        toml::to_string(&manifest).unwrap(),
+    )
+    .unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Refresh {
+    /// The page was recorded and differs from the saved copy. If it came from a different URL
+    /// (see `Fixture::url_prefix`), that's the new URL.
+    Updated {
+        new_url: Option<String>,
+    },
+    Unchanged,
+    /// Nothing matching the fixture was fetched in the recorded run
+    NotRecorded,
+}
+
+impl fmt::Display for Refresh {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Refresh::Updated { new_url: None } => write!(f, "updated"),
+            Refresh::Updated { new_url: Some(url) } => write!(f, "updated from {}", url),
+            Refresh::Unchanged => write!(f, "unchanged"),
+            Refresh::NotRecorded => write!(f, "not recorded"),
+        }
+    }
+}
+
+/// JSON is saved pretty-printed, so that changes to it can be read in a diff
+fn prettify(path: &str, body: &str) -> String {
+    if path.ends_with(".json") {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
+            return serde_json::to_string_pretty(&json).unwrap() + "\n";
+        }
+    }
+    body.to_string()
+}
+
+/// The recorded page to use for a fixture: the page at its URL if there is one, and otherwise a
+/// page whose URL starts with its `url_prefix`. Pages which are linked to from `linked_from` (e.g.
+/// the new copy of the listing in the same directory) are preferred, so that listings and event
+/// pages stay consistent with each other.
+fn choose_recording<'a>(
+    fixture: &Fixture,
+    recordings: &'a HashMap<String, Recording>,
+    linked_from: &[&str],
+) -> Option<&'a Recording> {
+    if let Some(recording) = recordings.get(&fixture.url) {
+        return Some(recording);
+    }
+    let prefix = fixture.url_prefix.as_ref()?;
+    let mut candidates: Vec<&Recording> = recordings
+        .values()
+        .filter(|recording| recording.url.starts_with(prefix.as_str()))
+        .collect();
+    candidates.sort_by(|a, b| a.url.cmp(&b.url));
+    let is_linked = |recording: &&Recording| {
+        let path = reqwest::Url::parse(&recording.url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_default();
+        !path.is_empty() && linked_from.iter().any(|body| body.contains(&path))
+    };
+    candidates
+        .iter()
+        .copied()
+        .find(is_linked)
+        .or(candidates.first().copied())
+}
+
+/// Replace saved pages with the copies fetched in a recorded run. Only fixtures whose path starts
+/// with one of `only` are refreshed (all of them, if it's empty). The manifest is updated with the
+/// new URLs of pages which came from somewhere else.
+pub fn refresh(
+    fixtures_dir: &Path,
+    recordings: &HashMap<String, Recording>,
+    only: &[String],
+) -> Vec<(Fixture, Refresh)> {
+    let mut fixtures = read_manifest(fixtures_dir);
+    let mut results = vec![];
+    // Pages with exact URLs (i.e. listings) go first, so that event pages can be chosen from them
+    let mut order: Vec<usize> = (0..fixtures.len()).collect();
+    order.sort_by_key(|&i| fixtures[i].url_prefix.is_some());
+    let mut new_bodies: HashMap<String, String> = HashMap::new();
+
+    for i in order {
+        let fixture = &fixtures[i];
+        if !only.is_empty() && !only.iter().any(|prefix| fixture.path.starts_with(prefix)) {
+            continue;
+        }
+        let dir = fixture.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let siblings: Vec<&str> = new_bodies
+            .iter()
+            .filter(|(path, _)| path.rsplit_once('/').map_or("", |(dir, _)| dir) == dir)
+            .map(|(_, body)| body.as_str())
+            .collect();
+
+        let result = match choose_recording(fixture, recordings, &siblings) {
+            None => Refresh::NotRecorded,
+            Some(recording) => {
+                let path = fixtures_dir.join(&fixture.path);
+                let body = prettify(&fixture.path, &recording.body);
+                let old_body = std::fs::read_to_string(&path).unwrap_or_default();
+                new_bodies.insert(fixture.path.clone(), recording.body.clone());
+                let new_url = (recording.url != fixture.url).then(|| recording.url.clone());
+                if body == old_body && new_url.is_none() {
+                    Refresh::Unchanged
+                } else {
+                    std::fs::write(&path, body).unwrap();
+                    Refresh::Updated { new_url }
+                }
+            }
+        };
+        if let Refresh::Updated { new_url: Some(url) } = &result {
+            fixtures[i].url = url.clone();
+        }
+        results.push((fixtures[i].clone(), result));
+    }
+
+    if results
+        .iter()
+        .any(|(_, result)| matches!(result, Refresh::Updated { new_url: Some(_) }))
+    {
+        write_manifest(fixtures_dir, &fixtures);
+    }
+    results
+}
@@ -0,0 +1,216 @@
+use crate::classify;
+use crate::core;
+use crate::http;
+use crate::media;
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::HashSet;
+
+const GUILDHALL_BASE_URL: &str = "https://www.gsmd.ac.uk";
+pub const GUILDHALL_API_URL: &str = "https://www.gsmd.ac.uk/api/events?category=music";
+
+/// The Guildhall School's events calendar is filled from a JSON API, so unlike most venues there
+/// is no need to fetch each event's page
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    let mut concerts: Vec<core::ConcertData> = vec![];
+
+    let mut next_url = Some(GUILDHALL_API_URL.to_string());
+    let mut visited = HashSet::new();
+    while let Some(url) = next_url {
+        if !visited.insert(url.clone()) {
+            warn!(
+                "Guildhall School listing links back to page {}, stopping",
+                url
+            );
+            break;
+        }
+        debug!("Fetching Guildhall School events from URL {}", url);
+        let Some(json) = client.try_get_json::<Value>(&url).await else {
+            break;
+        };
+        let page = parse_events(&json);
+        concerts.extend(page.concerts);
+        next_url = page.next_page_url;
+    }
+
+    let now = Utc::now();
+    concerts.retain(|concert| concert.datetime > now);
+    concerts.iter().for_each(core::report_concert);
+    info!("Scraped {} concerts from Guildhall School", concerts.len());
+    concerts
+}
+
+/// One page of results from the events API
+#[derive(Debug, Clone)]
+pub struct EventsPage {
+    pub concerts: Vec<core::ConcertData>,
+    pub next_page_url: Option<String>,
+}
+
+fn absolute_url(path: &str) -> String {
+    reqwest::Url::parse(GUILDHALL_BASE_URL)
+        .unwrap()
+        .join(path)
+        .unwrap()
+        .to_string()
+}
+
+/// The API says directly whether an event is free and whether it has to be booked, as
+/// `ticketing.type`: one of "paid", "free_booking", or "free_no_booking". Prices are in pounds.
+/// Any other type gives `None`.
+fn parse_ticketing(ticketing: &Value) -> Option<(Option<u32>, Option<u32>, core::Availability)> {
+    match ticketing["type"].as_str() {
+        Some("free_no_booking") => Some((Some(0), Some(0), core::Availability::FreeNoBooking)),
+        Some("free_booking") => Some((Some(0), Some(0), core::Availability::Free)),
+        Some("paid") => {
+            let prices: Vec<u32> = ticketing["prices"]
+                .as_array()
+                .map(|prices| {
+                    prices
+                        .iter()
+                        .filter_map(|p| p.as_f64())
+                        .map(|pounds| (pounds * 100.0).round() as u32)
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some((
+                prices.iter().min().copied(),
+                prices.iter().max().copied(),
+                core::Availability::Ticketed,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Events without a URL, title, or venue, or whose start or ticketing can't be read, are skipped
+fn parse_event(event: &Value) -> Option<core::ConcertData> {
+    let Some(path) = event["url"].as_str() else {
+        warn!(
+            "Skipping Guildhall School event without a URL: {}",
+            event["title"]
+        );
+        return None;
+    };
+    let url = absolute_url(path);
+    let Some(title) = event["title"].as_str() else {
+        warn!("Skipping {}, which has no title", url);
+        return None;
+    };
+    // Most events are at Milton Court or Silk Street, but some are at e.g. LSO St Luke's or the
+    // Barbican
+    let Some(venue) = event["venue"].as_str() else {
+        warn!("Skipping {}, which has no venue", url);
+        return None;
+    };
+    let Some(datetime_str) = event["start"].as_str() else {
+        warn!("Skipping {}, which has no start", url);
+        return None;
+    };
+    let datetime = match DateTime::parse_from_rfc3339(datetime_str) {
+        Ok(datetime) => datetime.with_timezone(&Utc),
+        Err(e) => {
+            warn!(
+                "Skipping {} at unexpected datetime {}: {}",
+                url, datetime_str, e
+            );
+            return None;
+        }
+    };
+    let description = event["summary"]
+        .as_str()
+        .map(core::text_of_html)
+        .unwrap_or_default();
+    let Some((min_price, max_price, availability)) = parse_ticketing(&event["ticketing"]) else {
+        warn!(
+            "Skipping {} with unexpected ticketing type {}",
+            url, event["ticketing"]["type"]
+        );
+        return None;
+    };
+
+    let performers: Vec<core::Performer> = event["performers"]
+        .as_array()
+        .map(|performers| {
+            performers
+                .iter()
+                .filter_map(|p| {
+                    Some(core::Performer {
+                        name: p["name"].as_str()?.to_string(),
+                        instrument: p["role"]
+                            .as_str()
+                            .map(|s| s.to_string())
+                            .filter(|s| !s.is_empty()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let pieces: Vec<core::Piece> = event["programme"]
+        .as_array()
+        .map(|pieces| {
+            pieces
+                .iter()
+                .filter_map(|p| {
+                    Some(core::Piece {
+                        composer: p["composer"].as_str()?.to_string(),
+                        title: p["work"].as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // e.g. "Orchestral", "Chamber music", or "Opera"
+    let tags: Vec<String> = event["tags"]
+        .as_array()
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| t.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut concert = core::ConcertData {
+        datetime,
+        url: url.clone(),
+        performers,
+        title: title.to_string(),
+        subtitle: event["subtitle"]
+            .as_str()
+            .map(|s| s.to_string())
+            .filter(|s| !s.is_empty()),
+        description: Some(description.clone()),
+        programme_pdf_url: None,
+        programme_text: None,
+        pieces,
+        venue: venue.to_string(),
+        category: core::Category::Other,
+        min_price,
+        max_price,
+        availability,
+        media: media::find_media(&description, datetime, &url),
+        is_wigmore_u35: false,
+        series: vec![],
+        production: None,
+    };
+    concert.category = classify::classify(&concert, &tags);
+    Some(concert)
+}
+
+/// Parse one page of results from the events API
+pub fn parse_events(json: &Value) -> EventsPage {
+    let concerts = match json["events"].as_array() {
+        Some(events) => events.iter().filter_map(parse_event).collect(),
+        None => {
+            warn!("Guildhall School response has no events");
+            vec![]
+        }
+    };
+    let next_page_url = json["next"].as_str().map(absolute_url);
+    EventsPage {
+        concerts,
+        next_page_url,
+    }
+}
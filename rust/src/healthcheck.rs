@@ -0,0 +1,808 @@
+use crate::churches::{self, smith_square, southwark_cathedral, st_pauls, westminster_abbey};
+use crate::declarative::Definition;
+use crate::http;
+use crate::schema_org;
+use crate::source::Source;
+use crate::{eno, guildhall, kingsplace, lso_st_lukes, proms, ram, rcm, royal_opera_house};
+use itertools::Itertools;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Something on a page that a scraper depends on. Required probes are things that every page has
+/// (e.g. the title), so if they match nothing the scraper is broken; optional ones (e.g. the
+/// performers) are legitimately missing from some pages, so missing them is only a warning.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Probe {
+    /// A CSS selector, which should match at least one element
+    Css { selector: String, required: bool },
+    /// A path into JSON, e.g. "items.0.node.date" (numbers index into arrays). The JSON is the
+    /// whole page if `script` is `None`, and otherwise the text of the element matching `script`.
+    Json {
+        script: Option<String>,
+        path: String,
+        required: bool,
+    },
+}
+
+impl Probe {
+    pub fn css(selector: &str) -> Probe {
+        Probe::Css {
+            selector: selector.to_string(),
+            required: true,
+        }
+    }
+
+    pub fn optional_css(selector: &str) -> Probe {
+        Probe::Css {
+            selector: selector.to_string(),
+            required: false,
+        }
+    }
+
+    pub fn json(script: Option<&str>, path: &str) -> Probe {
+        Probe::Json {
+            script: script.map(|s| s.to_string()),
+            path: path.to_string(),
+            required: true,
+        }
+    }
+
+    pub fn optional_json(script: Option<&str>, path: &str) -> Probe {
+        Probe::Json {
+            script: script.map(|s| s.to_string()),
+            path: path.to_string(),
+            required: false,
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        match self {
+            Probe::Css { required, .. } | Probe::Json { required, .. } => *required,
+        }
+    }
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Probe::Css { selector, .. } => write!(f, "{}", selector),
+            Probe::Json {
+                script: None, path, ..
+            } => write!(f, "JSON {}", path),
+            Probe::Json {
+                script: Some(script),
+                path,
+                ..
+            } => write!(f, "JSON {} in {}", path, script),
+        }
+    }
+}
+
+/// How to get from a listing to one of its events
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetailLink {
+    /// The `href` of the first element matching a selector
+    Css(String),
+    /// A URL (or part of one) at a JSON path, which is resolved against `base` if given, and
+    /// otherwise against the listing's URL
+    Json {
+        script: Option<String>,
+        path: String,
+        base: Option<String>,
+    },
+}
+
+/// Probes for an event page, and how to find one
+#[derive(Debug, Clone)]
+pub struct DetailCheck {
+    pub link: DetailLink,
+    pub probes: Vec<Probe>,
+}
+
+/// Everything to check for one venue: a listing, and (for scrapers which fetch them) one event
+/// page found on it
+#[derive(Debug, Clone)]
+pub struct VenueCheck {
+    pub name: String,
+    pub listing_url: String,
+    pub listing_probes: Vec<Probe>,
+    pub detail: Option<DetailCheck>,
+}
+
+/// Result of checking one probe on a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeResult {
+    pub probe: Probe,
+    /// Number of elements (or 1 for a JSON path) that the probe found
+    pub n_matches: usize,
+    /// If nothing was found, the nearest thing that does exist, e.g. the parent element of a
+    /// selector which matched nothing, or the keys of the deepest part of a JSON path which exists
+    pub context: Option<String>,
+}
+
+impl ProbeResult {
+    pub fn is_ok(&self) -> bool {
+        self.n_matches > 0
+    }
+}
+
+/// Results for one page
+#[derive(Debug, Clone)]
+pub struct PageReport {
+    /// e.g. "listing" or "event"
+    pub kind: &'static str,
+    pub url: Option<String>,
+    pub results: Vec<ProbeResult>,
+    /// If the page couldn't be fetched (or no event page could be found on the listing)
+    pub error: Option<String>,
+}
+
+impl PageReport {
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none()
+            && self
+                .results
+                .iter()
+                .all(|result| result.is_ok() || !result.probe.is_required())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VenueReport {
+    pub name: String,
+    pub pages: Vec<PageReport>,
+}
+
+impl VenueReport {
+    pub fn is_healthy(&self) -> bool {
+        self.pages.iter().all(PageReport::is_healthy)
+    }
+}
+
+impl fmt::Display for VenueReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if self.is_healthy() { "OK" } else { "BROKEN" };
+        writeln!(f, "{}: {}", self.name, status)?;
+        for page in &self.pages {
+            let url = page.url.as_deref().unwrap_or("(none)");
+            writeln!(f, "  {} {}", page.kind, url)?;
+            if let Some(error) = &page.error {
+                writeln!(f, "    ERROR {}", error)?;
+            }
+            let n_ok = page.results.iter().filter(|r| r.is_ok()).count();
+            if !page.results.is_empty() {
+                writeln!(f, "    {}/{} probes matched", n_ok, page.results.len())?;
+            }
+            for result in page.results.iter().filter(|r| !r.is_ok()) {
+                let level = if result.probe.is_required() {
+                    "MISSING"
+                } else {
+                    "missing (optional)"
+                };
+                writeln!(f, "    {} {}", level, result.probe)?;
+                if let Some(context) = &result.context {
+                    writeln!(f, "      near: {}", context)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Longest snippet of HTML or JSON shown for a probe that matched nothing
+const MAX_CONTEXT_LENGTH: usize = 300;
+
+fn shorten(text: &str) -> String {
+    let text = text.split_whitespace().join(" ");
+    match text.char_indices().nth(MAX_CONTEXT_LENGTH) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    }
+}
+
+/// Parse a selector, which may be broken (e.g. in a scraper definition)
+fn try_selector(selector: &str) -> Option<Selector> {
+    Selector::parse(selector).ok()
+}
+
+/// The HTML around where a selector which matched nothing should have matched. Parts of the
+/// selector are dropped from the end until it matches something, e.g. for "div.event-meta__date
+/// time" this is the `div.event-meta__date`. If even the first part matches nothing, the first
+/// element with a class in the same BEM block is used instead (e.g. `c-event-masthead` for
+/// `h1.c-event-masthead__title`), since sites tend to rename one element at a time.
+fn nearest_html(doc: &Html, selector: &str) -> Option<String> {
+    let parts: Vec<&str> = selector
+        .split_whitespace()
+        .filter(|part| *part != ">")
+        .collect();
+    for n in (1..parts.len()).rev() {
+        let shorter = parts[..n].join(" ");
+        if let Some(elem) = try_selector(&shorter).and_then(|slc| doc.select(&slc).next()) {
+            return Some(shorten(&elem.html()));
+        }
+    }
+
+    let last_class = parts
+        .iter()
+        .rev()
+        .find(|part| part.contains('.'))?
+        .rsplit('.')
+        .next()?;
+    let block = last_class
+        .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .next()?
+        .split("__")
+        .next()?;
+    if block.is_empty() {
+        return None;
+    }
+    let slc_any = Selector::parse("*").unwrap();
+    doc.select(&slc_any)
+        .find(|elem| elem.value().classes().any(|class| class.starts_with(block)))
+        .map(|elem: ElementRef| shorten(&elem.html()))
+}
+
+/// Parse JSON from a page. Wigmore Hall's props have an invalid escape in them, which is fixed.
+fn parse_json(text: &str) -> Option<Value> {
+    serde_json::from_str(&text.replace("<\\!--", "<!--")).ok()
+}
+
+/// Look up a path in some JSON. If it doesn't exist, returns the deepest part of the path which
+/// does exist and its value instead.
+fn lookup<'a>(json: &'a Value, path: &str) -> Result<&'a Value, (String, &'a Value)> {
+    let mut current = json;
+    let mut found: Vec<&str> = vec![];
+    for key in path.split('.') {
+        let next = match key.parse::<usize>() {
+            Ok(index) => current.get(index),
+            Err(_) => current.get(key),
+        };
+        match next {
+            Some(value) if !value.is_null() => {
+                current = value;
+                found.push(key);
+            }
+            _ => return Err((found.join("."), current)),
+        }
+    }
+    Ok(current)
+}
+
+fn describe_json(path: &str, value: &Value) -> String {
+    let path = if path.is_empty() { "(root)" } else { path };
+    match value {
+        Value::Object(obj) => format!("{} has keys {}", path, obj.keys().join(", ")),
+        Value::Array(items) => format!("{} is a list of {} items", path, items.len()),
+        other => format!("{} is {}", path, shorten(&other.to_string())),
+    }
+}
+
+/// The JSON that a probe looks in: the page itself, or a script on it
+fn get_json(body: &str, doc: &Html, script: Option<&str>) -> Result<Value, String> {
+    let text = match script {
+        None => body.to_string(),
+        Some(script) => {
+            let slc = try_selector(script).ok_or(format!("invalid selector {}", script))?;
+            doc.select(&slc)
+                .next()
+                .ok_or(format!("no {} on the page", script))?
+                .text()
+                .collect()
+        }
+    };
+    parse_json(&text).ok_or("not valid JSON".to_string())
+}
+
+/// Check all the probes for a page against its HTML (or JSON)
+pub fn check_page(body: &str, probes: &[Probe]) -> Vec<ProbeResult> {
+    let doc = Html::parse_document(body);
+    let mut json_cache: HashMap<Option<String>, Result<Value, String>> = HashMap::new();
+
+    probes
+        .iter()
+        .map(|probe| match probe {
+            Probe::Css { selector, .. } => match try_selector(selector) {
+                None => ProbeResult {
+                    probe: probe.clone(),
+                    n_matches: 0,
+                    context: Some("invalid selector".to_string()),
+                },
+                Some(slc) => {
+                    let n_matches = doc.select(&slc).count();
+                    ProbeResult {
+                        probe: probe.clone(),
+                        n_matches,
+                        context: (n_matches == 0)
+                            .then(|| nearest_html(&doc, selector))
+                            .flatten(),
+                    }
+                }
+            },
+            Probe::Json { script, path, .. } => {
+                let json = json_cache
+                    .entry(script.clone())
+                    .or_insert_with(|| get_json(body, &doc, script.as_deref()));
+                let (n_matches, context) = match json {
+                    Err(e) => (0, Some(e.clone())),
+                    Ok(json) => match lookup(json, path) {
+                        Ok(_) => (1, None),
+                        Err((found, value)) => (0, Some(describe_json(&found, value))),
+                    },
+                };
+                ProbeResult {
+                    probe: probe.clone(),
+                    n_matches,
+                    context,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Find the URL of an event page on a listing
+pub fn find_detail_url(body: &str, page_url: &str, link: &DetailLink) -> Option<String> {
+    let doc = Html::parse_document(body);
+    let (href, base) = match link {
+        DetailLink::Css(selector) => {
+            let slc = try_selector(selector)?;
+            let href = doc.select(&slc).find_map(|e| e.value().attr("href"))?;
+            (href.to_string(), page_url.to_string())
+        }
+        DetailLink::Json { script, path, base } => {
+            let json = get_json(body, &doc, script.as_deref()).ok()?;
+            let href = lookup(&json, path).ok()?.as_str()?.to_string();
+            (href, base.clone().unwrap_or(page_url.to_string()))
+        }
+    };
+    Some(
+        reqwest::Url::parse(&base)
+            .ok()?
+            .join(&href)
+            .ok()?
+            .to_string(),
+    )
+}
+
+/// Checks for a scraper written as a definition: everything in it, of which only the links on the
+/// listing and the title and datetime of events are required
+pub fn definition_check(definition: &Definition) -> VenueCheck {
+    let required = [
+        definition.event.title.selector(),
+        definition.event.datetime.field.selector(),
+    ];
+    let event_probes = definition
+        .selectors()
+        .into_iter()
+        .filter(|selector| {
+            *selector != definition.listing.link
+                && Some(*selector) != definition.listing.next_page.as_deref()
+        })
+        .unique()
+        .map(|selector| match required.contains(&selector) {
+            true => Probe::css(selector),
+            false => Probe::optional_css(selector),
+        })
+        .collect();
+    VenueCheck {
+        name: definition.name.clone(),
+        listing_url: definition.listing.url.replace("{page}", "1"),
+        listing_probes: vec![Probe::css(&definition.listing.link)],
+        detail: Some(DetailCheck {
+            link: DetailLink::Css(definition.listing.link.clone()),
+            probes: event_probes,
+        }),
+    }
+}
+
+/// Checks for a venue which is scraped using only its JSON-LD (see `schema_org::Site`)
+pub fn site_check(site: &schema_org::Site) -> VenueCheck {
+    let json_ld = Probe::css(r#"script[type="application/ld+json"]"#);
+    match &site.link {
+        None => VenueCheck {
+            name: site.name.clone(),
+            listing_url: site.listing_url.clone(),
+            listing_probes: vec![json_ld],
+            detail: None,
+        },
+        Some(link) => VenueCheck {
+            name: site.name.clone(),
+            listing_url: site.listing_url.clone(),
+            listing_probes: vec![Probe::css(link)],
+            detail: Some(DetailCheck {
+                link: DetailLink::Css(link.clone()),
+                probes: vec![json_ld],
+            }),
+        },
+    }
+}
+
+/// Checks for a church's calendar page (see `churches::CalendarPage`)
+pub fn calendar_check(name: &str, url: &str, page: &churches::CalendarPage) -> VenueCheck {
+    let mut listing_probes = vec![
+        Probe::css(page.item),
+        Probe::css(page.title),
+        Probe::css(page.datetime),
+        Probe::optional_css(page.link),
+        Probe::optional_css(page.description),
+        Probe::optional_css(page.admission),
+    ];
+    listing_probes.extend(page.venue.map(Probe::optional_css));
+    listing_probes.extend(page.tags.map(Probe::optional_css));
+    VenueCheck {
+        name: name.to_string(),
+        listing_url: url.to_string(),
+        listing_probes,
+        detail: None,
+    }
+}
+
+/// Checks for a page whose events are all in its JSON-LD
+fn json_ld_check(name: &str, url: &str) -> VenueCheck {
+    VenueCheck {
+        name: name.to_string(),
+        listing_url: url.to_string(),
+        listing_probes: vec![Probe::css(r#"script[type="application/ld+json"]"#)],
+        detail: None,
+    }
+}
+
+fn css_probes(required: &[&str], optional: &[&str]) -> Vec<Probe> {
+    required
+        .iter()
+        .map(|selector| Probe::css(selector))
+        .chain(
+            optional
+                .iter()
+                .map(|selector| Probe::optional_css(selector)),
+        )
+        .collect()
+}
+
+fn json_probes(script: Option<&str>, required: &[&str], optional: &[&str]) -> Vec<Probe> {
+    required
+        .iter()
+        .map(|path| Probe::json(script, path))
+        .chain(
+            optional
+                .iter()
+                .map(|path| Probe::optional_json(script, path)),
+        )
+        .collect()
+}
+
+/// What each scraper depends on. CSS selectors come from the `selectors` module of each scraper
+/// (or its definition), so they can't drift apart; JSON paths have to be kept in step with the
+/// scrapers by hand. The tests check them against the same saved pages as the scrapers.
+pub fn checks(source: Source) -> Vec<VenueCheck> {
+    match source {
+        Source::Wigmore => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: "https://www.wigmore-hall.org.uk/api/v1/listings/whats-on?page=1"
+                .to_string(),
+            listing_probes: json_probes(
+                None,
+                &[
+                    "totalPages",
+                    "items.0.node.date",
+                    "items.0.node.url",
+                    "items.0.node.titleOverrideText",
+                ],
+                &["items.0.node.subtitleText"],
+            ),
+            detail: Some(DetailCheck {
+                link: DetailLink::Json {
+                    script: None,
+                    path: "items.0.node.url".to_string(),
+                    base: Some("https://wigmore-hall.org.uk".to_string()),
+                },
+                probes: json_probes(
+                    Some("script#props"),
+                    &["data.page.pricesText", "data.page.overviewText"],
+                    &[
+                        "data.page.repertoire",
+                        "data.page.credits",
+                        "data.page.bookingInformationText",
+                        "data.page.series",
+                        "data.page.programmeDocument.url",
+                    ],
+                ),
+            }),
+        }],
+        Source::Proms => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: proms::PROMS_2025_URL.to_string(),
+            listing_probes: css_probes(
+                &[
+                    proms::selectors::DATE_EVENTS,
+                    proms::selectors::DATE,
+                    proms::selectors::EVENT,
+                    proms::selectors::TITLE,
+                    proms::selectors::LINK,
+                    proms::selectors::TIME,
+                    proms::selectors::VENUE,
+                ],
+                &[
+                    proms::selectors::DESCRIPTION,
+                    proms::selectors::PIECE,
+                    proms::selectors::PERFORMER,
+                    proms::selectors::PERFORMER_NAME,
+                    proms::selectors::PERFORMER_ROLE,
+                    proms::selectors::PRICE,
+                ],
+            ),
+            detail: None,
+        }],
+        Source::Southbank => vec![definition_check(&Definition::load("southbank"))],
+        Source::KingsPlace => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: kingsplace::KINGS_PLACE_URL.to_string(),
+            listing_probes: css_probes(
+                &[kingsplace::selectors::EVENT_LINK],
+                &[kingsplace::selectors::NEXT_PAGE],
+            ),
+            detail: Some(DetailCheck {
+                link: DetailLink::Css(kingsplace::selectors::EVENT_LINK.to_string()),
+                probes: css_probes(
+                    &[
+                        kingsplace::selectors::TITLE,
+                        kingsplace::selectors::PERFORMANCE,
+                        kingsplace::selectors::PERFORMANCE_TIME,
+                    ],
+                    &[
+                        kingsplace::selectors::SUBTITLE,
+                        kingsplace::selectors::DESCRIPTION,
+                        kingsplace::selectors::PRICE,
+                        kingsplace::selectors::PERFORMER,
+                        kingsplace::selectors::PERFORMER_NAME,
+                        kingsplace::selectors::PERFORMER_ROLE,
+                        kingsplace::selectors::PIECE,
+                        kingsplace::selectors::PIECE_COMPOSER,
+                        kingsplace::selectors::PIECE_TITLE,
+                        kingsplace::selectors::SERIES,
+                        kingsplace::selectors::GENRE,
+                        kingsplace::selectors::PERFORMANCE_HALL,
+                    ],
+                ),
+            }),
+        }],
+        Source::LsoStLukes => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: lso_st_lukes::LSO_ST_LUKES_URL.to_string(),
+            listing_probes: css_probes(&[lso_st_lukes::selectors::EVENT_LINK], &[]),
+            detail: Some(DetailCheck {
+                link: DetailLink::Css(lso_st_lukes::selectors::EVENT_LINK.to_string()),
+                probes: css_probes(
+                    &[
+                        lso_st_lukes::selectors::VENUE,
+                        lso_st_lukes::selectors::TITLE,
+                        lso_st_lukes::selectors::DATE,
+                    ],
+                    &[
+                        lso_st_lukes::selectors::SUBTITLE,
+                        lso_st_lukes::selectors::DESCRIPTION,
+                        lso_st_lukes::selectors::PRICE,
+                        lso_st_lukes::selectors::ARTIST,
+                        lso_st_lukes::selectors::ARTIST_NAME,
+                        lso_st_lukes::selectors::ARTIST_ROLE,
+                        lso_st_lukes::selectors::PIECE,
+                        lso_st_lukes::selectors::SERIES,
+                    ],
+                ),
+            }),
+        }],
+        Source::RoyalOperaHouse => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: royal_opera_house::ROH_URL.to_string(),
+            listing_probes: json_probes(
+                Some("script#__NEXT_DATA__"),
+                &[
+                    "props.pageProps.events.0.slug",
+                    "props.pageProps.events.0.category",
+                ],
+                &[],
+            ),
+            detail: Some(DetailCheck {
+                link: DetailLink::Json {
+                    script: Some("script#__NEXT_DATA__".to_string()),
+                    path: "props.pageProps.events.0.slug".to_string(),
+                    base: Some(royal_opera_house::ROH_EVENT_URL_PREFIX.to_string()),
+                },
+                probes: json_probes(
+                    Some("script#__NEXT_DATA__"),
+                    &[
+                        "props.pageProps.production.title",
+                        "props.pageProps.production.composer",
+                        "props.pageProps.production.performances.0.dateTime",
+                    ],
+                    &[
+                        "props.pageProps.production.strapline",
+                        "props.pageProps.production.description",
+                        "props.pageProps.production.runningTime",
+                        "props.pageProps.production.language",
+                        "props.pageProps.production.creatives",
+                        "props.pageProps.production.performances.0.venue",
+                        "props.pageProps.production.performances.0.cast",
+                        "props.pageProps.production.performances.0.minPrice",
+                        "props.pageProps.production.performances.0.maxPrice",
+                    ],
+                ),
+            }),
+        }],
+        Source::Eno => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: eno::ENO_URL.to_string(),
+            listing_probes: css_probes(&[eno::selectors::PRODUCTION_LINK], &[]),
+            detail: Some(DetailCheck {
+                link: DetailLink::Css(eno::selectors::PRODUCTION_LINK.to_string()),
+                probes: css_probes(
+                    &[
+                        eno::selectors::TITLE,
+                        eno::selectors::COMPOSER,
+                        eno::selectors::PERFORMANCE,
+                    ],
+                    &[
+                        eno::selectors::SUBTITLE,
+                        eno::selectors::DESCRIPTION,
+                        eno::selectors::PRICE,
+                        eno::selectors::INFO_TERM,
+                        eno::selectors::CAST_MEMBER,
+                        eno::selectors::CAST_ROLE,
+                        eno::selectors::CAST_NAME,
+                    ],
+                ),
+            }),
+        }],
+        Source::Ram => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: ram::RAM_URL.to_string(),
+            listing_probes: css_probes(&[ram::selectors::EVENT_LINK], &[ram::selectors::NEXT_PAGE]),
+            detail: Some(DetailCheck {
+                link: DetailLink::Css(ram::selectors::EVENT_LINK.to_string()),
+                probes: css_probes(
+                    &[ram::selectors::TITLE, ram::selectors::DATETIME],
+                    &[
+                        ram::selectors::SUBTITLE,
+                        ram::selectors::VENUE,
+                        ram::selectors::PRICE,
+                        ram::selectors::DESCRIPTION,
+                        ram::selectors::PERFORMER,
+                        ram::selectors::PIECE,
+                        ram::selectors::TAG,
+                    ],
+                ),
+            }),
+        }],
+        Source::Rcm => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: rcm::RCM_URL.to_string(),
+            listing_probes: css_probes(&[rcm::selectors::EVENT_LINK], &[]),
+            detail: Some(DetailCheck {
+                link: DetailLink::Css(rcm::selectors::EVENT_LINK.to_string()),
+                probes: css_probes(
+                    &[rcm::selectors::TITLE, rcm::selectors::DATETIME],
+                    &[
+                        rcm::selectors::VENUE,
+                        rcm::selectors::PRICE,
+                        rcm::selectors::DESCRIPTION,
+                        rcm::selectors::PERFORMER,
+                        rcm::selectors::PIECE,
+                        rcm::selectors::SERIES,
+                    ],
+                ),
+            }),
+        }],
+        Source::Guildhall => vec![VenueCheck {
+            name: source.name().to_string(),
+            listing_url: guildhall::GUILDHALL_API_URL.to_string(),
+            listing_probes: json_probes(
+                None,
+                &[
+                    "events.0.title",
+                    "events.0.url",
+                    "events.0.start",
+                    "events.0.venue",
+                    "events.0.ticketing.type",
+                ],
+                &[
+                    "next",
+                    "events.0.subtitle",
+                    "events.0.summary",
+                    "events.0.tags",
+                    "events.0.performers",
+                    "events.0.programme",
+                ],
+            ),
+            detail: None,
+        }],
+        Source::SmithSquare => vec![json_ld_check(source.name(), smith_square::SJSS_URL)],
+        Source::SouthwarkCathedral => vec![calendar_check(
+            source.name(),
+            southwark_cathedral::SOUTHWARK_URL,
+            &southwark_cathedral::CALENDAR,
+        )],
+        Source::StPauls => vec![calendar_check(
+            source.name(),
+            st_pauls::ST_PAULS_URL,
+            &st_pauls::CALENDAR,
+        )],
+        Source::WestminsterAbbey => {
+            vec![json_ld_check(source.name(), westminster_abbey::ABBEY_URL)]
+        }
+        Source::CadoganHall => vec![site_check(&schema_org::Site::load("cadogan_hall"))],
+        Source::StMartinInTheFields => {
+            vec![site_check(&schema_org::Site::load(
+                "st_martin_in_the_fields",
+            ))]
+        }
+    }
+}
+
+/// Fetch a venue's listing and one of its event pages, and check them
+pub async fn check_venue(client: &http::Client, check: &VenueCheck) -> VenueReport {
+    let mut pages = vec![];
+    let listing = client.get_text(&check.listing_url).await;
+    let listing_body = match listing {
+        Ok(body) => {
+            pages.push(PageReport {
+                kind: "listing",
+                url: Some(check.listing_url.clone()),
+                results: check_page(&body, &check.listing_probes),
+                error: None,
+            });
+            Some(body)
+        }
+        Err(e) => {
+            pages.push(PageReport {
+                kind: "listing",
+                url: Some(check.listing_url.clone()),
+                results: vec![],
+                error: Some(format!("failed to fetch: {}", e)),
+            });
+            None
+        }
+    };
+
+    if let (Some(detail), Some(body)) = (&check.detail, listing_body) {
+        let page = match find_detail_url(&body, &check.listing_url, &detail.link) {
+            None => PageReport {
+                kind: "event",
+                url: None,
+                results: vec![],
+                error: Some("could not find an event on the listing".to_string()),
+            },
+            Some(url) => match client.get_text(&url).await {
+                Ok(body) => PageReport {
+                    kind: "event",
+                    url: Some(url),
+                    results: check_page(&body, &detail.probes),
+                    error: None,
+                },
+                Err(e) => PageReport {
+                    kind: "event",
+                    url: Some(url),
+                    results: vec![],
+                    error: Some(format!("failed to fetch: {}", e)),
+                },
+            },
+        };
+        pages.push(page);
+    }
+
+    VenueReport {
+        name: check.name.clone(),
+        pages,
+    }
+}
+
+/// Check every venue of the given sources
+pub async fn run(client: &http::Client, sources: &[Source]) -> Vec<VenueReport> {
+    let mut reports = vec![];
+    for source in sources {
+        for check in checks(*source) {
+            reports.push(check_venue(client, &check).await);
+        }
+    }
+    reports
+}
@@ -0,0 +1,220 @@
+use crate::fixtures::Recording;
+use chrono::Utc;
+use log::warn;
+use reqwest::{header, StatusCode};
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum number of times a request is attempted before giving up
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Counters for HTTP activity. These are atomic because scrapers make lots of requests
+/// concurrently.
+#[derive(Debug, Default)]
+pub struct FetchStats {
+    pub requests: AtomicU32,
+    pub retries: AtomicU32,
+    pub cache_hits: AtomicU32,
+    pub errors: AtomicU32,
+}
+
+/// Wrapper around `reqwest::Client` which retries failed requests and keeps track of how many
+/// requests were made.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: reqwest::Client,
+    stats: Arc<FetchStats>,
+    /// Directory to save a copy of every page to (see `recording_to`)
+    record_dir: Option<PathBuf>,
+}
+
+impl Client {
+    pub fn new(inner: reqwest::Client) -> Client {
+        Client {
+            inner,
+            stats: Arc::new(FetchStats::default()),
+            record_dir: None,
+        }
+    }
+
+    /// Save every HTML or JSON page which is fetched to `dir`, so that the test fixtures can be
+    /// refreshed from a real run (see `fixtures::refresh`)
+    pub fn recording_to(mut self, dir: PathBuf) -> Client {
+        self.record_dir = Some(dir);
+        self
+    }
+
+    /// Create a client which shares the same underlying connection pool, but has its own
+    /// counters. This is used to get separate statistics for each source.
+    pub fn with_new_stats(&self) -> Client {
+        Client {
+            inner: self.inner.clone(),
+            stats: Arc::new(FetchStats::default()),
+            record_dir: self.record_dir.clone(),
+        }
+    }
+
+    pub fn stats(&self) -> &FetchStats {
+        &self.stats
+    }
+
+    /// Record that something was reused instead of being fetched again
+    pub fn record_cache_hit(&self) {
+        self.stats.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an error which happened after the request itself succeeded, e.g. if the response
+    /// couldn't be parsed
+    pub fn record_error(&self) {
+        self.stats.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Fetch a URL, retrying on connection errors, rate limiting, and server errors
+    async fn get(&self, url: &str) -> reqwest::Result<reqwest::Response> {
+        let mut attempt = 1;
+        loop {
+            self.stats.requests.fetch_add(1, Ordering::Relaxed);
+            let result = self
+                .inner
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match result {
+                Ok(response) => match &self.record_dir {
+                    Some(dir) => return record(dir, url, response).await,
+                    None => return Ok(response),
+                },
+                Err(e) if attempt < MAX_ATTEMPTS && is_retryable(&e) => {
+                    warn!("Request to {} failed (attempt {}): {}", url, attempt, e);
+                    self.stats.retries.fetch_add(1, Ordering::Relaxed);
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    pub async fn get_text(&self, url: &str) -> reqwest::Result<String> {
+        self.get(url).await?.text().await
+    }
+
+    pub async fn get_bytes(&self, url: &str) -> reqwest::Result<Vec<u8>> {
+        Ok(self.get(url).await?.bytes().await?.to_vec())
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> reqwest::Result<T> {
+        self.get(url).await?.json().await
+    }
+
+    /// Like `get_text`, but a failure is logged and counted as an error instead of being returned.
+    /// Scrapers use this so that one page which can't be fetched doesn't stop everything else
+    /// from being scraped.
+    pub async fn try_get_text(&self, url: &str) -> Option<String> {
+        match self.get(url).await {
+            Ok(response) => self.body_or_warn(url, response.text().await),
+            Err(e) => {
+                warn!("Failed to fetch {}: {}", url, e);
+                None
+            }
+        }
+    }
+
+    /// Like `get_json`, but a failure is logged and counted as an error instead of being returned
+    pub async fn try_get_json<T: DeserializeOwned>(&self, url: &str) -> Option<T> {
+        match self.get(url).await {
+            Ok(response) => self.body_or_warn(url, response.json().await),
+            Err(e) => {
+                warn!("Failed to fetch {}: {}", url, e);
+                None
+            }
+        }
+    }
+
+    fn body_or_warn<T>(&self, url: &str, body: reqwest::Result<T>) -> Option<T> {
+        match body {
+            Ok(body) => Some(body),
+            Err(e) => {
+                warn!("Failed to read response from {}: {}", url, e);
+                self.record_error();
+                None
+            }
+        }
+    }
+}
+
+/// Save a copy of a response to `dir` if it's HTML or JSON. The body can only be read once, so the
+/// response is put back together from the copy.
+async fn record(
+    dir: &Path,
+    url: &str,
+    response: reqwest::Response,
+) -> reqwest::Result<reqwest::Response> {
+    let is_text = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("html") || value.contains("json"));
+    if !is_text {
+        return Ok(response);
+    }
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.text().await?;
+    let recording = Recording {
+        url: url.to_string(),
+        fetched_at: Utc::now(),
+        body: body.clone(),
+    };
+    if let Err(e) = recording.write(dir) {
+        warn!("Failed to record {} in {}: {}", url, dir.display(), e);
+    }
+
+    let mut rebuilt = ::http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.headers_mut() = headers;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+fn is_retryable(e: &reqwest::Error) -> bool {
+    match e.status() {
+        Some(status) => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+        None => e.is_connect() || e.is_timeout() || e.is_request(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn failed_fetches_are_counted() {
+        let client = Client::new(reqwest::Client::new());
+        assert_eq!(client.try_get_text("not a url").await, None);
+        assert_eq!(client.try_get_json::<Value>("not a url").await, None);
+        assert_eq!(client.stats().requests.load(Ordering::Relaxed), 2);
+        assert_eq!(client.stats().retries.load(Ordering::Relaxed), 0);
+        assert_eq!(client.stats().errors.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn stats_are_per_client() {
+        let client = Client::new(reqwest::Client::new());
+        let other = client.with_new_stats();
+        client.record_cache_hit();
+        other.record_error();
+        assert_eq!(client.stats().cache_hits.load(Ordering::Relaxed), 1);
+        assert_eq!(client.stats().errors.load(Ordering::Relaxed), 0);
+        assert_eq!(other.stats().cache_hits.load(Ordering::Relaxed), 0);
+        assert_eq!(other.stats().errors.load(Ordering::Relaxed), 1);
+    }
+}
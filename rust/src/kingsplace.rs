@@ -0,0 +1,249 @@
+use crate::classify;
+use crate::core;
+use crate::datetime;
+use crate::http;
+use crate::media;
+use chrono::Utc;
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{debug, info, warn};
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+
+pub const KINGS_PLACE_URL: &str = "https://www.kingsplace.co.uk/whats-on/?genre=classical";
+
+/// Selectors for the listing and event pages, which the healthcheck probes as well
+pub mod selectors {
+    pub const EVENT_LINK: &str = "article.event-card a.event-card__link";
+    pub const NEXT_PAGE: &str = "a.pagination__next";
+    pub const TITLE: &str = "h1.event-header__title";
+    pub const SUBTITLE: &str = "p.event-header__subtitle";
+    pub const DESCRIPTION: &str = "div.event-content > p";
+    pub const PRICE: &str = "div.event-header__price";
+    pub const PERFORMER: &str = "ul.event-performers > li";
+    pub const PERFORMER_NAME: &str = "span.event-performers__name";
+    pub const PERFORMER_ROLE: &str = "span.event-performers__role";
+    pub const PIECE: &str = "ul.event-repertoire > li";
+    pub const PIECE_COMPOSER: &str = "span.event-repertoire__composer";
+    pub const PIECE_TITLE: &str = "span.event-repertoire__work";
+    pub const SERIES: &str = "a.event-header__series";
+    pub const GENRE: &str = "ul.event-genres li";
+    pub const PERFORMANCE: &str = "ul.event-performances > li";
+    pub const PERFORMANCE_TIME: &str = "ul.event-performances > li time";
+    pub const PERFORMANCE_HALL: &str = "span.event-performances__hall";
+}
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    let mut concerts: Vec<core::ConcertData> = vec![];
+
+    // The listing is split into pages, each of which links to the next one
+    let mut next_url = Some(KINGS_PLACE_URL.to_string());
+    // Stop if a page links back to one which has already been scraped, rather than going round
+    // in circles
+    let mut visited = HashSet::new();
+    while let Some(url) = next_url {
+        if !visited.insert(url.clone()) {
+            warn!("Kings Place listing links back to page {}, stopping", url);
+            break;
+        }
+        debug!("Scraping Kings Place listing from URL {}", url);
+        let Some(html) = client.try_get_text(&url).await else {
+            break;
+        };
+        let listing = parse_listing(&html, &url);
+        let futures = listing
+            .event_urls
+            .iter()
+            .map(|event_url| scrape_event(event_url, client));
+        concerts.extend(join_all(futures).await.into_iter().flatten());
+        next_url = listing.next_page_url;
+    }
+
+    info!("Scraped {} concerts from Kings Place", concerts.len());
+    concerts
+}
+
+async fn scrape_event(event_url: &str, client: &http::Client) -> Vec<core::ConcertData> {
+    let Some(html) = client.try_get_text(event_url).await else {
+        return vec![];
+    };
+    let now = Utc::now();
+    parse_event(event_url, &html)
+        .into_iter()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect()
+}
+
+/// Links found on one page of the Kings Place listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing {
+    pub event_urls: Vec<String>,
+    pub next_page_url: Option<String>,
+}
+
+/// Parse a page of the listing. `page_url` is the URL of the page, which relative links are
+/// resolved against.
+pub fn parse_listing(html: &str, page_url: &str) -> Listing {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let resolve = |href: &str| base.join(href).ok().map(|url| url.to_string());
+
+    let slc_event_link = Selector::parse(selectors::EVENT_LINK).unwrap();
+    // Links without an href can't be followed, so they are left out
+    let event_urls = doc
+        .select(&slc_event_link)
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(resolve)
+        .unique()
+        .collect();
+
+    let slc_next = Selector::parse(selectors::NEXT_PAGE).unwrap();
+    let next_page_url = doc
+        .select(&slc_next)
+        .next()
+        .and_then(|elem| elem.value().attr("href"))
+        .and_then(resolve);
+
+    Listing {
+        event_urls,
+        next_page_url,
+    }
+}
+
+/// Parse an event page. Events which are performed more than once (e.g. a matinee and an evening
+/// performance) give one concert per performance. Events without a title are skipped.
+pub fn parse_event(event_url: &str, html: &str) -> Vec<core::ConcertData> {
+    let doc = Html::parse_document(html);
+
+    let slc_title = Selector::parse(selectors::TITLE).unwrap();
+    let Some(title) = doc.select(&slc_title).next().map(core::element_text) else {
+        warn!("Skipping {}, which has no title", event_url);
+        return vec![];
+    };
+
+    let slc_subtitle = Selector::parse(selectors::SUBTITLE).unwrap();
+    let subtitle = doc
+        .select(&slc_subtitle)
+        .next()
+        .map(core::element_text)
+        .filter(|s| !s.is_empty());
+
+    let slc_description = Selector::parse(selectors::DESCRIPTION).unwrap();
+    let description = doc
+        .select(&slc_description)
+        .map(core::element_text)
+        .join("\n");
+
+    // Prices are given as a range, e.g. "£10 – £45", or as "Free"
+    let slc_price = Selector::parse(selectors::PRICE).unwrap();
+    let price_text = doc
+        .select(&slc_price)
+        .next()
+        .map(core::element_text)
+        .unwrap_or_default();
+    let prices = core::parse_prices(&price_text);
+    let min_price = prices.iter().min().copied();
+    let max_price = prices.iter().max().copied();
+    let availability = core::parse_availability(&price_text, min_price, max_price);
+
+    let slc_performers = Selector::parse(selectors::PERFORMER).unwrap();
+    let slc_performer_name = Selector::parse(selectors::PERFORMER_NAME).unwrap();
+    let slc_performer_role = Selector::parse(selectors::PERFORMER_ROLE).unwrap();
+    let performers: Vec<core::Performer> = doc
+        .select(&slc_performers)
+        .filter_map(|li| {
+            Some(core::Performer {
+                name: li
+                    .select(&slc_performer_name)
+                    .next()
+                    .map(core::element_text)?,
+                instrument: li
+                    .select(&slc_performer_role)
+                    .next()
+                    .map(core::element_text),
+            })
+        })
+        .collect();
+
+    let slc_pieces = Selector::parse(selectors::PIECE).unwrap();
+    let slc_composer = Selector::parse(selectors::PIECE_COMPOSER).unwrap();
+    let slc_work = Selector::parse(selectors::PIECE_TITLE).unwrap();
+    let pieces: Vec<core::Piece> = doc
+        .select(&slc_pieces)
+        .filter_map(|li| {
+            let composer = li.select(&slc_composer).next().map(core::element_text)?;
+            let title = li.select(&slc_work).next().map(core::element_text)?;
+            Some(core::Piece { composer, title })
+        })
+        .collect();
+
+    // Kings Place's year-long themes, e.g. "Time Unwrapped"
+    let slc_series = Selector::parse(selectors::SERIES).unwrap();
+    let series: Vec<core::Series> = doc
+        .select(&slc_series)
+        .map(|elem| core::Series {
+            name: core::element_text(elem),
+            organiser: "Kings Place".to_string(),
+            start_date: None,
+            end_date: None,
+            url: elem
+                .value()
+                .attr("href")
+                .and_then(|href| reqwest::Url::parse(event_url).ok()?.join(href).ok())
+                .map(|url| url.to_string()),
+        })
+        .collect();
+
+    let slc_genres = Selector::parse(selectors::GENRE).unwrap();
+    let mut tags: Vec<String> = doc.select(&slc_genres).map(core::element_text).collect();
+    tags.extend(series.iter().map(|s| s.name.clone()));
+
+    // Each performance has its own date, time, and hall (Hall One or Hall Two). The datetime
+    // attribute is in London time, e.g. "2026-02-07T19:30".
+    let slc_performance = Selector::parse(selectors::PERFORMANCE).unwrap();
+    let slc_time = Selector::parse(selectors::PERFORMANCE_TIME).unwrap();
+    let slc_hall = Selector::parse(selectors::PERFORMANCE_HALL).unwrap();
+    doc.select(&slc_performance)
+        .filter_map(|li| {
+            let Some(datetime_str) = li
+                .select(&slc_time)
+                .next()
+                .and_then(|elem| elem.value().attr("datetime"))
+            else {
+                warn!("Skipping performance of {} without a datetime", event_url);
+                return None;
+            };
+            let datetime = datetime::parse_london(datetime_str)
+                .inspect_err(|e| warn!("Skipping performance of {}: {}", event_url, e))
+                .ok()?;
+            let venue = match li.select(&slc_hall).next().map(core::element_text) {
+                Some(hall) => format!("Kings Place ({})", hall),
+                None => "Kings Place".to_string(),
+            };
+
+            let mut concert = core::ConcertData {
+                datetime,
+                url: event_url.to_string(),
+                performers: performers.clone(),
+                title: title.clone(),
+                subtitle: subtitle.clone(),
+                description: Some(description.clone()),
+                programme_pdf_url: None,
+                programme_text: None,
+                pieces: pieces.clone(),
+                venue,
+                category: core::Category::Other,
+                min_price,
+                max_price,
+                availability,
+                media: media::find_media(&description, datetime, event_url),
+                is_wigmore_u35: false,
+                series: series.clone(),
+                production: None,
+            };
+            concert.category = classify::classify(&concert, &tags);
+            Some(concert)
+        })
+        .collect()
+}
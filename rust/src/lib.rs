@@ -1,4 +1,36 @@
+pub mod alerts;
+pub mod archive;
+pub mod churches;
+pub mod classify;
 pub mod core;
+pub mod daemon;
+pub mod datetime;
+pub mod declarative;
+pub mod diff;
+pub mod eno;
+pub mod feed;
+pub mod fixtures;
+pub mod guildhall;
+pub mod healthcheck;
+pub mod http;
+pub mod kingsplace;
+pub mod lso_st_lukes;
+pub mod media;
+pub mod opera;
+pub mod output;
+pub mod programmes;
 pub mod proms;
+pub mod query;
+pub mod ram;
+pub mod rcm;
+pub mod report;
+pub mod royal_opera_house;
+pub mod schema_org;
+pub mod scrape;
+pub mod search;
+pub mod server;
+pub mod source;
 pub mod southbank;
+pub mod validate;
+pub mod webhooks;
 pub mod wigmore;
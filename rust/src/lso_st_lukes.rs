@@ -0,0 +1,222 @@
+use crate::classify;
+use crate::core;
+use crate::http;
+use crate::media;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{info, warn};
+use scraper::{ElementRef, Html, Selector};
+
+pub const LSO_ST_LUKES_URL: &str = "https://www.lso.co.uk/whats-on/?venue=lso-st-lukes";
+
+pub const VENUE: &str = "LSO St Luke's";
+
+/// Selectors for the listing and event pages, which the healthcheck probes as well
+pub mod selectors {
+    pub const EVENT_LINK: &str = "div.event-listing a.event-listing__link";
+    pub const VENUE: &str = "p.event-hero__venue";
+    pub const TITLE: &str = "h1.event-hero__title";
+    pub const SUBTITLE: &str = "p.event-hero__strapline";
+    pub const DESCRIPTION: &str = "div.event-body p";
+    pub const PRICE: &str = "p.event-hero__price";
+    pub const ARTIST: &str = "div.artist-card";
+    pub const ARTIST_NAME: &str = ".artist-card__name";
+    pub const ARTIST_ROLE: &str = ".artist-card__role";
+    pub const PIECE: &str = "ul.event-programme > li";
+    pub const PIECE_COMPOSER: &str = "strong";
+    pub const SERIES: &str = "a.event-hero__series";
+    pub const DATE: &str = "ul.event-dates time";
+}
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping LSO St Luke's from URL: {}", LSO_ST_LUKES_URL);
+    let Some(html) = client.try_get_text(LSO_ST_LUKES_URL).await else {
+        return vec![];
+    };
+
+    let futures = parse_listing(&html, LSO_ST_LUKES_URL)
+        .into_iter()
+        .map(|event_url| async move {
+            match client.try_get_text(&event_url).await {
+                Some(html) => parse_event(&event_url, &html),
+                None => vec![],
+            }
+        });
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = join_all(futures)
+        .await
+        .into_iter()
+        .flatten()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!("Scraped {} concerts from LSO St Luke's", concerts.len());
+    concerts
+}
+
+/// URLs of all the events in the listing. `page_url` is the URL of the listing, which relative
+/// links are resolved against.
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<String> {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let slc_event_link = Selector::parse(selectors::EVENT_LINK).unwrap();
+    // Links without an href can't be followed, so they are left out
+    doc.select(&slc_event_link)
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(|href| base.join(href).ok())
+        .map(|url| url.to_string())
+        .unique()
+        .collect()
+}
+
+/// Parse a single piece in the programme, which is written as e.g. "<strong>Schubert</strong>
+/// Piano Sonata in A, D959". Lines without a composer (e.g. "Interval") are ignored.
+fn parse_piece(li: ElementRef) -> Option<core::Piece> {
+    let slc_composer = Selector::parse(selectors::PIECE_COMPOSER).unwrap();
+    let composer = core::element_text(li.select(&slc_composer).next()?);
+    let full_text = core::element_text(li);
+    let title = full_text
+        .strip_prefix(composer.as_str())
+        .unwrap_or(&full_text)
+        .trim_start_matches([' ', ':', '-', '–'])
+        .trim()
+        .to_string();
+    if title.is_empty() {
+        return None;
+    }
+    Some(core::Piece { composer, title })
+}
+
+/// Parse an event page, giving one concert per performance. The LSO's listings include events at
+/// other venues (mostly the Barbican), which are left out, as are events without a venue or
+/// title.
+pub fn parse_event(event_url: &str, html: &str) -> Vec<core::ConcertData> {
+    let doc = Html::parse_document(html);
+
+    let slc_venue = Selector::parse(selectors::VENUE).unwrap();
+    let Some(venue) = doc.select(&slc_venue).next().map(core::element_text) else {
+        warn!("Skipping {}, which has no venue", event_url);
+        return vec![];
+    };
+    if !venue.contains(VENUE) {
+        return vec![];
+    }
+
+    let slc_title = Selector::parse(selectors::TITLE).unwrap();
+    let Some(title) = doc.select(&slc_title).next().map(core::element_text) else {
+        warn!("Skipping {}, which has no title", event_url);
+        return vec![];
+    };
+
+    let slc_subtitle = Selector::parse(selectors::SUBTITLE).unwrap();
+    let subtitle = doc
+        .select(&slc_subtitle)
+        .next()
+        .map(core::element_text)
+        .filter(|s| !s.is_empty());
+
+    let slc_description = Selector::parse(selectors::DESCRIPTION).unwrap();
+    let description = doc
+        .select(&slc_description)
+        .map(core::element_text)
+        .join("\n");
+
+    // e.g. "£5.50 – £20", or "Free, no booking required"
+    let slc_price = Selector::parse(selectors::PRICE).unwrap();
+    let price_text = doc
+        .select(&slc_price)
+        .next()
+        .map(core::element_text)
+        .unwrap_or_default();
+    let prices = core::parse_prices(&price_text);
+    let min_price = prices.iter().min().copied();
+    let max_price = prices.iter().max().copied();
+    let availability = core::parse_availability(&price_text, min_price, max_price);
+
+    let slc_artists = Selector::parse(selectors::ARTIST).unwrap();
+    let slc_artist_name = Selector::parse(selectors::ARTIST_NAME).unwrap();
+    let slc_artist_role = Selector::parse(selectors::ARTIST_ROLE).unwrap();
+    let performers: Vec<core::Performer> = doc
+        .select(&slc_artists)
+        .filter_map(|card| {
+            Some(core::Performer {
+                name: card
+                    .select(&slc_artist_name)
+                    .next()
+                    .map(core::element_text)?,
+                instrument: card
+                    .select(&slc_artist_role)
+                    .next()
+                    .map(core::element_text)
+                    .filter(|s| !s.is_empty()),
+            })
+        })
+        .collect();
+
+    let slc_pieces = Selector::parse(selectors::PIECE).unwrap();
+    let pieces: Vec<core::Piece> = doc.select(&slc_pieces).filter_map(parse_piece).collect();
+
+    // e.g. the BBC Radio 3 Lunchtime Concerts
+    let slc_series = Selector::parse(selectors::SERIES).unwrap();
+    let series: Vec<core::Series> = doc
+        .select(&slc_series)
+        .map(|elem| core::Series {
+            name: core::element_text(elem),
+            organiser: "LSO".to_string(),
+            start_date: None,
+            end_date: None,
+            url: elem
+                .value()
+                .attr("href")
+                .and_then(|href| reqwest::Url::parse(event_url).ok()?.join(href).ok())
+                .map(|url| url.to_string()),
+        })
+        .collect();
+    let tags: Vec<String> = series.iter().map(|s| s.name.clone()).collect();
+
+    // The datetime attribute includes the UTC offset, e.g. "2026-01-15T13:00:00+00:00"
+    let slc_dates = Selector::parse(selectors::DATE).unwrap();
+    doc.select(&slc_dates)
+        .filter_map(|elem| {
+            let Some(datetime_str) = elem.value().attr("datetime") else {
+                warn!("Skipping performance of {} without a datetime", event_url);
+                return None;
+            };
+            let datetime = match DateTime::parse_from_rfc3339(datetime_str) {
+                Ok(datetime) => datetime.with_timezone(&Utc),
+                Err(e) => {
+                    warn!(
+                        "Skipping performance of {} at unexpected datetime {}: {}",
+                        event_url, datetime_str, e
+                    );
+                    return None;
+                }
+            };
+
+            let mut concert = core::ConcertData {
+                datetime,
+                url: event_url.to_string(),
+                performers: performers.clone(),
+                title: title.clone(),
+                subtitle: subtitle.clone(),
+                description: Some(description.clone()),
+                programme_pdf_url: None,
+                programme_text: None,
+                pieces: pieces.clone(),
+                venue: VENUE.to_string(),
+                category: core::Category::Other,
+                min_price,
+                max_price,
+                availability,
+                media: media::find_media(&description, datetime, event_url),
+                is_wigmore_u35: false,
+                series: series.clone(),
+                production: None,
+            };
+            concert.category = classify::classify(&concert, &tags);
+            Some(concert)
+        })
+        .collect()
+}
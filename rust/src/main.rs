@@ -1,110 +1,615 @@
-use log::{debug, info};
-use london_classical::{core, proms, southbank, wigmore};
-use reqwest::header;
-use std::fs::{create_dir_all, File};
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
+use log::{error, info, warn};
+use london_classical::source::Source;
+use london_classical::{
+    alerts, archive, core, daemon, diff, feed, fixtures, healthcheck, output, programmes, report,
+    scrape, search, server, validate, webhooks,
+};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::create_dir_all;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
 
-fn envvar_is_empty_or_undefined(var: &str) -> bool {
-    match std::env::var(var) {
-        Ok(value) => value.is_empty(),
-        Err(_) => true,
+/// Directory where concerts.json (and everything else we generate) is written to
+fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/assets")
+}
+
+/// Directory for files which are kept between runs but aren't published with the website, which
+/// can be set with $LDNCLS_DATA_DIR
+fn data_dir() -> PathBuf {
+    match std::env::var("LDNCLS_DATA_DIR") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => Path::new(env!("CARGO_MANIFEST_DIR")).join("data"),
     }
 }
 
+/// Where the programme texts are kept (see `programmes::write_texts`)
+fn programmes_path() -> PathBuf {
+    data_dir().join("programmes.json")
+}
+
+/// Directory where the Atom feeds are written to. Everything in `public` is copied as it is into
+/// the website when it's built, so the feeds end up at e.g. `<site>/feeds/all.xml`.
+fn feeds_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../public/feeds")
+}
+
+const USAGE: &str = "Usage:
+    london_classical [scrape]              Scrape all venues and write concerts.json
+    london_classical daemon                Keep running, scraping each venue on its own schedule
+                                           and keeping concerts.json up to date
+    london_classical diff OLD NEW [--json] Compare two concerts.json files
+    london_classical alerts OLD NEW        Generate (and send) saved-search alert digests for
+                                           concerts in NEW which are not in OLD
+    london_classical validate [CURRENT [PREVIOUS]]
+                                           Check concerts.json for problems before publishing
+                                           (exits with status 1 if any errors are found)
+    london_classical search QUERY...       Search concerts.json, e.g.
+                                           `search 'composer:dvorak performer:\"pavel haas\"'`
+    london_classical serve [FILE]          Serve concerts over a JSON API (the address can be set
+                                           with $LDNCLS_SERVE_ADDR, default 127.0.0.1:3000)
+    london_classical healthcheck           Fetch a listing and an event page for each enabled venue
+                                           and report selectors which match nothing (exits with
+                                           status 1 if any required ones are missing)
+    london_classical refresh-fixtures RECORD_DIR [PATH...]
+                                           Replace the pages saved for tests with the ones fetched
+                                           in a run with $LDNCLS_RECORD_DIR=RECORD_DIR (only those
+                                           under the given paths, e.g. `kingsplace`, if any)";
+
 #[tokio::main]
 async fn main() {
     pretty_env_logger::init();
 
-    let mut headers = header::HeaderMap::new();
-    headers.insert(header::ACCEPT, header::HeaderValue::from_static("text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7"));
-
-    let client = reqwest::Client::builder()
-        .default_headers(headers)
-        .user_agent("penelopeysm/london-classical/0.1")
-        .build()
-        .unwrap();
-
-    // Fetch Wigmore concerts, first reading from $LDNCLS_WIGMORE_MAX and defaulting to 220 if not
-    // given. If you push it a bit more it starts to rate limit
-    let max_wigmore_concerts = {
-        match std::env::var("LDNCLS_WIGMORE_MAX") {
-            Ok(s) => match s.as_str() {
-                "all" => None,
-                _ => Some(s.parse::<usize>().unwrap()),
-            },
-            Err(_) => Some(220),
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|s| s.as_str()) {
+        None | Some("scrape") => scrape().await,
+        Some("daemon") => run_daemon().await,
+        Some("diff") => run_diff(&args[1..]),
+        Some("alerts") => run_alerts(&args[1..]).await,
+        Some("validate") => run_validate(&args[1..]),
+        Some("serve") => run_serve(&args[1..]).await,
+        Some("search") => run_search(&args[1..]),
+        Some("healthcheck") => run_healthcheck().await,
+        Some("refresh-fixtures") => run_refresh_fixtures(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => println!("{}", USAGE),
+        Some(cmd) => {
+            eprintln!("Unknown command: {}\n\n{}", cmd, USAGE);
+            std::process::exit(2);
         }
+    }
+}
+
+fn run_diff(args: &[String]) {
+    let json_output = args.iter().any(|arg| arg == "--json");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--json").collect();
+    let [old_path, new_path] = paths[..] else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
     };
-    debug!("max_wigmore_concerts: {:?}", max_wigmore_concerts);
-    let scrape_wigmore = envvar_is_empty_or_undefined("LDNCLS_WIGMORE_DISABLE");
-    let mut wigmore_concerts = if scrape_wigmore {
-        info!(
-            "Scraping Wigmore Hall concerts (up to a maximum of {:?})",
-            max_wigmore_concerts
-        );
-        let cs = wigmore::get_concerts(&client, max_wigmore_concerts).await;
-        info!("Found {} Wigmore Hall concerts", cs.len());
-        cs
+    let old = core::read_concerts(Path::new(old_path))
+        .unwrap_or_else(|| panic!("File not found: {}", old_path));
+    let new = core::read_concerts(Path::new(new_path))
+        .unwrap_or_else(|| panic!("File not found: {}", new_path));
+
+    let concerts_diff = diff::diff(&old, &new);
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&concerts_diff).unwrap());
     } else {
-        info!("$LDNCLS_WIGMORE_DISABLE not empty; skipping Wigmore Hall concerts");
-        vec![]
+        print!("{}", concerts_diff);
+    }
+}
+
+/// Path to the alerts configuration file, if alerts are enabled
+fn alerts_config_path() -> Option<PathBuf> {
+    match std::env::var("LDNCLS_ALERTS_CONFIG") {
+        Ok(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => None,
+    }
+}
+
+async fn send_alerts(new_concerts: &[&core::Concert]) {
+    let Some(config_path) = alerts_config_path() else {
+        info!("$LDNCLS_ALERTS_CONFIG not set; skipping alerts");
+        return;
     };
+    let config = alerts::AlertsConfig::read(&config_path);
+    // The digests contain subscribers' email addresses, so they mustn't be published
+    alerts::process(&config, new_concerts, &data_dir().join("digests")).await;
+}
 
-    // Fetch Proms
-    let scrape_proms = envvar_is_empty_or_undefined("LDNCLS_PROMS_DISABLE");
-    let mut proms_concerts = if scrape_proms {
-        info!("Scraping Proms");
-        let cs = proms::scrape(&client).await;
-        info!("Found {} Proms", cs.len());
-        cs
-    } else {
-        info!("$LDNCLS_PROMS_DISABLE not empty; skipping Proms");
-        vec![]
+/// Send events to the webhooks configured in $LDNCLS_WEBHOOKS_CONFIG, if it is set
+async fn send_webhook_events(events: &[webhooks::Event]) {
+    match std::env::var("LDNCLS_WEBHOOKS_CONFIG") {
+        Ok(path) if !path.is_empty() => {
+            let config = webhooks::WebhooksConfig::read(Path::new(&path));
+            webhooks::deliver(&config, events).await;
+        }
+        _ => info!("$LDNCLS_WEBHOOKS_CONFIG not set; skipping webhooks"),
+    }
+}
+
+async fn run_alerts(args: &[String]) {
+    let [old_path, new_path] = args else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
     };
+    let old = core::read_concerts(Path::new(old_path))
+        .unwrap_or_else(|| panic!("File not found: {}", old_path));
+    let new = core::read_concerts(Path::new(new_path))
+        .unwrap_or_else(|| panic!("File not found: {}", new_path));
+    if alerts_config_path().is_none() {
+        eprintln!("$LDNCLS_ALERTS_CONFIG must be set to the path of the alerts configuration");
+        std::process::exit(2);
+    }
+    // Use the diff rather than just comparing IDs, so that rescheduled concerts don't count as new
+    let concerts_diff = diff::diff(&old, &new);
+    send_alerts(&concerts_diff.added.iter().collect::<Vec<_>>()).await;
+}
 
-    // Southbank Centre
-    let scrape_southbank = envvar_is_empty_or_undefined("LDNCLS_SOUTHBANK_DISABLE");
-    let mut southbank_concerts = if scrape_southbank {
-        info!("Scraping Southbank Centre concerts");
-        let cs = southbank::scrape(&client).await;
-        info!("Found {} Southbank Centre concerts", cs.len());
-        cs
-    } else {
-        info!("$LDNCLS_SOUTHBANK_DISABLE not empty; skipping Southbank Centre concerts");
-        vec![]
+fn run_validate(args: &[String]) {
+    let (current_path, previous_path) = match args {
+        [] => (
+            output_dir().join("concerts.json"),
+            data_dir().join("concerts.previous.json"),
+        ),
+        [current] => (
+            PathBuf::from(current),
+            data_dir().join("concerts.previous.json"),
+        ),
+        [current, previous] => (PathBuf::from(current), PathBuf::from(previous)),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
     };
+    let current = core::read_concerts(&current_path)
+        .unwrap_or_else(|| panic!("File not found: {}", current_path.display()));
+    let previous = core::read_concerts(&previous_path);
+    if previous.is_none() {
+        info!(
+            "No previous concerts found at {}; skipping checks against previous run",
+            previous_path.display()
+        );
+    }
 
-    // Concatenate and sort
-    let mut full_concerts = vec![];
-    full_concerts.append(&mut wigmore_concerts);
-    full_concerts.append(&mut proms_concerts);
-    full_concerts.append(&mut southbank_concerts);
-    full_concerts.sort_by_key(|concert| concert.datetime);
-
-    // Add IDs in
-    let full_concerts_with_ids: Vec<core::Concert> = full_concerts
-        .into_iter()
-        .map(core::add_id_to_concert)
-        .collect();
-
-    // Check uniqueness of IDs
-    let mut all_ids: Vec<&str> = full_concerts_with_ids
+    // Sources which were disabled for this run have no concerts, which isn't a drop
+    let options = scrape::ScrapeOptions::from_env(&data_dir().join("cache"));
+    let issues = validate::validate(
+        &current,
+        previous.as_deref(),
+        &options.enabled_sources,
+        Utc::now(),
+    );
+    for issue in &issues {
+        println!("{}", issue);
+    }
+    let n_errors = issues
         .iter()
-        .map(|c| c.id.as_str())
-        .collect();
-    all_ids.sort();
-
-    let n_concerts = all_ids.len();
-    info!("Found {} concerts in total", n_concerts);
-    if n_concerts > 0 {
-        for i in 0..n_concerts - 1 {
-            if all_ids[i] == all_ids[i + 1] {
-                panic!("Duplicate ID: {}", all_ids[i]);
+        .filter(|issue| issue.severity == validate::Severity::Error)
+        .count();
+    println!(
+        "{} concerts checked: {} errors, {} warnings",
+        current.len(),
+        n_errors,
+        issues.len() - n_errors
+    );
+    if validate::has_errors(&issues) {
+        std::process::exit(1);
+    }
+}
+
+async fn run_serve(args: &[String]) {
+    let path = match args {
+        [] => output_dir().join("concerts.json"),
+        [path] => PathBuf::from(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let mut concerts =
+        core::read_concerts(&path).unwrap_or_else(|| panic!("File not found: {}", path.display()));
+    programmes::read_texts(&programmes_path(), &mut concerts);
+    let addr = std::env::var("LDNCLS_SERVE_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:3000".to_string())
+        .parse()
+        .expect("Invalid address in $LDNCLS_SERVE_ADDR");
+    server::serve(concerts, addr).await;
+}
+
+async fn run_healthcheck() {
+    let options = scrape::ScrapeOptions::from_env(&data_dir().join("cache"));
+    let client = scrape::build_client();
+    let reports = healthcheck::run(&client, &options.enabled_sources).await;
+    for report in &reports {
+        print!("{}", report);
+    }
+    let n_broken = reports.iter().filter(|report| !report.is_healthy()).count();
+    println!("{} venues checked: {} broken", reports.len(), n_broken);
+    if n_broken > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn run_refresh_fixtures(args: &[String]) {
+    let Some((record_dir, only)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let recordings = fixtures::read_recordings(Path::new(record_dir));
+    info!(
+        "Read {} recorded pages from {}",
+        recordings.len(),
+        record_dir
+    );
+    let results = fixtures::refresh(&fixtures::fixtures_dir(), &recordings, only);
+    for (fixture, result) in &results {
+        println!("{}: {}", fixture.path, result);
+    }
+    println!(
+        "Run `LDNCLS_UPDATE_SNAPSHOTS=1 cargo test --test snapshots` to update the snapshots, and \
+         check the changes to them before committing"
+    );
+}
+
+fn run_search(args: &[String]) {
+    if args.is_empty() {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    }
+    let query = args.join(" ");
+    let path = output_dir().join("concerts.json");
+    let mut concerts =
+        core::read_concerts(&path).unwrap_or_else(|| panic!("File not found: {}", path.display()));
+    programmes::read_texts(&programmes_path(), &mut concerts);
+
+    let index = search::SearchIndex::new(&concerts);
+    let hits = index.search(&query);
+    for hit in &hits {
+        let c = &concerts[hit.index];
+        println!(
+            "{:>6.1}  {}  {} | {} ({})",
+            hit.score,
+            c.concert
+                .datetime
+                .with_timezone(&chrono_tz::Europe::London)
+                .format("%Y-%m-%d %H:%M"),
+            c.concert.venue,
+            c.concert.title,
+            c.id
+        );
+    }
+    println!("{} results", hits.len());
+}
+
+/// Get a readable message out of a panic
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => "unknown error".to_string(),
+        },
+    }
+}
+
+/// The scrapers panic if anything unexpected happens, so we catch that to be able to report the
+/// failure
+async fn catch_failure<T>(f: impl Future<Output = T>) -> Result<T, String> {
+    AssertUnwindSafe(f)
+        .catch_unwind()
+        .await
+        .map_err(panic_message)
+}
+
+async fn report_failure(message: &str) {
+    error!("Scraping failed: {}", message);
+    send_webhook_events(&[webhooks::run_failed_event(message, Utc::now())]).await;
+}
+
+/// Scrape a single source, catching any failure. Pages which can't be fetched are skipped, so a
+/// source also counts as failed if it found no concerts because of errors. A failure comes with a
+/// report of what was fetched before it happened.
+async fn scrape_one(
+    source: Source,
+    client: &london_classical::http::Client,
+    options: &scrape::ScrapeOptions,
+) -> Result<(Vec<core::ConcertData>, report::SourceReport), (String, report::SourceReport)> {
+    let client = client.with_new_stats();
+    let start = Instant::now();
+    let failed = |message: String| {
+        let source_report =
+            report::SourceReport::failed(source, message.clone(), client.stats(), start.elapsed());
+        (message, source_report)
+    };
+    let (concerts, source_report) = catch_failure(scrape::scrape_source(source, &client, options))
+        .await
+        .map_err(|message| failed(format!("{}: {}", source.name(), message)))?;
+    if concerts.is_empty() && source_report.errors > 0 {
+        return Err(failed(format!(
+            "{}: no concerts found, with {} errors",
+            source.name(),
+            source_report.errors
+        )));
+    }
+    Ok((concerts, source_report))
+}
+
+/// Scrape all venues and write the results to concerts.json
+async fn scrape() {
+    let started_at = Utc::now();
+    let start = Instant::now();
+    let client = scrape::build_client();
+    let options = scrape::ScrapeOptions::from_env(&data_dir().join("cache"));
+
+    // A source which fails keeps its concerts from the previous run, in the same way as sources
+    // which the daemon hasn't got round to scraping yet
+    let mut state = daemon::DaemonState::default();
+    let mut source_reports = vec![];
+    for source in &options.enabled_sources {
+        match scrape_one(*source, &client, &options).await {
+            Ok((concerts, source_report)) => {
+                state.record(*source, started_at, concerts);
+                source_reports.push(source_report);
+            }
+            Err((message, source_report)) => {
+                report_failure(&message).await;
+                source_reports.push(source_report);
             }
         }
     }
 
-    let output_dir = env!("CARGO_MANIFEST_DIR").to_string() + "/../src/assets";
+    let previous = output::read(&output_dir().join("concerts.json"));
+    let (previous_concerts, previous_sources) = match &previous {
+        Some(previous) => (previous.concerts(), previous.sources()),
+        None => (&[][..], &[][..]),
+    };
+    // Disabled sources keep their previous concerts too, so that disabling a source for a run
+    // doesn't make all of its concerts look removed (and then added again when it's re-enabled)
+    let merged = state.merged_concerts(&Source::ALL, previous_concerts, started_at);
+    let scraped_at = state.scraped_at(&Source::ALL, previous_sources);
+    publish(
+        scrape::add_ids(merged),
+        scraped_at,
+        source_reports,
+        started_at,
+        start,
+    )
+    .await;
+}
+
+/// Write concerts.json, together with the feeds, alerts, webhooks, archive, and report which go
+/// with it. `scraped_at` says when each source whose concerts are included was last scraped.
+async fn publish(
+    full_concerts_with_ids: Vec<core::Concert>,
+    scraped_at: BTreeMap<Source, DateTime<Utc>>,
+    source_reports: Vec<report::SourceReport>,
+    started_at: DateTime<Utc>,
+    start: Instant,
+) {
+    info!("Found {} concerts in total", full_concerts_with_ids.len());
+    let envelope = output::Envelope::new(full_concerts_with_ids, &scraped_at, Utc::now());
+    let full_concerts_with_ids = &envelope.concerts;
+    let output_dir = output_dir();
     create_dir_all(&output_dir).unwrap();
-    let output_file = File::create(output_dir + "/concerts.json").unwrap();
-    serde_json::to_writer_pretty(output_file, &full_concerts_with_ids).unwrap();
+    let output_path = output_dir.join("concerts.json");
+
+    let previous = core::read_concerts(&output_path);
+
+    // Generate feeds of recently announced concerts. If we don't know when concerts were first
+    // seen yet, those in the previous output (or, if there isn't one, in this one) are taken to
+    // be old, so that the feeds aren't flooded with every concert at once.
+    let first_seen_path = data_dir().join("first_seen.json");
+    let mut first_seen = feed::FirstSeen::read(&first_seen_path).unwrap_or_else(|| {
+        feed::FirstSeen::already_seen(previous.as_deref().unwrap_or(full_concerts_with_ids))
+    });
+    first_seen.update(full_concerts_with_ids, started_at);
+    first_seen.write(&first_seen_path);
+    feed::write_feeds(
+        &feeds_dir(),
+        &first_seen,
+        full_concerts_with_ids,
+        started_at,
+    );
+    info!("Wrote Atom feeds to {}", feeds_dir().display());
+
+    // Alert about concerts which have never been seen before, rather than those which weren't in
+    // the previous output, so that concerts which were missing for a while (or were rescheduled)
+    // aren't alerted about again
+    send_alerts(&first_seen.new_at(full_concerts_with_ids, started_at)).await;
+
+    match previous {
+        Some(previous_concerts) => {
+            // Keep a copy of the previous output around so that `validate` can compare against it
+            create_dir_all(data_dir()).unwrap();
+            std::fs::copy(&output_path, data_dir().join("concerts.previous.json")).unwrap();
+
+            let concerts_diff = diff::diff(&previous_concerts, full_concerts_with_ids);
+            let events =
+                webhooks::events_from_diff(&concerts_diff, full_concerts_with_ids, started_at);
+            send_webhook_events(&events).await;
+        }
+        None => info!("No previous concerts.json found; skipping webhooks"),
+    }
+
+    // Anything which can't read the envelope yet can still have the bare array of concerts
+    let bare = std::env::var("LDNCLS_BARE_ARRAY").is_ok_and(|value| !value.is_empty());
+    envelope.write(&output_path, bare);
+    programmes::write_texts(&programmes_path(), full_concerts_with_ids);
+
+    // Optionally record the run in the SQLite archive
+    if let Ok(db_path) = std::env::var("LDNCLS_SQLITE_DB") {
+        if !db_path.is_empty() {
+            info!("Recording concerts in SQLite archive at {}", db_path);
+            let mut archive =
+                archive::Archive::open(Path::new(&db_path)).expect("Failed to open archive");
+            archive
+                .record_snapshot(full_concerts_with_ids, started_at)
+                .expect("Failed to record concerts in archive");
+        }
+    }
+
+    let run_report = report::RunReport {
+        started_at,
+        duration_secs: start.elapsed().as_secs_f64(),
+        n_concerts: full_concerts_with_ids.len(),
+        sources: source_reports,
+    };
+    run_report.write(&data_dir());
+    print!("{}", run_report);
+}
+
+/// Path to the daemon's state file, which can be set with $LDNCLS_DAEMON_STATE
+fn daemon_state_path() -> PathBuf {
+    match std::env::var("LDNCLS_DAEMON_STATE") {
+        Ok(path) if !path.is_empty() => PathBuf::from(path),
+        _ => data_dir().join("daemon_state.json"),
+    }
+}
+
+/// Wait for Ctrl-C or SIGTERM in the background. The returned receiver changes to `true` once
+/// either of them is received.
+fn shutdown_signal() -> watch::Receiver<bool> {
+    let (tx, rx) = watch::channel(false);
+    let mut sigterm = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = sigterm.recv() => {}
+        }
+        info!("Received shutdown signal");
+        tx.send(true).unwrap();
+    });
+    rx
+}
+
+/// Run the command in $LDNCLS_DAEMON_PUBLISH_CMD (if set) after concerts.json has been updated,
+/// e.g. to upload it
+async fn run_publish_command() {
+    let Ok(command) = std::env::var("LDNCLS_DAEMON_PUBLISH_CMD") else {
+        return;
+    };
+    if command.is_empty() {
+        return;
+    }
+    info!("Running publish command: {}", command);
+    match tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .status()
+        .await
+    {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("Publish command failed with {}", status),
+        Err(e) => warn!("Failed to run publish command: {}", e),
+    }
+}
+
+/// Keep running, scraping each source whenever it is due according to its schedule (see
+/// `daemon::schedule`), and updating concerts.json after each round of scraping. Stops on Ctrl-C
+/// or SIGTERM: a scrape in progress is abandoned, but sources which were already scraped are
+/// written out first.
+async fn run_daemon() {
+    let client = scrape::build_client();
+    let options = scrape::ScrapeOptions::from_env(&data_dir().join("cache"));
+    let state_path = daemon_state_path();
+    let output_path = output_dir().join("concerts.json");
+    let mut state = daemon::DaemonState::read(&state_path);
+    // Sources which failed are retried after a while, rather than immediately
+    let mut retry_at: HashMap<Source, DateTime<Utc>> = HashMap::new();
+    let mut shutdown = shutdown_signal();
+    info!("Daemon started; state is saved in {}", state_path.display());
+
+    loop {
+        let now = Utc::now();
+        let next_runs: Vec<(Source, DateTime<Utc>)> = options
+            .enabled_sources
+            .iter()
+            .map(|source| {
+                let next_run = state.next_run(*source, now);
+                match retry_at.get(source) {
+                    Some(retry) => (*source, next_run.max(*retry)),
+                    None => (*source, next_run),
+                }
+            })
+            .collect();
+        let due: Vec<Source> = next_runs
+            .iter()
+            .filter(|(_, next_run)| *next_run <= now)
+            .map(|(source, _)| *source)
+            .collect();
+
+        if due.is_empty() {
+            let Some((source, wake_at)) = next_runs.iter().min_by_key(|(_, next_run)| *next_run)
+            else {
+                info!("No sources enabled; stopping");
+                return;
+            };
+            info!(
+                "Next run: {} at {}",
+                source.name(),
+                wake_at
+                    .with_timezone(&chrono_tz::Europe::London)
+                    .format("%Y-%m-%d %H:%M")
+            );
+            let sleep_for = (*wake_at - now).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(sleep_for) => continue,
+                _ = shutdown.wait_for(|stop| *stop) => break,
+            }
+        }
+
+        let started_at = Utc::now();
+        let start = Instant::now();
+        let mut source_reports = vec![];
+        let mut interrupted = false;
+        for source in due {
+            tokio::select! {
+                result = scrape_one(source, &client, &options) => {
+                    match result {
+                        Ok((concerts, source_report)) => {
+                            state.record(source, started_at, concerts);
+                            retry_at.remove(&source);
+                            source_reports.push(source_report);
+                        }
+                        Err((message, source_report)) => {
+                            report_failure(&message).await;
+                            retry_at.insert(source, Utc::now() + daemon::RETRY_DELAY);
+                            source_reports.push(source_report);
+                        }
+                    }
+                }
+                _ = shutdown.wait_for(|stop| *stop) => {
+                    info!("Abandoning scrape of {}", source.name());
+                    interrupted = true;
+                    break;
+                }
+            }
+        }
+
+        if !source_reports.is_empty() {
+            let previous = output::read(&output_path);
+            let (previous_concerts, previous_sources) = match &previous {
+                Some(previous) => (previous.concerts(), previous.sources()),
+                None => (&[][..], &[][..]),
+            };
+            let merged = state.merged_concerts(&Source::ALL, previous_concerts, Utc::now());
+            let scraped_at = state.scraped_at(&Source::ALL, previous_sources);
+            let concerts = scrape::add_ids(merged);
+            publish(concerts, scraped_at, source_reports, started_at, start).await;
+            state.write(&state_path);
+            run_publish_command().await;
+        }
+        if interrupted || *shutdown.borrow() {
+            break;
+        }
+    }
+    info!("Daemon stopped");
 }
@@ -0,0 +1,244 @@
+use crate::core::{Broadcast, BroadcastMedium, Media, OnDemand};
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde_json::Value;
+
+/// Channels which concerts are commonly broadcast on
+const CHANNELS: [(&str, BroadcastMedium); 7] = [
+    ("BBC Radio 3", BroadcastMedium::Radio),
+    ("BBC Radio 4", BroadcastMedium::Radio),
+    ("Classic FM", BroadcastMedium::Radio),
+    ("BBC One", BroadcastMedium::Tv),
+    ("BBC Two", BroadcastMedium::Tv),
+    ("BBC Four", BroadcastMedium::Tv),
+    ("Sky Arts", BroadcastMedium::Tv),
+];
+
+/// Whether a channel is radio or TV. Channels which aren't in `CHANNELS` are guessed from their
+/// name.
+fn medium_of(channel: &str) -> BroadcastMedium {
+    match CHANNELS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(channel))
+    {
+        Some((_, medium)) => *medium,
+        None if ["radio", "fm"]
+            .iter()
+            .any(|word| channel.to_lowercase().contains(word)) =>
+        {
+            BroadcastMedium::Radio
+        }
+        None => BroadcastMedium::Tv,
+    }
+}
+
+/// Read media information from the structured data in a page's JSON props, as Wigmore Hall's
+/// pages have:
+///
+/// - `livestream`: `null`, or an object with the stream's `url` and, if it can be watched
+///   afterwards, `availableUntil` (an RFC 3339 timestamp)
+/// - `broadcasts`: a list of objects with the `channel` and whether the broadcast is `live`
+///
+/// Returns `None` if the props have neither field, in which case the page's text has to be used
+/// instead (see `find_media`).
+pub fn media_from_props(props: &Value, concert_datetime: DateTime<Utc>) -> Option<Media> {
+    let livestream = props.get("livestream")?;
+    let broadcasts = props.get("broadcasts")?;
+    let on_demand = livestream["availableUntil"]
+        .as_str()
+        .and_then(|until| DateTime::parse_from_rfc3339(until).ok())
+        .map(|until| OnDemand {
+            from: concert_datetime,
+            until: Some(until.with_timezone(&Utc)),
+        });
+    let broadcasts = broadcasts
+        .as_array()
+        .map(|broadcasts| {
+            broadcasts
+                .iter()
+                .filter_map(|broadcast| {
+                    let channel = broadcast["channel"].as_str()?.trim();
+                    Some(Broadcast {
+                        channel: channel.to_string(),
+                        medium: medium_of(channel),
+                        is_live: broadcast["live"].as_bool().unwrap_or(false),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(Media {
+        livestream_url: livestream["url"].as_str().map(|url| url.to_string()),
+        on_demand,
+        broadcasts,
+    })
+}
+
+fn sentences(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(['.', '\n', '!'])
+        .map(|sentence| sentence.trim().to_lowercase())
+        .filter(|sentence| !sentence.is_empty())
+}
+
+/// Find broadcasts mentioned in a piece of text, e.g. "This concert will be broadcast live on BBC
+/// Radio 3". Each channel is only returned once.
+pub fn find_broadcasts(text: &str) -> Vec<Broadcast> {
+    let mut broadcasts: Vec<Broadcast> = vec![];
+    for sentence in sentences(text) {
+        let is_about_broadcast = ["broadcast", "live on", "recorded for", "on air"]
+            .iter()
+            .any(|phrase| sentence.contains(phrase));
+        if !is_about_broadcast {
+            continue;
+        }
+        for (channel, medium) in CHANNELS {
+            let channel_lower = channel.to_lowercase();
+            if sentence.contains(&channel_lower) && !broadcasts.iter().any(|b| b.channel == channel)
+            {
+                // The same sentence can mention several channels, e.g. "Live on BBC Radio 3,
+                // broadcast on BBC Four on 3 August"
+                let is_live = sentence.contains(&format!("live on {}", channel_lower))
+                    || sentence.contains(&format!("live by {}", channel_lower))
+                    || (sentence.contains("broadcast live") && !sentence.contains("recorded"));
+                broadcasts.push(Broadcast {
+                    channel: channel.to_string(),
+                    medium,
+                    is_live,
+                });
+            }
+        }
+    }
+    broadcasts
+}
+
+/// Whether a piece of text says that the concert is livestreamed
+pub fn mentions_livestream(text: &str) -> bool {
+    let re = Regex::new(r"(?i)live[- ]?stream|streamed live").unwrap();
+    re.is_match(text)
+}
+
+/// Find how long a recording is available for after the concert, from text like "available to
+/// watch on demand for 30 days". Returns `None` if the text doesn't mention it; if it mentions
+/// on-demand availability without a duration, the end of the window is unknown.
+pub fn find_on_demand(text: &str, concert_datetime: DateTime<Utc>) -> Option<OnDemand> {
+    let days_re =
+        Regex::new(r"(?i)(?:on demand|to watch|to listen|catch up)[^.]*?for (\d+) days").unwrap();
+    if let Some(caps) = days_re.captures(text) {
+        let days: i64 = caps[1].parse().unwrap();
+        return Some(OnDemand {
+            from: concert_datetime,
+            until: Some(concert_datetime + Duration::days(days)),
+        });
+    }
+    let re = Regex::new(r"(?i)on[- ]demand").unwrap();
+    re.is_match(text).then_some(OnDemand {
+        from: concert_datetime,
+        until: None,
+    })
+}
+
+/// Work out all the media information which is mentioned in a piece of text. If the text says the
+/// concert is livestreamed, but doesn't say where, `default_livestream_url` is used.
+pub fn find_media(
+    text: &str,
+    concert_datetime: DateTime<Utc>,
+    default_livestream_url: &str,
+) -> Media {
+    Media {
+        livestream_url: mentions_livestream(text).then(|| default_livestream_url.to_string()),
+        on_demand: find_on_demand(text, concert_datetime),
+        broadcasts: find_broadcasts(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn concert_datetime() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 6, 19, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn props() {
+        let props = json!({
+            "title": "Pavel Haas Quartet",
+            "livestream": {
+                "url": "https://www.youtube.com/watch?v=abc123",
+                "availableUntil": "2026-04-05T19:30:00Z"
+            },
+            "broadcasts": [
+                {"channel": "BBC Radio 3", "live": true},
+                {"channel": "BBC Four", "live": false},
+                {"channel": "Scala Radio"},
+                {"live": true}
+            ]
+        });
+        let media = media_from_props(&props, concert_datetime()).unwrap();
+        assert_eq!(
+            media.livestream_url.as_deref(),
+            Some("https://www.youtube.com/watch?v=abc123")
+        );
+        assert_eq!(
+            media.on_demand,
+            Some(OnDemand {
+                from: concert_datetime(),
+                until: Some(Utc.with_ymd_and_hms(2026, 4, 5, 19, 30, 0).unwrap()),
+            })
+        );
+        let broadcasts: Vec<(&str, BroadcastMedium, bool)> = media
+            .broadcasts
+            .iter()
+            .map(|b| (b.channel.as_str(), b.medium, b.is_live))
+            .collect();
+        assert_eq!(
+            broadcasts,
+            vec![
+                ("BBC Radio 3", BroadcastMedium::Radio, true),
+                ("BBC Four", BroadcastMedium::Tv, false),
+                ("Scala Radio", BroadcastMedium::Radio, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn props_without_media() {
+        let props = json!({"livestream": null, "broadcasts": []});
+        assert_eq!(
+            media_from_props(&props, concert_datetime()),
+            Some(Media::default())
+        );
+        assert_eq!(
+            media_from_props(&json!({"title": "Recital"}), concert_datetime()),
+            None
+        );
+    }
+
+    #[test]
+    fn text() {
+        let text = "Live on BBC Radio 3, and broadcast on BBC Four on 3 August. The concert will \
+                    also be livestreamed, and available to watch on demand for 30 days.";
+        let media = find_media(text, concert_datetime(), "https://example.com/concert");
+        assert_eq!(
+            media.livestream_url.as_deref(),
+            Some("https://example.com/concert")
+        );
+        assert_eq!(
+            media.on_demand.unwrap().until,
+            Some(concert_datetime() + Duration::days(30))
+        );
+        let broadcasts: Vec<(&str, bool)> = media
+            .broadcasts
+            .iter()
+            .map(|b| (b.channel.as_str(), b.is_live))
+            .collect();
+        assert_eq!(broadcasts, vec![("BBC Radio 3", true), ("BBC Four", false)]);
+
+        assert_eq!(
+            find_media("A recital of songs.", concert_datetime(), ""),
+            Media::default()
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::core::{Performer, Production};
+use regex::Regex;
+
+fn parse_count(word: &str) -> Option<u32> {
+    match word {
+        "no" | "without" => Some(0),
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        "four" => Some(4),
+        n => n.parse().ok(),
+    }
+}
+
+/// Parse a running time, e.g. "Approximately 3 hours 10 minutes, including two intervals", into
+/// the total length in minutes and the number of intervals
+pub fn parse_running_time(text: &str) -> (Option<u32>, Option<u32>) {
+    let text = text.to_lowercase();
+    let hours_re = Regex::new(r"(\d+)\s*(?:hours?|hrs?|h)\b").unwrap();
+    let minutes_re = Regex::new(r"(\d+)\s*(?:minutes?|mins?|m)\b").unwrap();
+    let hours = hours_re
+        .captures(&text)
+        .map(|cap| cap[1].parse::<u32>().unwrap());
+    let minutes = minutes_re
+        .captures(&text)
+        .map(|cap| cap[1].parse::<u32>().unwrap());
+    let running_time = match (hours, minutes) {
+        (None, None) => None,
+        (h, m) => Some(h.unwrap_or(0) * 60 + m.unwrap_or(0)),
+    };
+
+    let intervals_re =
+        Regex::new(r"\b(no|without|an?|one|two|three|four|\d+)\s+(?:[\w-]+\s+)?intervals?\b")
+            .unwrap();
+    let n_intervals = intervals_re
+        .captures(&text)
+        .and_then(|cap| parse_count(&cap[1]));
+
+    (running_time, n_intervals)
+}
+
+/// Parse a description of the language, e.g. "Sung in Italian with English surtitles" or "Sung in
+/// English, surtitles in English", into the language the work is sung in and the language of the
+/// surtitles
+pub fn parse_language(text: &str) -> (Option<String>, Option<String>) {
+    let language_re = Regex::new(r"(?i)\bsung in ([a-z]+)").unwrap();
+    let surtitles_re = Regex::new(r"(?i)\b([a-z]+) surtitles\b|\bsurtitles in ([a-z]+)").unwrap();
+    let language = language_re.captures(text).map(|cap| cap[1].to_string());
+    let surtitles = surtitles_re
+        .captures(text)
+        .and_then(|cap| cap.get(1).or(cap.get(2)))
+        .map(|m| m.as_str().to_string())
+        // e.g. "with surtitles" or "no surtitles"
+        .filter(|s| s.starts_with(|c: char| c.is_uppercase()));
+    (language, surtitles)
+}
+
+/// The performers of a production: the conductor, then the cast (with their roles in place of an
+/// instrument), so that they can be searched for like the performers of any other concert
+pub fn performers(production: &Production) -> Vec<Performer> {
+    production
+        .conductor
+        .iter()
+        .map(|name| Performer {
+            name: name.clone(),
+            instrument: Some("conductor".to_string()),
+        })
+        .chain(production.cast.iter().map(|member| Performer {
+            name: member.name.clone(),
+            instrument: Some(member.role.clone()),
+        }))
+        .collect()
+}
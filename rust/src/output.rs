@@ -0,0 +1,141 @@
+use crate::core::Concert;
+use crate::source::Source;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Version of the format of concerts.json. This goes up whenever a change could break something
+/// which reads it (e.g. a field being removed or renamed, or its meaning changing), but not when a
+/// field is added. Version 1 was a bare array of concerts, which is still written if
+/// $LDNCLS_BARE_ARRAY is set.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Where the concerts from one source came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct SourceMetadata {
+    pub source: Source,
+    /// e.g. "Wigmore Hall"
+    pub name: String,
+    /// When the source was last scraped, which (when running as a daemon) can be well before
+    /// `generated_at`
+    pub scraped_at: DateTime<Utc>,
+    pub n_concerts: usize,
+}
+
+/// The contents of concerts.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct Envelope {
+    /// Version of the format, which goes up whenever a change could break something reading it
+    pub schema_version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub sources: Vec<SourceMetadata>,
+    pub concerts: Vec<Concert>,
+}
+
+impl Envelope {
+    /// Wrap up concerts from the sources in `scraped_at`, which says when each one was scraped
+    pub fn new(
+        concerts: Vec<Concert>,
+        scraped_at: &BTreeMap<Source, DateTime<Utc>>,
+        generated_at: DateTime<Utc>,
+    ) -> Envelope {
+        let sources = scraped_at
+            .iter()
+            .map(|(&source, &scraped_at)| SourceMetadata {
+                source,
+                name: source.name().to_string(),
+                scraped_at,
+                n_concerts: concerts
+                    .iter()
+                    .filter(|c| Source::from_url(&c.concert.url) == Some(source))
+                    .count(),
+            })
+            .collect();
+        Envelope {
+            schema_version: SCHEMA_VERSION,
+            generated_at,
+            sources,
+            concerts,
+        }
+    }
+
+    /// Write to a file. If `bare` is set, only the array of concerts is written, as it was before
+    /// there was an envelope.
+    pub fn write(&self, path: &Path, bare: bool) {
+        let file = File::create(path)
+            .unwrap_or_else(|e| panic!("Failed to create {}: {}", path.display(), e));
+        if bare {
+            serde_json::to_writer_pretty(file, &self.concerts).unwrap();
+        } else {
+            serde_json::to_writer_pretty(file, self).unwrap();
+        }
+    }
+}
+
+/// A concerts.json file, which might have been written before there was an envelope (or with
+/// $LDNCLS_BARE_ARRAY set)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ConcertsFile {
+    Envelope(Envelope),
+    Bare(Vec<Concert>),
+}
+
+impl ConcertsFile {
+    pub fn concerts(&self) -> &[Concert] {
+        match self {
+            ConcertsFile::Envelope(envelope) => &envelope.concerts,
+            ConcertsFile::Bare(concerts) => concerts,
+        }
+    }
+
+    pub fn into_concerts(self) -> Vec<Concert> {
+        match self {
+            ConcertsFile::Envelope(envelope) => envelope.concerts,
+            ConcertsFile::Bare(concerts) => concerts,
+        }
+    }
+
+    /// Metadata about each source (there isn't any in a bare array)
+    pub fn sources(&self) -> &[SourceMetadata] {
+        match self {
+            ConcertsFile::Envelope(envelope) => &envelope.sources,
+            ConcertsFile::Bare(_) => &[],
+        }
+    }
+}
+
+/// Read a concerts.json file, in either format. Returns `None` if the file does not exist, and
+/// panics if it was written by a newer version of this program than this one.
+pub fn read(path: &Path) -> Option<ConcertsFile> {
+    let file = File::open(path).ok()?;
+    let contents: ConcertsFile = serde_json::from_reader(file)
+        .unwrap_or_else(|e| panic!("Failed to parse concerts from {}: {}", path.display(), e));
+    if let ConcertsFile::Envelope(envelope) = &contents {
+        if envelope.schema_version > SCHEMA_VERSION {
+            panic!(
+                "{} has schema version {}, but only versions up to {} can be read",
+                path.display(),
+                envelope.schema_version,
+                SCHEMA_VERSION
+            );
+        }
+    }
+    Some(contents)
+}
+
+/// The JSON Schema for concerts.json, which is kept in `concerts.schema.json` for anything else
+/// that reads it
+pub fn json_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Envelope)).unwrap() + "\n"
+}
+
+pub fn json_schema_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("concerts.schema.json")
+}
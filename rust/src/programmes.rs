@@ -0,0 +1,299 @@
+use crate::core::{Concert, ConcertData};
+use crate::http;
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where a programme PDF and its extracted text are stored. Files are named after a hash of the
+/// URL, so that each PDF is only downloaded once.
+fn store_paths(store_dir: &Path, url: &str) -> (PathBuf, PathBuf) {
+    let hash: String = Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    (
+        store_dir.join(format!("{}.pdf", hash)),
+        store_dir.join(format!("{}.txt", hash)),
+    )
+}
+
+/// Extract the text from a PDF, with all whitespace collapsed into single spaces. Returns `None`
+/// if the PDF couldn't be read.
+async fn extract_text(pdf: Vec<u8>) -> Option<String> {
+    // pdf-extract is slow, and panics on some PDFs rather than returning an error
+    let result = tokio::task::spawn_blocking(move || {
+        std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&pdf))
+    })
+    .await
+    .unwrap();
+    match result {
+        Ok(Ok(text)) => Some(text.split_whitespace().collect::<Vec<&str>>().join(" ")),
+        Ok(Err(e)) => {
+            warn!("Failed to extract text from PDF: {}", e);
+            None
+        }
+        Err(_) => {
+            warn!("Failed to extract text from PDF");
+            None
+        }
+    }
+}
+
+/// Get the text of a single programme, from the store if possible. PDFs whose text couldn't be
+/// extracted are remembered (as an empty text file), so they aren't downloaded again either.
+async fn get_programme_text(
+    client: &http::Client,
+    url: &str,
+    store_dir: Option<&Path>,
+) -> Option<String> {
+    let paths = store_dir.map(|dir| store_paths(dir, url));
+    if let Some((_, txt_path)) = &paths {
+        if let Ok(text) = std::fs::read_to_string(txt_path) {
+            client.record_cache_hit();
+            return Some(text).filter(|text| !text.is_empty());
+        }
+    }
+
+    let pdf = match client.get_bytes(url).await {
+        Ok(pdf) => pdf,
+        Err(e) => {
+            // Don't remember this, as it could be a temporary problem
+            warn!("Failed to download programme {}: {}", url, e);
+            return None;
+        }
+    };
+    if let Some((pdf_path, _)) = &paths {
+        std::fs::write(pdf_path, &pdf).unwrap();
+    }
+    let text = extract_text(pdf).await;
+    if text.is_none() {
+        warn!("Could not read programme {}", url);
+        client.record_error();
+    }
+    if let Some((_, txt_path)) = &paths {
+        std::fs::write(txt_path, text.as_deref().unwrap_or("")).unwrap();
+    }
+    text
+}
+
+/// Download the programme PDFs of concerts which have one, and fill in `programme_text` with the
+/// text extracted from them. If `store_dir` is given, the PDFs and their text are kept there.
+pub async fn attach_programme_texts(
+    concerts: &mut [ConcertData],
+    client: &http::Client,
+    store_dir: Option<&Path>,
+) {
+    if let Some(dir) = store_dir {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    let mut n_found = 0;
+    for concert in concerts.iter_mut() {
+        let Some(url) = &concert.programme_pdf_url else {
+            continue;
+        };
+        concert.programme_text = get_programme_text(client, url, store_dir).await;
+        if concert.programme_text.is_some() {
+            n_found += 1;
+        }
+    }
+    info!("Found text for {} programmes", n_found);
+}
+
+/// Write the programme texts of the concerts which have one to `path`, as a JSON object keyed by
+/// concert ID. They aren't in concerts.json, which the website has to load in full.
+///
+/// Concerts which were carried over from a previous run (e.g. because their source failed) don't
+/// have their text any more, so any text they already have in `path` is kept.
+pub fn write_texts(path: &Path, concerts: &[Concert]) {
+    let mut existing = read_file(path).unwrap_or_default();
+    let texts: BTreeMap<&str, String> = concerts
+        .iter()
+        .filter_map(|c| {
+            let text = match &c.concert.programme_text {
+                Some(text) => text.clone(),
+                None => existing.remove(&c.id)?,
+            };
+            Some((c.id.as_str(), text))
+        })
+        .collect();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    std::fs::write(path, serde_json::to_string(&texts).unwrap())
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+}
+
+/// Fill in the programme texts of concerts from a file written by `write_texts`, if it exists
+pub fn read_texts(path: &Path, concerts: &mut [Concert]) {
+    let Some(mut texts) = read_file(path) else {
+        return;
+    };
+    for concert in concerts {
+        if let Some(text) = texts.remove(&concert.id) {
+            concert.concert.programme_text = Some(text);
+        }
+    }
+}
+
+/// Returns `None` if the file does not exist
+fn read_file(path: &Path) -> Option<BTreeMap<String, String>> {
+    let text = std::fs::read_to_string(path).ok()?;
+    Some(
+        serde_json::from_str(&text)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::add_id_to_concert;
+    use std::sync::atomic::Ordering;
+
+    /// A minimal PDF with one page of text
+    fn pdf(text: &str) -> Vec<u8> {
+        let stream = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut pdf = "%PDF-1.4\n".to_string();
+        let mut offsets = vec![];
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref_offset = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        pdf.into_bytes()
+    }
+
+    #[tokio::test]
+    async fn text_is_extracted() {
+        assert_eq!(
+            extract_text(pdf("Schubert:   Winterreise"))
+                .await
+                .as_deref(),
+            Some("Schubert: Winterreise")
+        );
+        assert_eq!(extract_text(b"not a pdf".to_vec()).await, None);
+    }
+
+    #[tokio::test]
+    async fn stored_texts_are_not_downloaded_again() {
+        let dir = std::env::temp_dir().join(format!("ldncls-programmes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let client = http::Client::new(reqwest::Client::new());
+        // Neither of these could be downloaded
+        let (_, txt_path) = store_paths(&dir, "unreadable.pdf");
+        std::fs::write(txt_path, "").unwrap();
+        let (_, txt_path) = store_paths(&dir, "programme.pdf");
+        std::fs::write(txt_path, "Notes").unwrap();
+
+        assert_eq!(
+            get_programme_text(&client, "programme.pdf", Some(&dir))
+                .await
+                .as_deref(),
+            Some("Notes")
+        );
+        assert_eq!(
+            get_programme_text(&client, "unreadable.pdf", Some(&dir)).await,
+            None
+        );
+        assert_eq!(client.stats().requests.load(Ordering::Relaxed), 0);
+        assert_eq!(client.stats().cache_hits.load(Ordering::Relaxed), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn texts_are_kept_out_of_concerts_json() {
+        let mut concerts = vec![
+            add_id_to_concert(ConcertData {
+                title: "With programme".to_string(),
+                programme_text: Some("Notes".to_string()),
+                ..Default::default()
+            }),
+            add_id_to_concert(ConcertData {
+                title: "Without programme".to_string(),
+                ..Default::default()
+            }),
+        ];
+        let json = serde_json::to_value(&concerts[0]).unwrap();
+        assert!(json.get("programme_text").is_none());
+
+        let path = std::env::temp_dir().join(format!(
+            "ldncls-programme-texts-{}.json",
+            std::process::id()
+        ));
+        write_texts(&path, &concerts);
+        let written = concerts.clone();
+        for concert in &mut concerts {
+            concert.concert.programme_text = None;
+        }
+        read_texts(&path, &mut concerts);
+        assert_eq!(concerts, written);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn texts_of_carried_over_concerts_are_kept() {
+        let mut concerts = vec![
+            add_id_to_concert(ConcertData {
+                title: "Scraped".to_string(),
+                programme_text: Some("Old notes".to_string()),
+                ..Default::default()
+            }),
+            add_id_to_concert(ConcertData {
+                title: "Carried over".to_string(),
+                programme_text: Some("Notes".to_string()),
+                ..Default::default()
+            }),
+            add_id_to_concert(ConcertData {
+                title: "Gone".to_string(),
+                programme_text: Some("Notes".to_string()),
+                ..Default::default()
+            }),
+        ];
+        let path = std::env::temp_dir().join(format!(
+            "ldncls-carried-over-texts-{}.json",
+            std::process::id()
+        ));
+        write_texts(&path, &concerts);
+
+        concerts.pop();
+        concerts[0].concert.programme_text = Some("New notes".to_string());
+        concerts[1].concert.programme_text = None;
+        write_texts(&path, &concerts);
+
+        let texts = read_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let expected = BTreeMap::from([
+            (concerts[0].id.clone(), "New notes".to_string()),
+            (concerts[1].id.clone(), "Notes".to_string()),
+        ]);
+        assert_eq!(texts, expected);
+    }
+}
@@ -1,41 +1,80 @@
+use crate::classify;
 use crate::core;
-use chrono::{NaiveDate, Utc};
-use core::naivedt_to_utc;
-use log::info;
+use crate::datetime;
+use crate::http;
+use crate::media;
+use chrono::{NaiveDate, NaiveTime, Utc};
+use log::{info, warn};
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
 use std::cmp::min;
 
-const PROMS_2025_URL: &str = "https://www.bbc.co.uk/events/rb5v4f/by/date/2025";
+pub const PROMS_2025_URL: &str = "https://www.bbc.co.uk/events/rb5v4f/by/date/2025";
+
+/// Selectors for the season calendar, which the healthcheck probes as well
+pub mod selectors {
+    pub const DATE_EVENTS: &str = "li.ev-event-calendar__single-date-events";
+    pub const DATE: &str = "h3.ev-event-calendar__date";
+    pub const EVENT: &str = "li.ev-event-calendar__event-summary-container";
+    pub const TITLE: &str = "div.ev-event-calendar__name";
+    pub const LINK: &str = "div.ev-event-calendar__name>a";
+    pub const TIME: &str = "div.ev-event-calendar__time";
+    pub const VENUE: &str = "span.ev-event-calendar__event-location";
+    pub const DESCRIPTION: &str = "p.ev-event-calendar__event-description";
+    pub const PIECE: &str = "li.ev-act-schedule__performance-composer-segments";
+    pub const PERFORMER: &str =
+        "div[data-id-for-tests=\"event-schedule-artists\"] li.ev-act-schedule__artist";
+    pub const PERFORMER_NAME: &str = "div.ev-act-schedule__artist-details-container";
+    pub const PERFORMER_ROLE: &str = "div.ev-act-schedule__artist-role-container";
+    pub const PRICE: &str = "div.ev-event-calendar__ticket-link-subtitle--desktop";
+}
 
 // Scrapes concerts from BBC Proms website
-pub async fn scrape(client: &reqwest::Client) -> Vec<core::ConcertData> {
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
     info!("Scraping BBC Proms from URL: {}", PROMS_2025_URL);
 
-    let html: String = client
-        .get(PROMS_2025_URL)
-        .send()
-        .await
-        .expect("Failed to fetch Proms page")
-        .text()
-        .await
-        .expect("Failed to parse Proms page");
-    let doc: Html = Html::parse_document(&html);
+    let Some(html) = client.try_get_text(PROMS_2025_URL).await else {
+        return vec![];
+    };
+    let concerts = parse_calendar(&html, Utc::now().date_naive());
+    info!("Scraped {} Proms", concerts.len());
+    concerts
+}
+
+/// Parse the calendar of the whole season. Concerts before `today` are left out (they are still
+/// listed on the BBC website).
+pub fn parse_calendar(html: &str, today: NaiveDate) -> Vec<core::ConcertData> {
+    let doc: Html = Html::parse_document(html);
 
     let mut concerts: Vec<core::ConcertData> = vec![];
 
-    let date_selector: Selector = Selector::parse("li.ev-event-calendar__single-date-events")
-        .expect("Invalid overall date selector");
+    let date_selector: Selector =
+        Selector::parse(selectors::DATE_EVENTS).expect("Invalid overall date selector");
 
+    let mut all_dates = vec![];
+    let mut all_metadatas = vec![];
     for this_date_performances in doc.select(&date_selector) {
-        let (date, metadatas) = scrape_one_date(this_date_performances).await;
+        let Some((date, metadatas)) = scrape_one_date(this_date_performances, today) else {
+            continue;
+        };
+        all_dates.push(date);
+        all_metadatas.push((date, metadatas));
+    }
+
+    // Past dates are still listed, so the dates of the whole season are known
+    let series = core::Series {
+        name: "BBC Proms 2025".to_string(),
+        organiser: "BBC".to_string(),
+        start_date: all_dates.iter().min().copied(),
+        end_date: all_dates.iter().max().copied(),
+        url: Some(PROMS_2025_URL.to_string()),
+    };
+    for (date, metadatas) in all_metadatas {
         metadatas
             .into_iter()
-            .map(|metadata| make_full_concert(date, metadata))
+            .map(|metadata| make_full_concert(date, metadata, &series))
             .for_each(|concert| concerts.push(concert));
     }
-
-    info!("Scraped {} Proms", concerts.len());
     concerts
 }
 
@@ -45,8 +84,8 @@ pub async fn scrape(client: &reqwest::Client) -> Vec<core::ConcertData> {
 /// joined together with this to form the full core::Concert.
 #[derive(Debug)]
 struct PromsConcertMetadata {
-    /// (hour, minute) in London timezone
-    london_time: (u32, u32),
+    /// In London timezone
+    london_time: NaiveTime,
     title: String,
     description: Option<String>,
     venue: String,
@@ -55,11 +94,17 @@ struct PromsConcertMetadata {
     performers: Vec<core::Performer>,
     min_price: Option<u32>,
     max_price: Option<u32>,
+    /// All the text in the listing entry, which is where broadcasts are mentioned
+    listing_text: String,
 }
 
-/// Scrapes a single date's worth of concerts from the BBC Proms website
-async fn scrape_one_date(date_fragment: ElementRef<'_>) -> (NaiveDate, Vec<PromsConcertMetadata>) {
-    let date_selector = Selector::parse("h3.ev-event-calendar__date").unwrap();
+/// Scrapes a single date's worth of concerts from the BBC Proms website. If the date can't be read,
+/// the whole day is skipped.
+fn scrape_one_date(
+    date_fragment: ElementRef<'_>,
+    today: NaiveDate,
+) -> Option<(NaiveDate, Vec<PromsConcertMetadata>)> {
+    let date_selector = Selector::parse(selectors::DATE).unwrap();
     let date_str = date_fragment
         .select(&date_selector)
         .next()
@@ -69,28 +114,30 @@ async fn scrape_one_date(date_fragment: ElementRef<'_>) -> (NaiveDate, Vec<Proms
         .unwrap()
         .trim();
     // BBC's website reports dates as e.g. "Fri 23 Aug 2024"
-    let date = NaiveDate::parse_from_str(date_str, "%a %e %b %Y").unwrap();
+    let date = NaiveDate::parse_from_str(date_str, "%a %e %b %Y")
+        .inspect_err(|e| warn!("Skipping Proms on unexpected date {}: {}", date_str, e))
+        .ok()?;
 
     // The BBC website retains concerts from the past too, so we filter them out right here
-    if date < Utc::now().date_naive() {
-        return (date, vec![]);
+    if date < today {
+        return Some((date, vec![]));
     }
 
     // For any dates that aren't in the past, get the concerts themselves
     let mut intermediate_concerts: Vec<PromsConcertMetadata> = vec![];
-    let concert_details_selector =
-        Selector::parse("li.ev-event-calendar__event-summary-container").unwrap();
+    let concert_details_selector = Selector::parse(selectors::EVENT).unwrap();
     for concert_elem in date_fragment.select(&concert_details_selector) {
-        intermediate_concerts.push(parse_single_concert(concert_elem));
+        intermediate_concerts.extend(parse_single_concert(concert_elem));
     }
 
-    (date, intermediate_concerts)
+    Some((date, intermediate_concerts))
 }
 
-/// Parses a single concert entry within a date fragment
-fn parse_single_concert(elem: ElementRef<'_>) -> PromsConcertMetadata {
+/// Parses a single concert entry within a date fragment. Concerts whose time can't be read are
+/// skipped.
+fn parse_single_concert(elem: ElementRef<'_>) -> Option<PromsConcertMetadata> {
     let title = elem
-        .select(&Selector::parse("div.ev-event-calendar__name").unwrap())
+        .select(&Selector::parse(selectors::TITLE).unwrap())
         .next()
         .unwrap()
         .text()
@@ -100,43 +147,34 @@ fn parse_single_concert(elem: ElementRef<'_>) -> PromsConcertMetadata {
         .to_string();
 
     let time_string: &str = elem
-        .select(&Selector::parse("div.ev-event-calendar__time").unwrap())
+        .select(&Selector::parse(selectors::TIME).unwrap())
         .next()
         .unwrap()
         .text()
         .next()
         .unwrap()
         .trim();
-    let parsed_time: (u32, u32) = match time_string {
+    let parsed_time: NaiveTime = match time_string {
         // hack for a specific concert -- it's overnight so doesn't fit the usual pattern
-        "8 –  9 Aug 2025" => (23, 00),
-        _ => {
-            let time_string2: Vec<&str> = time_string.split(':').collect();
-            (
-                time_string2[0].parse().unwrap(),
-                time_string2[1].parse().unwrap(),
-            )
-        }
+        "8 –  9 Aug 2025" => NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+        _ => datetime::parse_time(time_string)
+            .inspect_err(|e| warn!("Skipping Prom {}: {}", title, e))
+            .ok()?,
     };
 
-    let pieces_selector =
-        Selector::parse("li.ev-act-schedule__performance-composer-segments").unwrap();
+    let pieces_selector = Selector::parse(selectors::PIECE).unwrap();
     let pieces: Vec<core::Piece> = elem
         .select(&pieces_selector)
         .filter_map(parse_piece)
         .collect();
 
-    let performer_selector = Selector::parse(
-        "div[data-id-for-tests=\"event-schedule-artists\"] li.ev-act-schedule__artist",
-    )
-    .unwrap();
+    let performer_selector = Selector::parse(selectors::PERFORMER).unwrap();
     let performers: Vec<core::Performer> = elem
         .select(&performer_selector)
         .map(|performer_elem| parse_performer(performer_elem))
         .collect();
 
-    let price_selector =
-        Selector::parse("div.ev-event-calendar__ticket-link-subtitle--desktop").unwrap();
+    let price_selector = Selector::parse(selectors::PRICE).unwrap();
     let price_text = elem
         .select(&price_selector)
         .next()
@@ -158,11 +196,11 @@ fn parse_single_concert(elem: ElementRef<'_>) -> PromsConcertMetadata {
         _ => panic!("couldn't parse prices from {:?}", price_text),
     };
 
-    PromsConcertMetadata {
+    Some(PromsConcertMetadata {
         london_time: parsed_time,
         title,
         description: elem
-            .select(&Selector::parse("p.ev-event-calendar__event-description").unwrap())
+            .select(&Selector::parse(selectors::DESCRIPTION).unwrap())
             .next()
             .unwrap()
             .text()
@@ -170,14 +208,14 @@ fn parse_single_concert(elem: ElementRef<'_>) -> PromsConcertMetadata {
             .map(|s| s.trim().to_string()),
         url: "https://bbc.co.uk".to_string()
             + elem
-                .select(&Selector::parse("div.ev-event-calendar__name>a").unwrap())
+                .select(&Selector::parse(selectors::LINK).unwrap())
                 .next()
                 .unwrap()
                 .value()
                 .attr("href")
                 .unwrap(),
         venue: elem
-            .select(&Selector::parse("span.ev-event-calendar__event-location").unwrap())
+            .select(&Selector::parse(selectors::VENUE).unwrap())
             .next()
             .unwrap()
             .text()
@@ -188,21 +226,31 @@ fn parse_single_concert(elem: ElementRef<'_>) -> PromsConcertMetadata {
         performers,
         min_price,
         max_price,
-    }
+        listing_text: elem.text().collect::<Vec<&str>>().join(" "),
+    })
 }
 
 /// Combines the date and the concert metadata to form a full core::Concert
-fn make_full_concert(date: NaiveDate, metadata: PromsConcertMetadata) -> core::ConcertData {
-    let utc_time = naivedt_to_utc(date, metadata.london_time.0, metadata.london_time.1);
+fn make_full_concert(
+    date: NaiveDate,
+    metadata: PromsConcertMetadata,
+    series: &core::Series,
+) -> core::ConcertData {
+    let utc_time = datetime::london_to_utc(date.and_time(metadata.london_time));
 
     let is_rah_prom = metadata.venue == "Royal Albert Hall"
         && (metadata.title.starts_with("Prom") || metadata.title.starts_with("First Night"));
     let promming_price = 800;
 
-    let concert = core::ConcertData {
+    // The BBC listing says e.g. "Live on BBC Radio 3" or "Broadcast on BBC Four on 3 August". Any
+    // livestream is on the BBC's own page for the concert.
+    let media = media::find_media(&metadata.listing_text, utc_time, &metadata.url);
+
+    let mut concert = core::ConcertData {
         datetime: utc_time,
         url: metadata.url,
         venue: metadata.venue,
+        category: core::Category::Other,
         title: metadata.title,
         description: metadata.description,
         pieces: metadata.pieces,
@@ -211,6 +259,7 @@ fn make_full_concert(date: NaiveDate, metadata: PromsConcertMetadata) -> core::C
         // Proms concerts don't have subtitles or programme PDFs available
         subtitle: None,
         programme_pdf_url: None,
+        programme_text: None,
 
         // Day promming prices aren't shown on the website so we add them in here
         min_price: if is_rah_prom {
@@ -222,18 +271,26 @@ fn make_full_concert(date: NaiveDate, metadata: PromsConcertMetadata) -> core::C
             metadata.min_price
         },
         max_price: metadata.max_price,
+        availability: core::Availability::Ticketed,
+        media,
 
         // By definition
         is_wigmore_u35: false,
-        is_prom: true,
+        series: vec![series.clone()],
+        production: None,
     };
 
+    // The Proms strand (e.g. "Relaxed Prom" or "Proms Plus") is part of the title, so there
+    // aren't any extra tags
+    concert.category = classify::classify(&concert, &[]);
+
     core::report_concert(&concert);
     concert
 }
 
-/// Helper function to parse a piece from a concert
-fn parse_piece(piece_elem: ElementRef<'_>) -> Option<core::Piece> {
+/// Helper function to parse a piece from a concert. The first piece of text in the element is the
+/// composer, and the rest is the title; intervals aren't pieces.
+pub fn parse_piece(piece_elem: ElementRef<'_>) -> Option<core::Piece> {
     // This is kind of hacky but it works
     let all_texts = piece_elem.text().collect::<Vec<&str>>();
     match all_texts[..] {
@@ -249,14 +306,14 @@ fn parse_piece(piece_elem: ElementRef<'_>) -> Option<core::Piece> {
 /// Helper function to parse a performer from a concert
 fn parse_performer(performer_elem: ElementRef<'_>) -> core::Performer {
     let name = performer_elem
-        .select(&Selector::parse("div.ev-act-schedule__artist-details-container").unwrap())
+        .select(&Selector::parse(selectors::PERFORMER_NAME).unwrap())
         .next()
         .unwrap()
         .text()
         .next()
         .unwrap();
     let role_texts = performer_elem
-        .select(&Selector::parse("div.ev-act-schedule__artist-role-container").unwrap())
+        .select(&Selector::parse(selectors::PERFORMER_ROLE).unwrap())
         .next()
         .unwrap()
         .text()
@@ -0,0 +1,280 @@
+use crate::core::{Availability, Category, Concert};
+use crate::search::SearchIndex;
+use chrono::NaiveDate;
+use chrono_tz::Europe::London;
+use deunicode::deunicode;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_PER_PAGE: usize = 50;
+const MAX_PER_PAGE: usize = 500;
+
+/// Discount schemes that concerts can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Discount {
+    WigmoreU35,
+}
+
+/// Ways of following a concert remotely that concerts can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaFilter {
+    Livestream,
+    OnDemand,
+    Broadcast,
+}
+
+/// Filters which can be applied to a list of concerts. All filters are optional, and a concert has
+/// to satisfy all of the ones which are given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConcertQuery {
+    /// Earliest date (inclusive, in London time)
+    pub from: Option<NaiveDate>,
+    /// Latest date (inclusive, in London time)
+    pub to: Option<NaiveDate>,
+    /// Substring of the venue name
+    pub venue: Option<String>,
+    /// Substring of the name of a composer of any of the pieces
+    pub composer: Option<String>,
+    /// Substring of the name of any of the performers
+    pub performer: Option<String>,
+    /// Maximum price in pennies. Concerts without a known price are excluded.
+    pub max_price: Option<u32>,
+    /// e.g. `free_no_booking` for concerts which anyone can just turn up to
+    pub availability: Option<Availability>,
+    pub discount: Option<Discount>,
+    pub media: Option<MediaFilter>,
+    pub category: Option<Category>,
+    /// Substring of the name of a series (e.g. a festival) the concert is part of
+    pub series: Option<String>,
+    /// Free-text search query (see `SearchIndex::search` for the syntax). If given, results are
+    /// sorted by relevance instead of chronologically.
+    pub q: Option<String>,
+    /// Page number, starting from 1
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// A single page of results
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<'a> {
+    /// Total number of concerts matching the query (across all pages)
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub concerts: Vec<&'a Concert>,
+}
+
+/// Case- and accent-insensitive substring check
+fn contains(haystack: &str, needle: &str) -> bool {
+    deunicode(haystack)
+        .to_lowercase()
+        .contains(&deunicode(needle).to_lowercase())
+}
+
+impl ConcertQuery {
+    /// Check whether a concert satisfies all the filters, apart from the free-text search
+    pub fn matches(&self, concert: &Concert) -> bool {
+        let c = &concert.concert;
+        let date = c.datetime.with_timezone(&London).date_naive();
+        if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+            return false;
+        }
+        if let Some(venue) = &self.venue {
+            if !contains(&c.venue, venue) {
+                return false;
+            }
+        }
+        if let Some(composer) = &self.composer {
+            if !c.pieces.iter().any(|p| contains(&p.composer, composer)) {
+                return false;
+            }
+        }
+        if let Some(performer) = &self.performer {
+            if !c.performers.iter().any(|p| contains(&p.name, performer)) {
+                return false;
+            }
+        }
+        if let Some(max_price) = self.max_price {
+            if c.min_price.is_none_or(|min_price| min_price > max_price) {
+                return false;
+            }
+        }
+        if self
+            .availability
+            .is_some_and(|availability| c.availability != availability)
+        {
+            return false;
+        }
+        if let Some(discount) = self.discount {
+            let has_discount = match discount {
+                Discount::WigmoreU35 => c.is_wigmore_u35,
+            };
+            if !has_discount {
+                return false;
+            }
+        }
+        if self.category.is_some_and(|category| c.category != category) {
+            return false;
+        }
+        if let Some(series) = &self.series {
+            if !c.series.iter().any(|s| contains(&s.name, series)) {
+                return false;
+            }
+        }
+        if let Some(media) = self.media {
+            let has_media = match media {
+                MediaFilter::Livestream => c.media.livestream_url.is_some(),
+                MediaFilter::OnDemand => c.media.on_demand.is_some(),
+                MediaFilter::Broadcast => !c.media.broadcasts.is_empty(),
+            };
+            if !has_media {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Apply the filters to a list of concerts and return the requested page of results. `index`
+    /// must have been built from the same list of concerts.
+    pub fn apply<'a>(&self, concerts: &'a [Concert], index: &SearchIndex) -> Page<'a> {
+        let matching: Vec<&Concert> = match self.q.as_deref().map(str::trim) {
+            Some(q) if !q.is_empty() => index
+                .search(q)
+                .into_iter()
+                .map(|hit| &concerts[hit.index])
+                .filter(|c| self.matches(c))
+                .collect(),
+            _ => concerts.iter().filter(|c| self.matches(c)).collect(),
+        };
+        self.paginate(matching)
+    }
+
+    /// Return the requested page out of a list of concerts
+    pub fn paginate<'a>(&self, concerts: Vec<&'a Concert>) -> Page<'a> {
+        let page = self.page.unwrap_or(1).max(1);
+        let per_page = self
+            .per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE);
+        Page {
+            total: concerts.len(),
+            page,
+            per_page,
+            concerts: concerts
+                .into_iter()
+                // Pages far beyond the end are empty rather than overflowing
+                .skip((page - 1).saturating_mul(per_page))
+                .take(per_page)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{add_id_to_concert, ConcertData, Performer, Series};
+    use chrono::{TimeZone, Utc};
+
+    fn concert(day: u32, hour: u32, venue: &str, min_price: Option<u32>) -> Concert {
+        add_id_to_concert(ConcertData {
+            datetime: Utc.with_ymd_and_hms(2026, 6, day, hour, 0, 0).unwrap(),
+            url: format!("https://example.com/{}", day),
+            title: "Recital".to_string(),
+            venue: venue.to_string(),
+            min_price,
+            performers: vec![Performer {
+                name: "Antonín Dvořák Quartet".to_string(),
+                instrument: None,
+            }],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn dates_are_in_london_time() {
+        // 23:00 UTC on 1 June is already 2 June in London
+        let c = concert(1, 23, "Wigmore Hall", None);
+        let query = |from, to| ConcertQuery {
+            from: NaiveDate::from_ymd_opt(2026, 6, from),
+            to: NaiveDate::from_ymd_opt(2026, 6, to),
+            ..Default::default()
+        };
+        assert!(query(2, 2).matches(&c));
+        assert!(!query(1, 1).matches(&c));
+    }
+
+    #[test]
+    fn text_filters_ignore_case_and_accents() {
+        let c = concert(1, 19, "Wigmore Hall", None);
+        let query = ConcertQuery {
+            venue: Some("wigmore".to_string()),
+            performer: Some("DVORAK".to_string()),
+            ..Default::default()
+        };
+        assert!(query.matches(&c));
+    }
+
+    #[test]
+    fn unknown_prices_are_excluded_by_max_price() {
+        let query = ConcertQuery {
+            max_price: Some(1000),
+            ..Default::default()
+        };
+        assert!(query.matches(&concert(1, 19, "Wigmore Hall", Some(500))));
+        assert!(!query.matches(&concert(1, 19, "Wigmore Hall", Some(1500))));
+        assert!(!query.matches(&concert(1, 19, "Wigmore Hall", None)));
+    }
+
+    #[test]
+    fn pagination() {
+        let concerts: Vec<Concert> = (1..=5)
+            .map(|day| concert(day, 19, "Wigmore Hall", None))
+            .collect();
+        let query = ConcertQuery {
+            page: Some(2),
+            per_page: Some(2),
+            ..Default::default()
+        };
+        let page = query.apply(&concerts, &SearchIndex::new(&concerts));
+        assert_eq!((page.total, page.page, page.per_page), (5, 2, 2));
+        assert_eq!(page.concerts, vec![&concerts[2], &concerts[3]]);
+
+        let query = ConcertQuery {
+            page: Some(0),
+            per_page: Some(10_000),
+            ..Default::default()
+        };
+        let page = query.paginate(concerts.iter().collect());
+        assert_eq!((page.page, page.per_page), (1, MAX_PER_PAGE));
+
+        let query = ConcertQuery {
+            page: Some(usize::MAX),
+            per_page: Some(MAX_PER_PAGE),
+            ..Default::default()
+        };
+        let page = query.paginate(concerts.iter().collect());
+        assert_eq!((page.total, page.page), (5, usize::MAX));
+        assert!(page.concerts.is_empty());
+    }
+
+    #[test]
+    fn series() {
+        let mut c = concert(1, 19, "Royal Albert Hall", None);
+        c.concert.series = vec![Series {
+            name: "BBC Proms 2026".to_string(),
+            organiser: "BBC".to_string(),
+            start_date: None,
+            end_date: None,
+            url: None,
+        }];
+        let query = |series: &str| ConcertQuery {
+            series: Some(series.to_string()),
+            ..Default::default()
+        };
+        assert!(query("proms").matches(&c));
+        assert!(!query("proms").matches(&concert(1, 19, "Wigmore Hall", None)));
+        assert!(!query("wigmore").matches(&c));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::classify;
+use crate::core;
+use crate::datetime;
+use crate::http;
+use crate::media;
+use chrono::Utc;
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{debug, info, warn};
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+
+pub const RAM_URL: &str = "https://www.ram.ac.uk/whats-on?type=concerts";
+
+/// Selectors for the listing and event pages, which the healthcheck probes as well
+pub mod selectors {
+    pub const EVENT_LINK: &str = "li.event-list__item a.event-list__link";
+    pub const NEXT_PAGE: &str = "a[rel=next]";
+    pub const TITLE: &str = "h1.event-title";
+    pub const SUBTITLE: &str = "p.event-subtitle";
+    pub const DATETIME: &str = "div.event-meta__date time";
+    pub const VENUE: &str = "div.event-meta__venue";
+    pub const PRICE: &str = "div.event-meta__price";
+    pub const DESCRIPTION: &str = "div.event-description p";
+    pub const PERFORMER: &str = "ul.event-performers > li";
+    pub const PIECE: &str = "ul.event-programme > li";
+    pub const TAG: &str = "ul.event-tags a";
+}
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    let mut concerts: Vec<core::ConcertData> = vec![];
+
+    let mut next_url = Some(RAM_URL.to_string());
+    let mut visited = HashSet::new();
+    while let Some(url) = next_url {
+        if !visited.insert(url.clone()) {
+            warn!(
+                "Royal Academy of Music listing links back to page {}, stopping",
+                url
+            );
+            break;
+        }
+        debug!("Scraping Royal Academy of Music listing from URL {}", url);
+        let Some(html) = client.try_get_text(&url).await else {
+            break;
+        };
+        let listing = parse_listing(&html, &url);
+        let futures = listing.event_urls.iter().map(|event_url| async move {
+            let html = client.try_get_text(event_url).await?;
+            parse_event(event_url, &html)
+        });
+        concerts.extend(join_all(futures).await.into_iter().flatten());
+        next_url = listing.next_page_url;
+    }
+
+    let now = Utc::now();
+    concerts.retain(|concert| concert.datetime > now);
+    concerts.iter().for_each(core::report_concert);
+    info!(
+        "Scraped {} concerts from Royal Academy of Music",
+        concerts.len()
+    );
+    concerts
+}
+
+/// Links found on one page of the listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listing {
+    pub event_urls: Vec<String>,
+    pub next_page_url: Option<String>,
+}
+
+/// Parse a page of the listing. `page_url` is the URL of the page, which relative links are
+/// resolved against.
+pub fn parse_listing(html: &str, page_url: &str) -> Listing {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let resolve = |href: &str| base.join(href).ok().map(|url| url.to_string());
+
+    let slc_event_link = Selector::parse(selectors::EVENT_LINK).unwrap();
+    // Links without an href can't be followed, so they are left out
+    let event_urls = doc
+        .select(&slc_event_link)
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(resolve)
+        .unique()
+        .collect();
+
+    let slc_next = Selector::parse(selectors::NEXT_PAGE).unwrap();
+    let next_page_url = doc
+        .select(&slc_next)
+        .next()
+        .and_then(|elem| elem.value().attr("href"))
+        .and_then(resolve);
+
+    Listing {
+        event_urls,
+        next_page_url,
+    }
+}
+
+/// Performers are listed as e.g. "Mariam Batsashvili (piano)", or just a name for ensembles
+fn parse_performer(text: &str) -> core::Performer {
+    let re = Regex::new(r"^(.*?)\s*\(([^)]*)\)$").unwrap();
+    match re.captures(text) {
+        Some(cap) => core::Performer {
+            name: cap[1].to_string(),
+            instrument: Some(cap[2].to_string()),
+        },
+        None => core::Performer {
+            name: text.to_string(),
+            instrument: None,
+        },
+    }
+}
+
+/// Pieces are listed as e.g. "Brahms: Piano Quintet in F minor, Op. 34". Lines without a composer
+/// (e.g. "Programme to include") are ignored.
+fn parse_piece(text: &str) -> Option<core::Piece> {
+    let (composer, title) = text.split_once(": ")?;
+    Some(core::Piece {
+        composer: composer.trim().to_string(),
+        title: title.trim().to_string(),
+    })
+}
+
+/// Parse an event page. Events without a title, or whose date and time can't be read, are
+/// skipped.
+pub fn parse_event(event_url: &str, html: &str) -> Option<core::ConcertData> {
+    let doc = Html::parse_document(html);
+
+    let slc_title = Selector::parse(selectors::TITLE).unwrap();
+    let Some(title) = doc.select(&slc_title).next().map(core::element_text) else {
+        warn!("Skipping {}, which has no title", event_url);
+        return None;
+    };
+
+    let slc_subtitle = Selector::parse(selectors::SUBTITLE).unwrap();
+    let subtitle = doc
+        .select(&slc_subtitle)
+        .next()
+        .map(core::element_text)
+        .filter(|s| !s.is_empty());
+
+    // The datetime attribute is in London time, e.g. "2026-02-03T13:05"
+    let slc_datetime = Selector::parse(selectors::DATETIME).unwrap();
+    let Some(datetime_str) = doc
+        .select(&slc_datetime)
+        .next()
+        .and_then(|elem| elem.value().attr("datetime"))
+    else {
+        warn!("Skipping {}, which has no datetime", event_url);
+        return None;
+    };
+    let datetime = datetime::parse_london(datetime_str)
+        .inspect_err(|e| warn!("Skipping {}: {}", event_url, e))
+        .ok()?;
+
+    // e.g. "Duke's Hall" or "Angela Burgess Recital Hall"
+    let slc_venue = Selector::parse(selectors::VENUE).unwrap();
+    let venue = match doc.select(&slc_venue).next().map(core::element_text) {
+        Some(hall) if !hall.is_empty() => format!("Royal Academy of Music ({})", hall),
+        _ => "Royal Academy of Music".to_string(),
+    };
+
+    // e.g. "Free, no booking required", "Free, booking required", or "£15 (£10 concessions)"
+    let slc_price = Selector::parse(selectors::PRICE).unwrap();
+    let price_text = doc
+        .select(&slc_price)
+        .next()
+        .map(core::element_text)
+        .unwrap_or_default();
+    let prices = core::parse_prices(&price_text);
+    let min_price = prices.iter().min().copied();
+    let max_price = prices.iter().max().copied();
+    let availability = core::parse_availability(&price_text, min_price, max_price);
+
+    let slc_description = Selector::parse(selectors::DESCRIPTION).unwrap();
+    let description = doc
+        .select(&slc_description)
+        .map(core::element_text)
+        .join("\n");
+
+    let slc_performers = Selector::parse(selectors::PERFORMER).unwrap();
+    let performers: Vec<core::Performer> = doc
+        .select(&slc_performers)
+        .map(|li| parse_performer(&core::element_text(li)))
+        .collect();
+
+    let slc_pieces = Selector::parse(selectors::PIECE).unwrap();
+    let pieces: Vec<core::Piece> = doc
+        .select(&slc_pieces)
+        .filter_map(|li| parse_piece(&core::element_text(li)))
+        .collect();
+
+    // e.g. "Lunchtime concerts" or "Opera"
+    let slc_tags = Selector::parse(selectors::TAG).unwrap();
+    let tags: Vec<String> = doc.select(&slc_tags).map(core::element_text).collect();
+
+    let mut concert = core::ConcertData {
+        datetime,
+        url: event_url.to_string(),
+        performers,
+        title,
+        subtitle,
+        description: Some(description.clone()),
+        programme_pdf_url: None,
+        programme_text: None,
+        pieces,
+        venue,
+        category: core::Category::Other,
+        min_price,
+        max_price,
+        availability,
+        media: media::find_media(&description, datetime, event_url),
+        is_wigmore_u35: false,
+        series: vec![],
+        production: None,
+    };
+    concert.category = classify::classify(&concert, &tags);
+    Some(concert)
+}
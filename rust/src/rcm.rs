@@ -0,0 +1,195 @@
+use crate::classify;
+use crate::core;
+use crate::datetime;
+use crate::http;
+use crate::media;
+use chrono::Utc;
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{info, warn};
+use scraper::{ElementRef, Html, Selector};
+
+pub const RCM_URL: &str = "https://www.rcm.ac.uk/events/?type=concert";
+
+/// Selectors for the listing and event pages, which the healthcheck probes as well
+pub mod selectors {
+    pub const EVENT_LINK: &str = "div.event-card h3 a";
+    pub const TITLE: &str = "h1.page-title";
+    pub const DATETIME: &str = "p.event-date";
+    pub const VENUE: &str = "p.event-venue";
+    pub const PRICE: &str = "p.event-price";
+    pub const DESCRIPTION: &str = "div.event-content p";
+    pub const PERFORMER: &str = "div.event-performers li";
+    pub const PIECE: &str = "div.event-repertoire li";
+    pub const PIECE_COMPOSER: &str = "strong";
+    pub const SERIES: &str = "a.event-series";
+}
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping Royal College of Music from URL: {}", RCM_URL);
+    let Some(html) = client.try_get_text(RCM_URL).await else {
+        return vec![];
+    };
+
+    let futures = parse_listing(&html, RCM_URL)
+        .into_iter()
+        .map(|event_url| async move {
+            let html = client.try_get_text(&event_url).await?;
+            parse_event(&event_url, &html)
+        });
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = join_all(futures)
+        .await
+        .into_iter()
+        .flatten()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!(
+        "Scraped {} concerts from Royal College of Music",
+        concerts.len()
+    );
+    concerts
+}
+
+/// URLs of all the events in the listing. `page_url` is the URL of the listing, which relative
+/// links are resolved against.
+pub fn parse_listing(html: &str, page_url: &str) -> Vec<String> {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let slc_event_link = Selector::parse(selectors::EVENT_LINK).unwrap();
+    // Links without an href can't be followed, so they are left out
+    doc.select(&slc_event_link)
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(|href| base.join(href).ok())
+        .map(|url| url.to_string())
+        .unique()
+        .collect()
+}
+
+/// Performers are listed as e.g. "Jâms Coleman, piano", or just a name for ensembles
+fn parse_performer(text: &str) -> core::Performer {
+    match text.split_once(", ") {
+        Some((name, role)) => core::Performer {
+            name: name.trim().to_string(),
+            instrument: Some(role.trim().to_string()),
+        },
+        None => core::Performer {
+            name: text.trim().to_string(),
+            instrument: None,
+        },
+    }
+}
+
+/// Pieces are written as e.g. "<strong>Ravel</strong> Piano Trio in A minor"
+fn parse_piece(li: ElementRef) -> Option<core::Piece> {
+    let slc_composer = Selector::parse(selectors::PIECE_COMPOSER).unwrap();
+    let composer = core::element_text(li.select(&slc_composer).next()?);
+    let full_text = core::element_text(li);
+    let title = full_text
+        .strip_prefix(composer.as_str())
+        .unwrap_or(&full_text)
+        .trim()
+        .to_string();
+    (!title.is_empty()).then_some(core::Piece { composer, title })
+}
+
+/// Parse an event page. Events without a title, or whose date and time can't be read, are
+/// skipped.
+pub fn parse_event(event_url: &str, html: &str) -> Option<core::ConcertData> {
+    let doc = Html::parse_document(html);
+
+    let slc_title = Selector::parse(selectors::TITLE).unwrap();
+    let Some(title) = doc.select(&slc_title).next().map(core::element_text) else {
+        warn!("Skipping {}, which has no title", event_url);
+        return None;
+    };
+
+    let slc_datetime = Selector::parse(selectors::DATETIME).unwrap();
+    // Written out in full, e.g. "Wednesday 4 February 2026, 7.30pm" or "Friday 6 March 2026, 12
+    // noon"
+    let Some(datetime_elem) = doc.select(&slc_datetime).next() else {
+        warn!("Skipping {}, which has no datetime", event_url);
+        return None;
+    };
+    let datetime = datetime::parse_london(&core::element_text(datetime_elem))
+        .inspect_err(|e| warn!("Skipping {}: {}", event_url, e))
+        .ok()?;
+
+    // e.g. "Amaryllis Fleming Concert Hall" or "Britten Theatre"
+    let slc_venue = Selector::parse(selectors::VENUE).unwrap();
+    let venue = match doc.select(&slc_venue).next().map(core::element_text) {
+        Some(hall) if !hall.is_empty() => format!("Royal College of Music ({})", hall),
+        _ => "Royal College of Music".to_string(),
+    };
+
+    let slc_price = Selector::parse(selectors::PRICE).unwrap();
+    let price_text = doc
+        .select(&slc_price)
+        .next()
+        .map(core::element_text)
+        .unwrap_or_default();
+    let prices = core::parse_prices(&price_text);
+    let min_price = prices.iter().min().copied();
+    let max_price = prices.iter().max().copied();
+    let availability = core::parse_availability(&price_text, min_price, max_price);
+
+    let slc_description = Selector::parse(selectors::DESCRIPTION).unwrap();
+    let description = doc
+        .select(&slc_description)
+        .map(core::element_text)
+        .join("\n");
+
+    let slc_performers = Selector::parse(selectors::PERFORMER).unwrap();
+    let performers: Vec<core::Performer> = doc
+        .select(&slc_performers)
+        .map(|li| parse_performer(&core::element_text(li)))
+        .collect();
+
+    let slc_pieces = Selector::parse(selectors::PIECE).unwrap();
+    let pieces: Vec<core::Piece> = doc.select(&slc_pieces).filter_map(parse_piece).collect();
+
+    // e.g. the RCM Chamber Music Festival
+    let slc_series = Selector::parse(selectors::SERIES).unwrap();
+    let series: Vec<core::Series> = doc
+        .select(&slc_series)
+        .map(|elem| core::Series {
+            name: core::element_text(elem),
+            organiser: "Royal College of Music".to_string(),
+            start_date: None,
+            end_date: None,
+            url: elem.value().attr("href").map(|href| {
+                reqwest::Url::parse(event_url)
+                    .unwrap()
+                    .join(href)
+                    .unwrap()
+                    .to_string()
+            }),
+        })
+        .collect();
+    let tags: Vec<String> = series.iter().map(|s| s.name.clone()).collect();
+
+    let mut concert = core::ConcertData {
+        datetime,
+        url: event_url.to_string(),
+        performers,
+        title,
+        subtitle: None,
+        description: Some(description.clone()),
+        programme_pdf_url: None,
+        programme_text: None,
+        pieces,
+        venue,
+        category: core::Category::Other,
+        min_price,
+        max_price,
+        availability,
+        media: media::find_media(&description, datetime, event_url),
+        is_wigmore_u35: false,
+        series,
+        production: None,
+    };
+    concert.category = classify::classify(&concert, &tags);
+    Some(concert)
+}
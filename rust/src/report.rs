@@ -0,0 +1,234 @@
+use crate::core::ConcertData;
+use crate::http::FetchStats;
+use crate::source::Source;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{create_dir_all, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// Statistics about scraping a single source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceReport {
+    pub source: Source,
+    pub n_concerts: usize,
+    pub n_missing_repertoire: usize,
+    pub n_missing_prices: usize,
+    pub n_missing_performers: usize,
+    pub http_requests: u32,
+    pub retries: u32,
+    pub cache_hits: u32,
+    pub errors: u32,
+    pub duration_secs: f64,
+    /// Why scraping the source failed, if it did. Its concerts from the previous run are kept.
+    #[serde(default)]
+    pub failure: Option<String>,
+}
+
+impl SourceReport {
+    pub fn new(
+        source: Source,
+        concerts: &[ConcertData],
+        stats: &FetchStats,
+        duration: Duration,
+    ) -> SourceReport {
+        SourceReport {
+            source,
+            n_concerts: concerts.len(),
+            n_missing_repertoire: concerts.iter().filter(|c| c.pieces.is_empty()).count(),
+            n_missing_prices: concerts
+                .iter()
+                .filter(|c| c.min_price.is_none() && c.max_price.is_none())
+                .count(),
+            n_missing_performers: concerts.iter().filter(|c| c.performers.is_empty()).count(),
+            http_requests: stats.requests.load(Ordering::Relaxed),
+            retries: stats.retries.load(Ordering::Relaxed),
+            cache_hits: stats.cache_hits.load(Ordering::Relaxed),
+            errors: stats.errors.load(Ordering::Relaxed),
+            duration_secs: duration.as_secs_f64(),
+            failure: None,
+        }
+    }
+
+    /// Report on a source which failed to scrape, with what was fetched before it failed
+    pub fn failed(
+        source: Source,
+        message: String,
+        stats: &FetchStats,
+        duration: Duration,
+    ) -> SourceReport {
+        SourceReport {
+            failure: Some(message),
+            ..SourceReport::new(source, &[], stats, duration)
+        }
+    }
+}
+
+/// Statistics about a whole scraping run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub started_at: DateTime<Utc>,
+    pub duration_secs: f64,
+    pub n_concerts: usize,
+    pub sources: Vec<SourceReport>,
+}
+
+impl RunReport {
+    /// Write the report to `<dir>/report.json`, and also append it to `<dir>/reports.jsonl`, which
+    /// contains one line for every run so far.
+    pub fn write(&self, dir: &Path) {
+        create_dir_all(dir).unwrap();
+        let report_file = File::create(dir.join("report.json")).unwrap();
+        serde_json::to_writer_pretty(report_file, self).unwrap();
+
+        let mut history_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("reports.jsonl"))
+            .unwrap();
+        writeln!(history_file, "{}", serde_json::to_string(self).unwrap()).unwrap();
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Run started at {}: {} concerts in {:.1}s",
+            self.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.n_concerts,
+            self.duration_secs
+        )?;
+        let width = self
+            .sources
+            .iter()
+            .map(|s| s.source.name().len())
+            .chain(["Source".len()])
+            .max()
+            .unwrap();
+        writeln!(
+            f,
+            "{:<width$} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>9}",
+            "Source",
+            "Concerts",
+            "No rep.",
+            "No price",
+            "No perf.",
+            "Requests",
+            "Retries",
+            "Cached",
+            "Errors",
+            "Time (s)"
+        )?;
+        for s in &self.sources {
+            writeln!(
+                f,
+                "{:<width$} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>9.1}",
+                s.source.name(),
+                s.n_concerts,
+                s.n_missing_repertoire,
+                s.n_missing_prices,
+                s.n_missing_performers,
+                s.http_requests,
+                s.retries,
+                s.cache_hits,
+                s.errors,
+                s.duration_secs
+            )?;
+        }
+        for s in &self.sources {
+            if let Some(failure) = &s.failure {
+                writeln!(f, "{} failed: {}", s.source.name(), failure)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Piece;
+    use chrono::TimeZone;
+
+    #[test]
+    fn source_report_counts_missing_details() {
+        let concerts = vec![
+            ConcertData {
+                min_price: Some(1000),
+                pieces: vec![Piece {
+                    title: "Winterreise".to_string(),
+                    composer: "Schubert".to_string(),
+                }],
+                ..Default::default()
+            },
+            ConcertData::default(),
+        ];
+        let stats = FetchStats::default();
+        stats.requests.store(5, Ordering::Relaxed);
+        stats.errors.store(1, Ordering::Relaxed);
+        let report = SourceReport::new(
+            Source::Wigmore,
+            &concerts,
+            &stats,
+            Duration::from_millis(1500),
+        );
+        assert_eq!(report.n_concerts, 2);
+        assert_eq!(report.n_missing_repertoire, 1);
+        assert_eq!(report.n_missing_prices, 1);
+        assert_eq!(report.n_missing_performers, 2);
+        assert_eq!((report.http_requests, report.errors), (5, 1));
+        assert_eq!(report.duration_secs, 1.5);
+    }
+
+    #[test]
+    fn reports_are_appended_to_history() {
+        let dir = std::env::temp_dir().join(format!("ldncls-report-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let report = RunReport {
+            started_at: Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap(),
+            duration_secs: 12.0,
+            n_concerts: 0,
+            sources: vec![],
+        };
+        report.write(&dir);
+        report.write(&dir);
+        let history = std::fs::read_to_string(dir.join("reports.jsonl")).unwrap();
+        assert_eq!(history.lines().count(), 2);
+        assert!(dir.join("report.json").exists());
+        assert!(report
+            .to_string()
+            .starts_with("Run started at 2026-03-01 06:00:00 UTC: 0 concerts in 12.0s\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_sources_are_reported() {
+        let stats = FetchStats::default();
+        stats.requests.store(3, Ordering::Relaxed);
+        stats.errors.store(3, Ordering::Relaxed);
+        let report = SourceReport::failed(
+            Source::StMartinInTheFields,
+            "St Martin-in-the-Fields: panicked".to_string(),
+            &stats,
+            Duration::from_secs(2),
+        );
+        assert_eq!(report.n_concerts, 0);
+        assert_eq!((report.http_requests, report.errors), (3, 3));
+        let run = RunReport {
+            started_at: Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap(),
+            duration_secs: 2.0,
+            n_concerts: 0,
+            sources: vec![report],
+        };
+        let text = run.to_string();
+        assert!(text.contains("St Martin-in-the-Fields failed: St Martin-in-the-Fields: panicked"));
+        // The source column is wide enough for the name
+        let header = text.lines().nth(1).unwrap();
+        let row = text.lines().nth(2).unwrap();
+        assert_eq!(header.find("Concerts"), row.find("       0"));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::core;
+use crate::http;
+use crate::media;
+use crate::opera;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{info, warn};
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+pub const ROH_URL: &str = "https://www.rbo.org.uk/tickets-and-events?category=opera";
+pub const ROH_EVENT_URL_PREFIX: &str = "https://www.rbo.org.uk/tickets-and-events/";
+
+pub async fn scrape(client: &http::Client) -> Vec<core::ConcertData> {
+    info!("Scraping Royal Opera House from URL: {}", ROH_URL);
+    let Some(html) = client.try_get_text(ROH_URL).await else {
+        return vec![];
+    };
+
+    let futures = parse_listing(&html).into_iter().map(|url| async move {
+        match client.try_get_text(&url).await {
+            Some(html) => parse_production(&url, &html),
+            None => vec![],
+        }
+    });
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = join_all(futures)
+        .await
+        .into_iter()
+        .flatten()
+        .filter(|concert| concert.datetime > now)
+        .inspect(core::report_concert)
+        .collect();
+
+    info!(
+        "Scraped {} performances from Royal Opera House",
+        concerts.len()
+    );
+    concerts
+}
+
+/// The site is built with Next.js, so everything on the page is in the JSON in
+/// `script#__NEXT_DATA__`, just like Wigmore Hall's `script#props`. `url` is only used in
+/// warnings if the script is missing or can't be parsed.
+fn get_page_props(url: &str, html: &str) -> Option<Value> {
+    let doc = Html::parse_document(html);
+    let selector = Selector::parse("script#__NEXT_DATA__").unwrap();
+    let Some(script) = doc.select(&selector).next() else {
+        warn!("Could not find __NEXT_DATA__ script on {}", url);
+        return None;
+    };
+    let json: Value = serde_json::from_str(&script.inner_html())
+        .inspect_err(|e| warn!("Failed to parse __NEXT_DATA__ on {}: {}", url, e))
+        .ok()?;
+    Some(json["props"]["pageProps"].clone())
+}
+
+/// URLs of all the opera productions in the listing. The Royal Ballet's productions are in the
+/// same listing, so are left out, as are events without a slug.
+pub fn parse_listing(html: &str) -> Vec<String> {
+    let Some(props) = get_page_props(ROH_URL, html) else {
+        return vec![];
+    };
+    let Some(events) = props["events"].as_array() else {
+        warn!("Royal Opera House listing has no events");
+        return vec![];
+    };
+    events
+        .iter()
+        .filter(|event| event["category"].as_str() == Some("Opera"))
+        .filter_map(|event| event["slug"].as_str())
+        .map(|slug| format!("{}{}", ROH_EVENT_URL_PREFIX, slug))
+        .unique()
+        .collect()
+}
+
+fn get_name_with_role(people: &Value, role: &str) -> Option<String> {
+    people
+        .as_array()?
+        .iter()
+        .find(|person| {
+            person["role"]
+                .as_str()
+                .is_some_and(|r| r.eq_ignore_ascii_case(role))
+        })
+        .and_then(|person| person["name"].as_str())
+        .map(|s| s.to_string())
+}
+
+/// Cast members without a role or a name are left out
+fn get_cast(cast: &Value) -> Vec<core::CastMember> {
+    cast.as_array()
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|member| {
+                    Some(core::CastMember {
+                        role: member["role"].as_str()?.to_string(),
+                        name: member["name"].as_str()?.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Prices are given in pounds
+fn get_price(value: &Value) -> Option<u32> {
+    value.as_f64().map(|pounds| (pounds * 100.0).round() as u32)
+}
+
+/// Parse a production page into one concert for every performance. The cast and conductor can
+/// change between performances, so each performance has its own. Productions without a title,
+/// composer, or performances are skipped.
+pub fn parse_production(url: &str, html: &str) -> Vec<core::ConcertData> {
+    let Some(props) = get_page_props(url, html) else {
+        return vec![];
+    };
+    let production = &props["production"];
+
+    let (Some(title), Some(composer)) = (
+        production["title"].as_str().map(|s| s.to_string()),
+        production["composer"].as_str().map(|s| s.to_string()),
+    ) else {
+        warn!("Skipping {}, which has no title or composer", url);
+        return vec![];
+    };
+    let Some(performances) = production["performances"].as_array() else {
+        warn!("Skipping {}, which has no performances", url);
+        return vec![];
+    };
+    let subtitle = production["strapline"]
+        .as_str()
+        .map(|s| s.to_string())
+        .filter(|s| !s.is_empty());
+    let description = production["description"]
+        .as_str()
+        .map(core::text_of_html)
+        .unwrap_or_default();
+    let (running_time_minutes, n_intervals) = production["runningTime"]
+        .as_str()
+        .map(opera::parse_running_time)
+        .unwrap_or_default();
+    let (language, surtitles) = production["language"]
+        .as_str()
+        .map(opera::parse_language)
+        .unwrap_or_default();
+    let director = get_name_with_role(&production["creatives"], "Director");
+    let default_conductor = get_name_with_role(&production["creatives"], "Conductor");
+
+    performances
+        .iter()
+        .filter_map(|performance| {
+            let Some(datetime_str) = performance["dateTime"].as_str() else {
+                warn!("Skipping performance of {} without a dateTime", url);
+                return None;
+            };
+            let datetime = match DateTime::parse_from_rfc3339(datetime_str) {
+                Ok(datetime) => datetime.with_timezone(&Utc),
+                Err(e) => {
+                    warn!(
+                        "Skipping performance of {} at unexpected datetime {}: {}",
+                        url, datetime_str, e
+                    );
+                    return None;
+                }
+            };
+            // Most performances are on the Main Stage, but some are in the Linbury Theatre
+            let venue = match performance["venue"].as_str() {
+                Some(stage) if stage != "Main Stage" => format!("Royal Opera House ({})", stage),
+                _ => "Royal Opera House".to_string(),
+            };
+
+            let production_details = core::Production {
+                title: title.clone(),
+                composer: composer.clone(),
+                conductor: performance["conductor"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .or(default_conductor.clone()),
+                director: director.clone(),
+                cast: get_cast(&performance["cast"]),
+                running_time_minutes,
+                n_intervals,
+                language: language.clone(),
+                surtitles: surtitles.clone(),
+            };
+
+            Some(core::ConcertData {
+                datetime,
+                url: url.to_string(),
+                performers: opera::performers(&production_details),
+                title: title.clone(),
+                subtitle: subtitle.clone(),
+                description: Some(description.clone()),
+                programme_pdf_url: None,
+                programme_text: None,
+                pieces: vec![core::Piece {
+                    composer: composer.clone(),
+                    title: title.clone(),
+                }],
+                venue,
+                category: core::Category::Opera,
+                min_price: get_price(&performance["minPrice"]),
+                max_price: get_price(&performance["maxPrice"]),
+                media: media::find_media(&description, datetime, url),
+                availability: core::Availability::Ticketed,
+                is_wigmore_u35: false,
+                series: vec![],
+                production: Some(production_details),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,352 @@
+use crate::classify;
+use crate::core;
+use crate::datetime;
+use crate::declarative;
+use crate::http;
+use crate::media;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use itertools::Itertools;
+use log::{debug, info, warn};
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A venue whose website describes its events with schema.org JSON-LD, so that it can be scraped
+/// without a module of its own. Each one is its own source, with a definition in `scrapers/` (e.g.
+/// `scrapers/cadogan_hall.toml`) which is read every time it is scraped.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Site {
+    pub name: String,
+    pub listing_url: String,
+    /// Links on the listing to pages with the events on. If not given, the events are on the
+    /// listing itself.
+    pub link: Option<String>,
+    /// Used for events which don't say where they are
+    pub venue: String,
+}
+
+impl Site {
+    pub fn from_toml(text: &str) -> Site {
+        let site: Site =
+            toml::from_str(text).unwrap_or_else(|e| panic!("Invalid site definition: {}", e));
+        if let Some(link) = &site.link {
+            Selector::parse(link).unwrap_or_else(|_| panic!("Invalid link selector: {}", link));
+        }
+        site
+    }
+
+    /// Read a site from `declarative::definitions_dir()`, e.g. `load("cadogan_hall")` reads
+    /// cadogan_hall.toml
+    pub fn load(name: &str) -> Site {
+        let path = declarative::definitions_dir().join(format!("{}.toml", name));
+        debug!("Reading site definition from {}", path.display());
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read site definition {}: {}", path.display(), e));
+        Site::from_toml(&text)
+    }
+}
+
+pub async fn scrape(client: &http::Client, site: &Site) -> Vec<core::ConcertData> {
+    info!("Scraping {} from URL: {}", site.name, site.listing_url);
+    let Some(html) = client.try_get_text(&site.listing_url).await else {
+        return vec![];
+    };
+
+    let concerts: Vec<core::ConcertData> = match &site.link {
+        None => parse_page(&html, &site.listing_url, &site.venue),
+        Some(link) => {
+            let futures = parse_listing(&html, &site.listing_url, link)
+                .into_iter()
+                .map(|url| async move {
+                    match client.try_get_text(&url).await {
+                        Some(html) => parse_page(&html, &url, &site.venue),
+                        None => vec![],
+                    }
+                });
+            join_all(futures).await.into_iter().flatten().collect()
+        }
+    };
+
+    // The same event can be on several pages, e.g. a series page and the event's own page
+    let now = Utc::now();
+    let concerts: Vec<core::ConcertData> = concerts
+        .into_iter()
+        .filter(|concert| concert.datetime > now)
+        .unique_by(|concert| (concert.datetime, concert.title.clone()))
+        .inspect(core::report_concert)
+        .collect();
+
+    info!("Scraped {} concerts from {}", concerts.len(), site.name);
+    concerts
+}
+
+/// URLs of the pages linked to from a listing. `page_url` is the URL of the listing, which
+/// relative links are resolved against.
+pub fn parse_listing(html: &str, page_url: &str, link_selector: &str) -> Vec<String> {
+    let doc = Html::parse_document(html);
+    let base = reqwest::Url::parse(page_url).unwrap();
+    let slc_link = Selector::parse(link_selector)
+        .unwrap_or_else(|_| panic!("Invalid link selector: {}", link_selector));
+    doc.select(&slc_link)
+        .filter_map(|elem| elem.value().attr("href"))
+        .filter_map(|href| base.join(href).ok())
+        .map(|url| url.to_string())
+        .unique()
+        .collect()
+}
+
+/// All the events on a page, classified. Cancelled events are left out. Events which link to
+/// another website (e.g. the venue's box office) get the URL of the page instead, so that every
+/// concert can be traced back to the site it was scraped from.
+pub fn parse_page(html: &str, page_url: &str, default_venue: &str) -> Vec<core::ConcertData> {
+    find_events(html)
+        .iter()
+        .filter(|event| !is_cancelled(event))
+        .filter_map(|event| {
+            let mut concert = parse_event(event, page_url, default_venue)?;
+            if !same_host(&concert.url, page_url) {
+                concert.url = page_url.to_string();
+            }
+            concert.category = classify::classify(&concert, &tags(event));
+            Some(concert)
+        })
+        .collect()
+}
+
+/// Whether two URLs are on the same website (with or without "www.")
+fn same_host(url: &str, other_url: &str) -> bool {
+    let host = |url: &str| {
+        reqwest::Url::parse(url).ok().and_then(|url| {
+            url.host_str()
+                .map(|host| host.trim_start_matches("www.").to_string())
+        })
+    };
+    host(url).is_some() && host(url) == host(other_url)
+}
+
+/// All the schema.org events embedded in a page as JSON-LD, i.e. in `<script
+/// type="application/ld+json">` tags. A script can have a single event, a list of them, or a
+/// `@graph` with events among other things (e.g. the organisation that runs the venue). Scripts
+/// which aren't valid JSON are skipped, because some sites get the escaping wrong.
+pub fn find_events(html: &str) -> Vec<Value> {
+    let doc = Html::parse_document(html);
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    doc.select(&selector)
+        .filter_map(|elem| {
+            // Not `inner_html`, which would escape e.g. "&amp;" a second time
+            let json_text: String = elem.text().collect();
+            serde_json::from_str::<Value>(&json_text)
+                .inspect_err(|e| warn!("Skipping invalid JSON-LD: {}", e))
+                .ok()
+        })
+        .flat_map(|json| match json {
+            Value::Array(items) => items,
+            Value::Object(ref obj) if obj.contains_key("@graph") => {
+                json["@graph"].as_array().cloned().unwrap_or_default()
+            }
+            other => vec![other],
+        })
+        .filter(is_event)
+        .collect()
+}
+
+/// Whether a JSON-LD item is an event. Its `@type` may be a string or a list of them, and there are
+/// many kinds of event (e.g. "MusicEvent" or "TheaterEvent").
+fn is_event(item: &Value) -> bool {
+    let is_event_type = |t: &Value| t.as_str().is_some_and(|t| t.ends_with("Event"));
+    match &item["@type"] {
+        Value::Array(types) => types.iter().any(is_event_type),
+        t => is_event_type(t),
+    }
+}
+
+/// Events which are cancelled stay on some sites with an `eventStatus` of
+/// "https://schema.org/EventCancelled"
+fn is_cancelled(event: &Value) -> bool {
+    event["eventStatus"]
+        .as_str()
+        .is_some_and(|status| status.ends_with("EventCancelled"))
+}
+
+/// Words the site uses to describe the event, i.e. its `genre` and `keywords`, which are useful for
+/// classifying it. Keywords can be a list or a comma-separated string.
+pub fn tags(event: &Value) -> Vec<String> {
+    let genres = one_or_many(&event["genre"])
+        .into_iter()
+        .filter_map(|genre| genre.as_str())
+        .map(|genre| genre.to_string());
+    let keywords = one_or_many(&event["keywords"])
+        .into_iter()
+        .filter_map(|keywords| keywords.as_str())
+        .flat_map(|keywords| keywords.split(','))
+        .map(|keyword| keyword.trim().to_string())
+        .filter(|keyword| !keyword.is_empty());
+    genres.chain(keywords).collect()
+}
+
+/// Parse a schema.org date and time. They should be in ISO 8601 with a UTC offset, e.g.
+/// "2026-02-02T13:10:00+00:00", but plenty of sites leave the offset out, in which case the time
+/// is in London.
+pub fn parse_datetime(text: &str) -> Result<DateTime<Utc>, datetime::DatetimeError> {
+    datetime::parse_london(text)
+}
+
+/// A value which may be given either once or as a list
+fn one_or_many(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(values) => values.iter().collect(),
+        Value::Null => vec![],
+        value => vec![value],
+    }
+}
+
+/// Prices are in pounds, and can be numbers or strings (e.g. "10.00")
+fn get_price(value: &Value) -> Option<u32> {
+    let pounds = match value {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => s.trim().trim_start_matches('£').parse::<f64>().ok()?,
+        _ => return None,
+    };
+    Some((pounds * 100.0).round() as u32)
+}
+
+/// All prices in an event's offers, in pennies. Each offer can have a single `price`, or a range
+/// from `lowPrice` to `highPrice`.
+fn get_prices(event: &Value) -> Vec<u32> {
+    let mut prices: Vec<u32> = one_or_many(&event["offers"])
+        .into_iter()
+        .flat_map(|offer| {
+            [&offer["price"], &offer["lowPrice"], &offer["highPrice"]]
+                .into_iter()
+                .filter_map(get_price)
+        })
+        .collect();
+    if prices.is_empty() && event["isAccessibleForFree"].as_bool() == Some(true) {
+        prices.push(0);
+    }
+    prices
+}
+
+/// The name of a Thing, which may also be given as just a string
+fn name_of(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .or(value["name"].as_str())
+        .map(core::text_of_html)
+        .filter(|name| !name.is_empty())
+}
+
+/// Performers are usually a Person or an Organization (e.g. a PerformingGroup). The instrument or
+/// voice can be given by wrapping the performer in a PerformanceRole with a `roleName`, e.g.
+/// `{"@type": "PerformanceRole", "roleName": "piano", "performer": {"name": "Mitsuko Uchida"}}`.
+fn parse_performer(value: &Value) -> Option<core::Performer> {
+    let (performer, role) = match value.get("performer") {
+        Some(performer) => (performer, value["roleName"].as_str()),
+        None => (value, value["roleName"].as_str()),
+    };
+    Some(core::Performer {
+        name: name_of(performer)?,
+        instrument: role
+            .map(|role| role.trim().to_string())
+            .filter(|role| !role.is_empty()),
+    })
+}
+
+/// Works are usually a MusicComposition, whose composer can be a Person, just a name, or a list of
+/// them (e.g. for arrangements). Works without a composer are kept, with an empty one.
+fn parse_work(value: &Value) -> Option<core::Piece> {
+    let title = name_of(value)?;
+    let composer = one_or_many(&value["composer"])
+        .into_iter()
+        .filter_map(name_of)
+        .join(" & ");
+    Some(core::Piece { composer, title })
+}
+
+/// Turn a schema.org event into a concert. `page_url` is the URL of the page it came from, which is
+/// used if the event doesn't have a URL of its own (and relative URLs are resolved against it).
+/// `default_venue` is used if the event doesn't say where it is. The category is left as
+/// `Other`, for the caller to classify. Events without a name, or whose start can't be read, are
+/// skipped.
+pub fn parse_event(
+    event: &Value,
+    page_url: &str,
+    default_venue: &str,
+) -> Option<core::ConcertData> {
+    let Some(title) = event["name"].as_str().map(core::text_of_html) else {
+        warn!("Skipping event without a name on {}", page_url);
+        return None;
+    };
+    let Some(start) = event["startDate"].as_str() else {
+        warn!("Skipping {} on {}, which has no startDate", title, page_url);
+        return None;
+    };
+    let datetime = parse_datetime(start)
+        .inspect_err(|e| warn!("Skipping {} on {}: {}", title, page_url, e))
+        .ok()?;
+    let url = event["url"]
+        .as_str()
+        .and_then(|href| reqwest::Url::parse(page_url).ok()?.join(href).ok())
+        .map_or(page_url.to_string(), |url| url.to_string());
+    let description = event["description"]
+        .as_str()
+        .map(core::text_of_html)
+        .unwrap_or_default();
+
+    // The location is usually a Place, but can be just its name
+    let venue = one_or_many(&event["location"])
+        .into_iter()
+        .find_map(|location| location.as_str().or(location["name"].as_str()))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| default_venue.to_string());
+
+    // Offers sometimes only have a description, e.g. "Free, retiring collection"
+    let offers_text = one_or_many(&event["offers"])
+        .into_iter()
+        .filter_map(|offer| offer["description"].as_str())
+        .join("\n");
+    let mut prices = get_prices(event);
+    if prices.is_empty() {
+        prices = core::parse_prices(&offers_text);
+    }
+    let min_price = prices.iter().min().copied();
+    let max_price = prices.iter().max().copied();
+    let availability = core::parse_availability(
+        &format!("{}\n{}", offers_text, description),
+        min_price,
+        max_price,
+    );
+
+    let performers: Vec<core::Performer> = one_or_many(&event["performer"])
+        .into_iter()
+        .filter_map(parse_performer)
+        .collect();
+    let pieces: Vec<core::Piece> = one_or_many(&event["workPerformed"])
+        .into_iter()
+        .filter_map(parse_work)
+        .collect();
+
+    Some(core::ConcertData {
+        datetime,
+        url: url.clone(),
+        performers,
+        title,
+        subtitle: None,
+        description: Some(description.clone()),
+        programme_pdf_url: None,
+        programme_text: None,
+        pieces,
+        venue,
+        category: core::Category::Other,
+        min_price,
+        max_price,
+        availability,
+        media: media::find_media(&description, datetime, &url),
+        is_wigmore_u35: false,
+        series: vec![],
+        production: None,
+    })
+}
@@ -0,0 +1,283 @@
+use crate::churches::{smith_square, southwark_cathedral, st_pauls, westminster_abbey};
+use crate::core::{self, Category, Concert, ConcertData};
+use crate::http;
+use crate::report::SourceReport;
+use crate::source::Source;
+use crate::{
+    eno, guildhall, kingsplace, lso_st_lukes, programmes, proms, ram, rcm, royal_opera_house,
+    schema_org, southbank, wigmore,
+};
+use log::{debug, info, warn};
+use reqwest::header;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Options controlling what gets scraped. These are read from environment variables.
+#[derive(Debug, Clone)]
+pub struct ScrapeOptions {
+    /// Maximum number of new or changed Wigmore Hall concerts to fetch (`None` means all of them)
+    pub max_wigmore_concerts: Option<usize>,
+    pub enabled_sources: Vec<Source>,
+    /// Directory where scrapers can keep data between runs, to avoid fetching it again. If `None`,
+    /// nothing is cached.
+    pub cache_dir: Option<PathBuf>,
+    /// Whether to download programme PDFs and extract their text
+    pub fetch_programmes: bool,
+    /// Categories of events to leave out. If `None`, each source's defaults are used.
+    pub excluded_categories: Option<Vec<Category>>,
+}
+
+fn envvar_is_empty_or_undefined(var: &str) -> bool {
+    match std::env::var(var) {
+        Ok(value) => value.is_empty(),
+        Err(_) => true,
+    }
+}
+
+impl ScrapeOptions {
+    /// Read options from environment variables. `cache_dir` is used unless caching is disabled by
+    /// setting $LDNCLS_NO_CACHE.
+    pub fn from_env(cache_dir: &Path) -> ScrapeOptions {
+        // Read from $LDNCLS_WIGMORE_MAX and default to 220 if not given. If you push it a bit more
+        // it starts to rate limit. Concerts which are already cached don't count towards this, so
+        // the whole season gets covered over a few runs.
+        let max_wigmore_concerts = {
+            match std::env::var("LDNCLS_WIGMORE_MAX") {
+                Ok(s) => match s.as_str() {
+                    "all" => None,
+                    _ => Some(s.parse::<usize>().unwrap()),
+                },
+                Err(_) => Some(220),
+            }
+        };
+        debug!("max_wigmore_concerts: {:?}", max_wigmore_concerts);
+
+        let enabled_sources = Source::ALL
+            .into_iter()
+            .filter(|source| {
+                let enabled = envvar_is_empty_or_undefined(source.disable_envvar());
+                if !enabled {
+                    info!(
+                        "${} not empty; skipping {}",
+                        source.disable_envvar(),
+                        source.name()
+                    );
+                }
+                enabled
+            })
+            .collect();
+
+        let cache_dir = if envvar_is_empty_or_undefined("LDNCLS_NO_CACHE") {
+            Some(cache_dir.to_path_buf())
+        } else {
+            info!("$LDNCLS_NO_CACHE not empty; not using cache");
+            None
+        };
+
+        let fetch_programmes = !envvar_is_empty_or_undefined("LDNCLS_PROGRAMMES");
+        debug!("fetch_programmes: {}", fetch_programmes);
+
+        // Comma-separated list of categories, e.g. "family,talk". "none" means that nothing is
+        // excluded.
+        let excluded_categories = match std::env::var("LDNCLS_EXCLUDE_CATEGORIES") {
+            Ok(s) if s == "none" => Some(vec![]),
+            Ok(s) if !s.is_empty() => Some(
+                s.split(',')
+                    .map(|name| {
+                        Category::from_name(name.trim()).unwrap_or_else(|| {
+                            panic!("Unknown category in $LDNCLS_EXCLUDE_CATEGORIES: {}", name)
+                        })
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+        debug!("excluded_categories: {:?}", excluded_categories);
+
+        ScrapeOptions {
+            max_wigmore_concerts,
+            enabled_sources,
+            cache_dir,
+            fetch_programmes,
+            excluded_categories,
+        }
+    }
+
+    pub fn excluded_categories(&self, source: Source) -> &[Category] {
+        match &self.excluded_categories {
+            Some(categories) => categories,
+            None => source.default_excluded_categories(),
+        }
+    }
+}
+
+pub fn build_client() -> http::Client {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::ACCEPT, header::HeaderValue::from_static("text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7"));
+
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .user_agent("penelopeysm/london-classical/0.1")
+        .build()
+        .unwrap();
+    match std::env::var("LDNCLS_RECORD_DIR") {
+        Ok(dir) if !dir.is_empty() => {
+            info!("Recording fetched pages in {}", dir);
+            http::Client::new(client).recording_to(PathBuf::from(dir))
+        }
+        _ => http::Client::new(client),
+    }
+}
+
+/// Scrape all concerts from a single source, returning them together with statistics about the
+/// scraping. The statistics are taken from `client`, which should only be used for this source
+/// (see `http::Client::with_new_stats`).
+pub async fn scrape_source(
+    source: Source,
+    client: &http::Client,
+    options: &ScrapeOptions,
+) -> (Vec<ConcertData>, SourceReport) {
+    let start = Instant::now();
+    let mut concerts = match source {
+        Source::Wigmore => {
+            info!("Scraping Wigmore Hall concerts");
+            let cache_path = options
+                .cache_dir
+                .as_ref()
+                .map(|dir| dir.join("wigmore.json"));
+            wigmore::get_concerts(client, options.max_wigmore_concerts, cache_path.as_deref()).await
+        }
+        Source::Proms => {
+            info!("Scraping Proms");
+            proms::scrape(client).await
+        }
+        Source::Southbank => {
+            info!("Scraping Southbank Centre concerts");
+            southbank::scrape(client).await
+        }
+        Source::KingsPlace => {
+            info!("Scraping Kings Place concerts");
+            kingsplace::scrape(client).await
+        }
+        Source::LsoStLukes => {
+            info!("Scraping LSO St Luke's concerts");
+            lso_st_lukes::scrape(client).await
+        }
+        Source::RoyalOperaHouse => {
+            info!("Scraping Royal Opera House performances");
+            royal_opera_house::scrape(client).await
+        }
+        Source::Eno => {
+            info!("Scraping English National Opera performances");
+            eno::scrape(client).await
+        }
+        Source::Ram => {
+            info!("Scraping Royal Academy of Music concerts");
+            ram::scrape(client).await
+        }
+        Source::Rcm => {
+            info!("Scraping Royal College of Music concerts");
+            rcm::scrape(client).await
+        }
+        Source::Guildhall => {
+            info!("Scraping Guildhall School concerts");
+            guildhall::scrape(client).await
+        }
+        Source::SmithSquare => {
+            info!("Scraping St John's Smith Square concerts");
+            smith_square::scrape(client).await
+        }
+        Source::SouthwarkCathedral => {
+            info!("Scraping Southwark Cathedral concerts");
+            southwark_cathedral::scrape(client).await
+        }
+        Source::StPauls => {
+            info!("Scraping St Paul's Cathedral concerts");
+            st_pauls::scrape(client).await
+        }
+        Source::WestminsterAbbey => {
+            info!("Scraping Westminster Abbey organ recitals");
+            westminster_abbey::scrape(client).await
+        }
+        Source::CadoganHall => {
+            schema_org::scrape(client, &schema_org::Site::load("cadogan_hall")).await
+        }
+        Source::StMartinInTheFields => {
+            schema_org::scrape(client, &schema_org::Site::load("st_martin_in_the_fields")).await
+        }
+    };
+    let excluded_categories = options.excluded_categories(source);
+    let n_found = concerts.len();
+    concerts.retain(|c| !excluded_categories.contains(&c.category));
+    if concerts.len() < n_found {
+        info!(
+            "Left out {} events from {} in excluded categories {:?}",
+            n_found - concerts.len(),
+            source.name(),
+            excluded_categories
+        );
+    }
+    info!("Found {} concerts from {}", concerts.len(), source.name());
+    if options.fetch_programmes {
+        info!("Fetching programmes for {}", source.name());
+        let store_dir = options.cache_dir.as_ref().map(|dir| dir.join("programmes"));
+        programmes::attach_programme_texts(&mut concerts, client, store_dir.as_deref()).await;
+    }
+    let report = SourceReport::new(source, &concerts, client.stats(), start.elapsed());
+    (concerts, report)
+}
+
+/// Sort concerts and give them IDs. The same concert can be listed by more than one source (e.g. a
+/// Guildhall School concert at LSO St Luke's), in which case it would get the same ID twice; only
+/// the first of these is kept, i.e. the one from the source which was scraped first.
+pub fn add_ids(mut concerts: Vec<ConcertData>) -> Vec<Concert> {
+    // The sort is stable, so concerts at the same time stay in the order they were scraped
+    concerts.sort_by_key(|concert| concert.datetime);
+
+    let mut seen_ids = HashSet::new();
+    concerts
+        .into_iter()
+        .map(core::add_id_to_concert)
+        .filter(|concert| {
+            let is_new = seen_ids.insert(concert.id.clone());
+            if !is_new {
+                warn!(
+                    "Dropping duplicate concert with ID {} ({})",
+                    concert.id, concert.concert.url
+                );
+            }
+            is_new
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn concerts_listed_twice_are_dropped() {
+        let concert = |hour, url: &str| ConcertData {
+            datetime: Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap(),
+            title: "Guildhall Symphony Orchestra".to_string(),
+            venue: "LSO St Luke's".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let concerts = add_ids(vec![
+            concert(19, "https://www.lso.co.uk/whats-on/gso"),
+            concert(13, "https://www.lso.co.uk/whats-on/gso-matinee"),
+            concert(19, "https://www.gsmd.ac.uk/events/gso"),
+        ]);
+        let urls: Vec<&str> = concerts.iter().map(|c| c.concert.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://www.lso.co.uk/whats-on/gso-matinee",
+                "https://www.lso.co.uk/whats-on/gso",
+            ]
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use crate::core::Concert;
+use deunicode::deunicode;
+
+/// Fields of a concert which can be searched. Each field has a weight, so that e.g. a match in the
+/// title counts for more than a match in the description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Subtitle,
+    Composer,
+    Piece,
+    Performer,
+    Venue,
+    Series,
+    Description,
+    Programme,
+}
+
+impl Field {
+    fn weight(&self) -> f64 {
+        match self {
+            Field::Title => 5.0,
+            Field::Composer => 4.0,
+            Field::Performer => 4.0,
+            Field::Subtitle => 3.0,
+            Field::Piece => 3.0,
+            Field::Venue => 2.0,
+            Field::Series => 2.0,
+            Field::Description => 1.0,
+            // Programme notes mention lots of things in passing
+            Field::Programme => 0.5,
+        }
+    }
+
+    /// The prefix which can be used in queries to only search this field, e.g. `composer:bach`
+    fn from_prefix(prefix: &str) -> Option<Field> {
+        match prefix {
+            "title" => Some(Field::Title),
+            "subtitle" => Some(Field::Subtitle),
+            "composer" => Some(Field::Composer),
+            "piece" => Some(Field::Piece),
+            "performer" => Some(Field::Performer),
+            "venue" => Some(Field::Venue),
+            "series" => Some(Field::Series),
+            "description" => Some(Field::Description),
+            "programme" => Some(Field::Programme),
+            _ => None,
+        }
+    }
+}
+
+/// Split text into lowercase ASCII words, so that e.g. "Dvořák" and "dvorak" are the same
+pub fn tokenise(text: &str) -> Vec<String> {
+    deunicode(text)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// One term of a search query. The words are a phrase: they all have to be found in the same
+/// field, one after the other, for the term to match (if there is more than one word, it was given
+/// in quotes, e.g. `performer:"jean rondeau"`, or after a field prefix, e.g. `title:st-john`).
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    field: Option<Field>,
+    words: Vec<String>,
+}
+
+/// Parse a query like `schubert performer:"andras schiff" venue:wigmore` into terms
+fn parse_query(query: &str) -> Vec<Term> {
+    // First split on whitespace, but keep quoted sections together, remembering which terms had
+    // quotes in them
+    let mut raw_terms = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    for c in query.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    raw_terms.push((std::mem::take(&mut current), quoted));
+                }
+                quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        raw_terms.push((current, quoted));
+    }
+
+    raw_terms
+        .into_iter()
+        .flat_map(|(raw_term, quoted)| {
+            let (field, text) = match raw_term.split_once(':') {
+                Some((prefix, text)) => match Field::from_prefix(&prefix.to_lowercase()) {
+                    Some(field) => (Some(field), text.to_string()),
+                    None => (None, raw_term.clone()),
+                },
+                None => (None, raw_term.clone()),
+            };
+            let words = tokenise(&text);
+            match (field, words.is_empty()) {
+                (_, true) => vec![],
+                // Unless they're quoted, words without a field prefix (e.g. `foo:bar`, where `foo`
+                // isn't a field) don't have to be in the same field
+                (None, false) if !quoted => words
+                    .into_iter()
+                    .map(|word| Term {
+                        field: None,
+                        words: vec![word],
+                    })
+                    .collect(),
+                (field, false) => vec![Term { field, words }],
+            }
+        })
+        .collect()
+}
+
+/// The tokenised text of a single concert
+#[derive(Debug, Clone)]
+struct Document {
+    /// Each entry is one field value, e.g. one performer's name
+    fields: Vec<(Field, Vec<String>)>,
+}
+
+impl Document {
+    fn new(concert: &Concert) -> Document {
+        let c = &concert.concert;
+        let mut fields = vec![
+            (Field::Title, tokenise(&c.title)),
+            (Field::Venue, tokenise(&c.venue)),
+        ];
+        if let Some(subtitle) = &c.subtitle {
+            fields.push((Field::Subtitle, tokenise(subtitle)));
+        }
+        if let Some(description) = &c.description {
+            fields.push((Field::Description, tokenise(description)));
+        }
+        if let Some(programme_text) = &c.programme_text {
+            fields.push((Field::Programme, tokenise(programme_text)));
+        }
+        for piece in &c.pieces {
+            fields.push((Field::Composer, tokenise(&piece.composer)));
+            fields.push((Field::Piece, tokenise(&piece.title)));
+        }
+        for performer in &c.performers {
+            fields.push((Field::Performer, tokenise(&performer.name)));
+        }
+        for series in &c.series {
+            fields.push((Field::Series, tokenise(&series.name)));
+        }
+        Document { fields }
+    }
+
+    /// Score a term against this document. Returns `None` if the term does not match.
+    fn score(&self, term: &Term) -> Option<f64> {
+        self.fields
+            .iter()
+            .filter(|(field, _)| term.field.is_none_or(|f| f == *field))
+            .filter_map(|(field, tokens)| {
+                // The words have to match consecutive tokens in this field, wherever they are
+                // best matched
+                let score = tokens
+                    .windows(term.words.len())
+                    .filter_map(|window| phrase_score(&term.words, window))
+                    .reduce(f64::max)?;
+                Some(score * field.weight())
+            })
+            .reduce(f64::max)
+    }
+}
+
+/// Score a phrase against the same number of tokens, word by word. Exact matches are better than
+/// prefix matches. Returns `None` if any word doesn't match its token.
+fn phrase_score(words: &[String], tokens: &[String]) -> Option<f64> {
+    words
+        .iter()
+        .zip(tokens)
+        .map(|(word, token)| {
+            if token == word {
+                Some(1.0)
+            } else if token.starts_with(word.as_str()) {
+                Some(0.5)
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+/// Search index over a list of concerts
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    documents: Vec<Document>,
+}
+
+/// A concert matching a search, identified by its index in the list of concerts the search index
+/// was built from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchHit {
+    pub index: usize,
+    pub score: f64,
+}
+
+impl SearchIndex {
+    pub fn new(concerts: &[Concert]) -> SearchIndex {
+        SearchIndex {
+            documents: concerts.iter().map(Document::new).collect(),
+        }
+    }
+
+    /// Search for concerts. Every term of the query has to match; the results are sorted by
+    /// descending score, and then by their original order (which is chronological for concerts
+    /// read from `concerts.json`).
+    ///
+    /// Terms can be restricted to a field with a prefix, e.g. `composer:dvorak`, and words in
+    /// quotes are matched as a phrase, i.e. next to each other in that order, in the same field,
+    /// e.g. `performer:"mitsuko uchida"`. The available prefixes are
+    /// `title`, `subtitle`, `composer`, `piece`, `performer`, `venue`, `series`, `description`, and
+    /// `programme` (the text of the programme PDF, if programmes were downloaded).
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms = parse_query(query);
+        if terms.is_empty() {
+            return vec![];
+        }
+
+        let mut hits: Vec<SearchHit> = self
+            .documents
+            .iter()
+            .enumerate()
+            .filter_map(|(index, document)| {
+                let mut score = 0.0;
+                for term in &terms {
+                    score += document.score(term)?;
+                }
+                Some(SearchHit { index, score })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.index.cmp(&b.index)));
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ConcertData, Performer, Piece};
+
+    fn concert(title: &str, composer: &str, performer: &str) -> Concert {
+        Concert {
+            id: title.to_string(),
+            concert: ConcertData {
+                title: title.to_string(),
+                venue: "Wigmore Hall".to_string(),
+                pieces: vec![Piece {
+                    composer: composer.to_string(),
+                    title: "Piano Quintet".to_string(),
+                }],
+                performers: vec![Performer {
+                    name: performer.to_string(),
+                    instrument: None,
+                }],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn indices(index: &SearchIndex, query: &str) -> Vec<usize> {
+        index
+            .search(query)
+            .into_iter()
+            .map(|hit| hit.index)
+            .collect()
+    }
+
+    #[test]
+    fn queries_are_parsed_into_terms() {
+        assert_eq!(
+            parse_query(r#"Dvořák performer:"Takács Quartet" foo:bar"#),
+            vec![
+                Term {
+                    field: None,
+                    words: vec!["dvorak".to_string()],
+                },
+                Term {
+                    field: Some(Field::Performer),
+                    words: vec!["takacs".to_string(), "quartet".to_string()],
+                },
+                Term {
+                    field: None,
+                    words: vec!["foo".to_string()],
+                },
+                Term {
+                    field: None,
+                    words: vec!["bar".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            parse_query(r#""Jean Rondeau""#),
+            vec![Term {
+                field: None,
+                words: vec!["jean".to_string(), "rondeau".to_string()],
+            }]
+        );
+        assert!(parse_query("  \"\" ").is_empty());
+    }
+
+    #[test]
+    fn results_are_ranked_by_field() {
+        let concerts = vec![
+            concert("Takács Quartet", "Schumann", "Takács Quartet"),
+            concert("Dvořák and Brahms", "Brahms", "Pavel Haas Quartet"),
+            concert("Piano quintets", "Dvořák", "Takács Quartet"),
+        ];
+        let index = SearchIndex::new(&concerts);
+        // A match in the title counts for more than one in a composer's name
+        assert_eq!(indices(&index, "dvorak"), vec![1, 2]);
+        assert_eq!(indices(&index, "composer:dvorak"), vec![2]);
+        // Every term has to match
+        assert_eq!(indices(&index, "takacs dvorak"), vec![2]);
+        // Exact matches come before prefix matches, and ties keep their original order
+        assert_eq!(indices(&index, "quartet"), vec![0, 1, 2]);
+        assert_eq!(indices(&index, "quint"), vec![2, 0, 1]);
+        assert_eq!(indices(&index, r#"performer:"haas quartet""#), vec![1]);
+        assert!(indices(&index, r#"performer:"quartet haas takacs""#).is_empty());
+    }
+
+    #[test]
+    fn quoted_words_are_matched_as_a_phrase() {
+        let concerts = vec![
+            concert("Haas and Janáček", "Haas", "Jerusalem Quartet"),
+            concert("Dvořák and Brahms", "Brahms", "Pavel Haas Quartet"),
+        ];
+        let index = SearchIndex::new(&concerts);
+        assert_eq!(indices(&index, "haas quartet"), vec![0, 1]);
+        assert_eq!(indices(&index, r#""haas quartet""#), vec![1]);
+        assert_eq!(indices(&index, r#""haas quart""#), vec![1]);
+        // The words have to be in the right order
+        assert!(indices(&index, r#"performer:"quartet haas""#).is_empty());
+        // and in the same field
+        assert!(indices(&index, r#""janacek jerusalem""#).is_empty());
+        assert!(indices(&index, "").is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::core::Concert;
+use crate::query::ConcertQuery;
+use crate::search::SearchIndex;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use log::info;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+struct AppState {
+    concerts: Vec<Concert>,
+    index: SearchIndex,
+}
+
+type SharedState = Arc<AppState>;
+
+/// Number of concerts associated with a venue or composer
+#[derive(Debug, Serialize)]
+struct NameCount<'a> {
+    name: &'a str,
+    n_concerts: usize,
+}
+
+/// Build the router for the JSON API. The endpoints are:
+///
+/// - `GET /concerts`: list concerts, filtered and paginated using the query parameters in
+///   `ConcertQuery`
+/// - `GET /concerts/:id`: a single concert
+/// - `GET /venues`: all venues, with the number of concerts at each
+/// - `GET /composers`: all composers, with the number of concerts featuring each
+pub fn router(concerts: Vec<Concert>) -> Router {
+    Router::new()
+        .route("/concerts", get(list_concerts))
+        .route("/concerts/:id", get(get_concert))
+        .route("/venues", get(list_venues))
+        .route("/composers", get(list_composers))
+        .with_state(Arc::new(AppState {
+            index: SearchIndex::new(&concerts),
+            concerts,
+        }))
+}
+
+/// Serve the JSON API on the given address until the process is killed
+pub async fn serve(concerts: Vec<Concert>, addr: SocketAddr) {
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    info!("Serving {} concerts on http://{}", concerts.len(), addr);
+    axum::serve(listener, router(concerts)).await.unwrap();
+}
+
+async fn list_concerts(
+    State(state): State<SharedState>,
+    Query(query): Query<ConcertQuery>,
+) -> Response {
+    Json(query.apply(&state.concerts, &state.index)).into_response()
+}
+
+async fn get_concert(State(state): State<SharedState>, Path(id): Path<String>) -> Response {
+    match state.concerts.iter().find(|c| c.id == id) {
+        Some(concert) => Json(concert).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("No concert with ID {}", id)).into_response(),
+    }
+}
+
+/// Sort names alphabetically and count how many concerts each appears in
+fn count_names<'a>(names_per_concert: impl Iterator<Item = Vec<&'a str>>) -> Vec<NameCount<'a>> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for mut names in names_per_concert {
+        // Each concert should only count once, even if e.g. it has several pieces by the same
+        // composer
+        names.sort();
+        names.dedup();
+        for name in names {
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(name, n_concerts)| NameCount { name, n_concerts })
+        .collect()
+}
+
+async fn list_venues(State(state): State<SharedState>) -> Response {
+    let venues = count_names(
+        state
+            .concerts
+            .iter()
+            .map(|c| vec![c.concert.venue.as_str()]),
+    );
+    Json(venues).into_response()
+}
+
+async fn list_composers(State(state): State<SharedState>) -> Response {
+    let composers = count_names(state.concerts.iter().map(|c| {
+        c.concert
+            .pieces
+            .iter()
+            .map(|p| p.composer.as_str())
+            .filter(|composer| !composer.is_empty())
+            .collect()
+    }));
+    Json(composers).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{add_id_to_concert, ConcertData, Piece};
+    use chrono::{TimeZone, Utc};
+    use serde_json::{json, Value};
+
+    fn concerts() -> Vec<Concert> {
+        let piece = |composer: &str| Piece {
+            composer: composer.to_string(),
+            title: "Sonata".to_string(),
+        };
+        vec![
+            add_id_to_concert(ConcertData {
+                datetime: Utc.with_ymd_and_hms(2026, 3, 1, 19, 30, 0).unwrap(),
+                url: "https://wigmore-hall.org.uk/a".to_string(),
+                title: "Schubert and Brahms".to_string(),
+                venue: "Wigmore Hall".to_string(),
+                pieces: vec![piece("Schubert"), piece("Schubert"), piece("Brahms")],
+                ..Default::default()
+            }),
+            add_id_to_concert(ConcertData {
+                datetime: Utc.with_ymd_and_hms(2026, 3, 2, 19, 30, 0).unwrap(),
+                url: "https://kingsplace.co.uk/b".to_string(),
+                title: "Late Schubert".to_string(),
+                venue: "Kings Place".to_string(),
+                pieces: vec![piece("Schubert")],
+                ..Default::default()
+            }),
+        ]
+    }
+
+    /// Serve the API on a free port and return its base URL
+    async fn start() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(concerts())).await });
+        format!("http://{}", addr)
+    }
+
+    async fn get(url: String) -> (StatusCode, String) {
+        let response = reqwest::get(url).await.unwrap();
+        (response.status(), response.text().await.unwrap())
+    }
+
+    #[test]
+    fn names_are_counted_once_per_concert() {
+        let counts = count_names(vec![vec!["B", "A", "B"], vec!["B"]].into_iter());
+        assert_eq!(
+            serde_json::to_value(counts).unwrap(),
+            json!([{"name": "A", "n_concerts": 1}, {"name": "B", "n_concerts": 2}])
+        );
+    }
+
+    #[tokio::test]
+    async fn endpoints() {
+        let base = start().await;
+        let ids: Vec<String> = concerts().into_iter().map(|c| c.id).collect();
+
+        let (status, body) = get(format!("{}/concerts?venue=kings&per_page=10", base)).await;
+        assert_eq!(status, StatusCode::OK);
+        let page: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            (page["total"].clone(), page["per_page"].clone()),
+            (json!(1), json!(10))
+        );
+        assert_eq!(page["concerts"][0]["id"], json!(ids[1]));
+
+        let (status, body) = get(format!("{}/concerts/{}", base, ids[0])).await;
+        assert_eq!(status, StatusCode::OK);
+        let concert: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(concert["title"], json!("Schubert and Brahms"));
+
+        let (status, _) = get(format!("{}/concerts/nonexistent", base)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, body) = get(format!("{}/composers", base)).await;
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            json!([
+                {"name": "Brahms", "n_concerts": 1},
+                {"name": "Schubert", "n_concerts": 2}
+            ])
+        );
+
+        let (status, _) = get(format!("{}/concerts?per_page=lots", base)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
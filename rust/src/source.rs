@@ -0,0 +1,188 @@
+use crate::core::Category;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// The websites that concerts are scraped from. Each source may list concerts at several venues
+/// (e.g. the Southbank Centre has the Royal Festival Hall, Queen Elizabeth Hall, and Purcell
+/// Room).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, TS, JsonSchema,
+)]
+#[ts(export)]
+pub enum Source {
+    Wigmore,
+    Proms,
+    Southbank,
+    KingsPlace,
+    LsoStLukes,
+    RoyalOperaHouse,
+    Eno,
+    Ram,
+    Rcm,
+    Guildhall,
+    SmithSquare,
+    SouthwarkCathedral,
+    StPauls,
+    WestminsterAbbey,
+    /// Scraped using only its JSON-LD, see `schema_org::Site`
+    CadoganHall,
+    /// Scraped using only its JSON-LD, see `schema_org::Site`
+    StMartinInTheFields,
+}
+
+impl Source {
+    pub const ALL: [Source; 16] = [
+        Source::Wigmore,
+        Source::Proms,
+        Source::Southbank,
+        Source::KingsPlace,
+        Source::LsoStLukes,
+        Source::RoyalOperaHouse,
+        Source::Eno,
+        Source::Ram,
+        Source::Rcm,
+        Source::Guildhall,
+        Source::SmithSquare,
+        Source::SouthwarkCathedral,
+        Source::StPauls,
+        Source::WestminsterAbbey,
+        Source::CadoganHall,
+        Source::StMartinInTheFields,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::Wigmore => "Wigmore Hall",
+            Source::Proms => "BBC Proms",
+            Source::Southbank => "Southbank Centre",
+            Source::KingsPlace => "Kings Place",
+            Source::LsoStLukes => "LSO St Luke's",
+            Source::RoyalOperaHouse => "Royal Opera House",
+            Source::Eno => "English National Opera",
+            Source::Ram => "Royal Academy of Music",
+            Source::Rcm => "Royal College of Music",
+            Source::Guildhall => "Guildhall School",
+            Source::SmithSquare => "St John's Smith Square",
+            Source::SouthwarkCathedral => "Southwark Cathedral",
+            Source::StPauls => "St Paul's Cathedral",
+            Source::WestminsterAbbey => "Westminster Abbey",
+            Source::CadoganHall => "Cadogan Hall",
+            Source::StMartinInTheFields => "St Martin-in-the-Fields",
+        }
+    }
+
+    /// Environment variable which, if set to a non-empty value, disables scraping this source
+    pub fn disable_envvar(&self) -> &'static str {
+        match self {
+            Source::Wigmore => "LDNCLS_WIGMORE_DISABLE",
+            Source::Proms => "LDNCLS_PROMS_DISABLE",
+            Source::Southbank => "LDNCLS_SOUTHBANK_DISABLE",
+            Source::KingsPlace => "LDNCLS_KINGSPLACE_DISABLE",
+            Source::LsoStLukes => "LDNCLS_LSO_DISABLE",
+            Source::RoyalOperaHouse => "LDNCLS_ROH_DISABLE",
+            Source::Eno => "LDNCLS_ENO_DISABLE",
+            Source::Ram => "LDNCLS_RAM_DISABLE",
+            Source::Rcm => "LDNCLS_RCM_DISABLE",
+            Source::Guildhall => "LDNCLS_GUILDHALL_DISABLE",
+            Source::SmithSquare => "LDNCLS_SJSS_DISABLE",
+            Source::SouthwarkCathedral => "LDNCLS_SOUTHWARK_DISABLE",
+            Source::StPauls => "LDNCLS_STPAULS_DISABLE",
+            Source::WestminsterAbbey => "LDNCLS_ABBEY_DISABLE",
+            Source::CadoganHall => "LDNCLS_CADOGAN_DISABLE",
+            Source::StMartinInTheFields => "LDNCLS_STMARTIN_DISABLE",
+        }
+    }
+
+    /// Categories of events which are left out unless configured otherwise. The Southbank Centre
+    /// lists lots of light music (e.g. Christmas Classics) under classical music, see
+    /// https://github.com/penelopeysm/london_classical/issues/3. Churches list their services (e.g.
+    /// daily evensong) together with their concerts, so those are left out too.
+    pub fn default_excluded_categories(&self) -> &'static [Category] {
+        match self {
+            Source::Wigmore => &[],
+            Source::Proms => &[],
+            Source::Southbank => &[Category::Light],
+            Source::KingsPlace => &[],
+            Source::LsoStLukes => &[],
+            Source::RoyalOperaHouse => &[],
+            Source::Eno => &[],
+            Source::Ram => &[],
+            Source::Rcm => &[],
+            Source::Guildhall => &[],
+            Source::SmithSquare => &[],
+            Source::SouthwarkCathedral => &[Category::Service],
+            Source::StPauls => &[Category::Service],
+            Source::WestminsterAbbey => &[Category::Service],
+            Source::CadoganHall => &[],
+            Source::StMartinInTheFields => &[],
+        }
+    }
+
+    /// Hostnames that concert URLs from this source can have
+    pub fn hosts(&self) -> &'static [&'static str] {
+        match self {
+            Source::Wigmore => &["wigmore-hall.org.uk", "www.wigmore-hall.org.uk"],
+            Source::Proms => &["bbc.co.uk", "www.bbc.co.uk"],
+            Source::Southbank => &["southbankcentre.co.uk", "www.southbankcentre.co.uk"],
+            Source::KingsPlace => &["kingsplace.co.uk", "www.kingsplace.co.uk"],
+            Source::LsoStLukes => &["lso.co.uk", "www.lso.co.uk"],
+            Source::RoyalOperaHouse => &[
+                "rbo.org.uk",
+                "www.rbo.org.uk",
+                "roh.org.uk",
+                "www.roh.org.uk",
+            ],
+            Source::Eno => &["eno.org", "www.eno.org"],
+            Source::Ram => &["ram.ac.uk", "www.ram.ac.uk"],
+            Source::Rcm => &["rcm.ac.uk", "www.rcm.ac.uk"],
+            Source::Guildhall => &["gsmd.ac.uk", "www.gsmd.ac.uk"],
+            Source::SmithSquare => &["sjss.org.uk", "www.sjss.org.uk"],
+            Source::SouthwarkCathedral => &["cathedral.southwark.anglican.org"],
+            Source::StPauls => &["stpauls.co.uk", "www.stpauls.co.uk"],
+            Source::WestminsterAbbey => &["westminster-abbey.org", "www.westminster-abbey.org"],
+            Source::CadoganHall => &["cadoganhall.com", "www.cadoganhall.com"],
+            Source::StMartinInTheFields => &[
+                "stmartin-in-the-fields.org",
+                "www.stmartin-in-the-fields.org",
+            ],
+        }
+    }
+
+    /// Venues that concerts from this source can be at. `None` means that the source lists
+    /// concerts at arbitrary venues (the Proms go on tour around the country).
+    pub fn venues(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Source::Wigmore => Some(&["Wigmore Hall"]),
+            Source::Proms => None,
+            Source::Southbank => Some(&[
+                "Royal Festival Hall",
+                "Queen Elizabeth Hall",
+                "Purcell Room",
+            ]),
+            Source::KingsPlace => Some(&["Kings Place"]),
+            Source::LsoStLukes => Some(&["LSO St Luke's"]),
+            Source::RoyalOperaHouse => Some(&["Royal Opera House"]),
+            Source::Eno => Some(&["London Coliseum"]),
+            Source::Ram => Some(&["Royal Academy of Music"]),
+            Source::Rcm => Some(&["Royal College of Music"]),
+            // Mostly at Milton Court and Silk Street, but students perform all over the City
+            Source::Guildhall => None,
+            Source::SmithSquare => Some(&["St John's Smith Square"]),
+            Source::SouthwarkCathedral => Some(&["Southwark Cathedral"]),
+            Source::StPauls => Some(&["St Paul's Cathedral"]),
+            Source::WestminsterAbbey => Some(&["Westminster Abbey"]),
+            Source::CadoganHall => Some(&["Cadogan Hall"]),
+            Source::StMartinInTheFields => Some(&["St Martin-in-the-Fields"]),
+        }
+    }
+
+    /// Determine which source a concert URL came from
+    pub fn from_url(url: &str) -> Option<Source> {
+        let parsed_url = reqwest::Url::parse(url).ok()?;
+        let host = parsed_url.host_str()?;
+        Source::ALL
+            .into_iter()
+            .find(|source| source.hosts().contains(&host))
+    }
+}
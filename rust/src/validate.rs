@@ -0,0 +1,359 @@
+use crate::core::{Availability, Concert};
+use crate::source::Source;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use chrono_tz::Europe::London;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// If a source has at least this many concerts in the previous run...
+const MIN_COUNT_FOR_DROP_CHECK: usize = 10;
+/// ...then it is an error if the number of concerts drops by more than this fraction
+const MAX_DROP_FRACTION: f64 = 0.5;
+/// It is an error if more than this fraction of a source's concerts are at midnight, since that
+/// is what the scrapers fall back to when they can't find a time
+const MAX_MIDNIGHT_FRACTION: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The concert which the issue is about, if any (some issues are about whole sources)
+    pub concert_id: Option<String>,
+    pub message: String,
+}
+
+impl Issue {
+    fn error(concert_id: Option<&str>, message: String) -> Issue {
+        Issue {
+            severity: Severity::Error,
+            concert_id: concert_id.map(|s| s.to_string()),
+            message,
+        }
+    }
+
+    fn warning(concert_id: Option<&str>, message: String) -> Issue {
+        Issue {
+            severity: Severity::Warning,
+            concert_id: concert_id.map(|s| s.to_string()),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match &self.concert_id {
+            Some(id) => write!(f, "{}: [{}] {}", severity, id, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Check a single concert for problems
+pub fn validate_concert(concert: &Concert, now: DateTime<Utc>) -> Vec<Issue> {
+    let c = &concert.concert;
+    let id = Some(concert.id.as_str());
+    let mut issues = vec![];
+
+    if c.title.trim().is_empty() {
+        issues.push(Issue::error(id, "Title is empty".to_string()));
+    }
+
+    // The Proms scraper only filters out past dates, not past times, so anything earlier today is
+    // still fine
+    let start_of_today = now
+        .with_timezone(&London)
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    if c.datetime.with_timezone(&London).naive_local() < start_of_today {
+        issues.push(Issue::error(
+            id,
+            format!("Concert is in the past ({})", c.datetime),
+        ));
+    }
+    if c.datetime > now + Duration::days(3 * 365) {
+        issues.push(Issue::warning(
+            id,
+            format!("Concert is implausibly far in the future ({})", c.datetime),
+        ));
+    }
+
+    if let (Some(min_price), Some(max_price)) = (c.min_price, c.max_price) {
+        if min_price > max_price {
+            issues.push(Issue::error(
+                id,
+                format!(
+                    "Minimum price ({}) is greater than maximum price ({})",
+                    min_price, max_price
+                ),
+            ));
+        }
+    }
+
+    if c.availability != Availability::Ticketed && (c.min_price, c.max_price) != (Some(0), Some(0))
+    {
+        issues.push(Issue::error(
+            id,
+            format!(
+                "Concert is free ({}) but has prices {:?}-{:?}",
+                c.availability.name(),
+                c.min_price,
+                c.max_price
+            ),
+        ));
+    }
+
+    match Source::from_url(&c.url) {
+        None => issues.push(Issue::error(
+            id,
+            format!("URL {} does not belong to any known source", c.url),
+        )),
+        Some(source) => {
+            let venue_is_known = match source.venues() {
+                None => !c.venue.trim().is_empty(),
+                Some(venues) => venues.iter().any(|v| c.venue.contains(v)),
+            };
+            if !venue_is_known {
+                issues.push(Issue::error(
+                    id,
+                    format!("Unknown venue '{}' for {}", c.venue, source.name()),
+                ));
+            }
+        }
+    }
+
+    for piece in &c.pieces {
+        if piece.composer.trim().is_empty() {
+            issues.push(Issue::warning(
+                id,
+                format!("Piece '{}' has no composer", piece.title),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Group concerts by source
+fn by_source(concerts: &[Concert]) -> BTreeMap<Option<Source>, Vec<&Concert>> {
+    let mut groups: BTreeMap<Option<Source>, Vec<&Concert>> = BTreeMap::new();
+    for concert in concerts {
+        groups
+            .entry(Source::from_url(&concert.concert.url))
+            .or_default()
+            .push(concert);
+    }
+    groups
+}
+
+/// Count the number of concerts at each venue of each source. A source which covers several
+/// venues (e.g. Southbank) can lose one of them without its total dropping by much.
+fn count_by_venue(concerts: &[Concert]) -> BTreeMap<(Source, &str), usize> {
+    let mut counts = BTreeMap::new();
+    for concert in concerts {
+        if let Some(source) = Source::from_url(&concert.concert.url) {
+            *counts
+                .entry((source, concert.concert.venue.as_str()))
+                .or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Check for problems which affect all concerts from a source, i.e. a sharp drop in the number of
+/// concerts at one of its venues compared to the previous run, or most concerts being at
+/// midnight. Sources which aren't in `enabled_sources` weren't scraped, so they are skipped.
+pub fn validate_sources(
+    current: &[Concert],
+    previous: Option<&[Concert]>,
+    enabled_sources: &[Source],
+) -> Vec<Issue> {
+    let mut issues = vec![];
+
+    for (source, concerts) in by_source(current) {
+        let Some(source) = source else { continue };
+        if !enabled_sources.contains(&source) {
+            continue;
+        }
+        let n_midnight = concerts
+            .iter()
+            .filter(|c| {
+                let london_datetime = c.concert.datetime.with_timezone(&London);
+                london_datetime.hour() == 0 && london_datetime.minute() == 0
+            })
+            .count();
+        if n_midnight as f64 > MAX_MIDNIGHT_FRACTION * concerts.len() as f64 {
+            issues.push(Issue::error(
+                None,
+                format!(
+                    "{} of {} concerts from {} are at midnight",
+                    n_midnight,
+                    concerts.len(),
+                    source.name()
+                ),
+            ));
+        }
+    }
+
+    if let Some(previous) = previous {
+        let current_by_venue = count_by_venue(current);
+        for ((source, venue), n_previous) in count_by_venue(previous) {
+            if !enabled_sources.contains(&source) {
+                continue;
+            }
+            let n_current = current_by_venue.get(&(source, venue)).copied().unwrap_or(0);
+            if n_previous >= MIN_COUNT_FOR_DROP_CHECK
+                && (n_current as f64) < (1.0 - MAX_DROP_FRACTION) * n_previous as f64
+            {
+                issues.push(Issue::error(
+                    None,
+                    format!(
+                        "Number of concerts from {} at {} dropped from {} to {}",
+                        source.name(),
+                        venue,
+                        n_previous,
+                        n_current
+                    ),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Run all checks on a set of concerts. If `previous` is given, it is used to check that the
+/// number of concerts at each venue hasn't dropped sharply. Only sources in `enabled_sources` are
+/// compared against the previous run.
+pub fn validate(
+    current: &[Concert],
+    previous: Option<&[Concert]>,
+    enabled_sources: &[Source],
+    now: DateTime<Utc>,
+) -> Vec<Issue> {
+    let mut issues = validate_sources(current, previous, enabled_sources);
+    for concert in current {
+        issues.extend(validate_concert(concert, now));
+    }
+    issues
+}
+
+/// Whether any of the issues are serious enough to block publishing
+pub fn has_errors(issues: &[Issue]) -> bool {
+    issues.iter().any(|issue| issue.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{add_id_to_concert, ConcertData};
+    use chrono::TimeZone;
+
+    fn concerts(host: &str, venue: &str, n: u32, hour: u32) -> Vec<Concert> {
+        (1..=n)
+            .map(|day| {
+                add_id_to_concert(ConcertData {
+                    datetime: Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap(),
+                    url: format!("https://{}/events/{}", host, day),
+                    title: "Recital".to_string(),
+                    venue: venue.to_string(),
+                    min_price: Some(1000),
+                    max_price: Some(2000),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn southbank(n_rfh: u32, n_qeh: u32) -> Vec<Concert> {
+        let mut cs = concerts(
+            "www.southbankcentre.co.uk",
+            "Royal Festival Hall",
+            n_rfh,
+            19,
+        );
+        cs.extend(concerts(
+            "www.southbankcentre.co.uk",
+            "Queen Elizabeth Hall",
+            n_qeh,
+            19,
+        ));
+        cs
+    }
+
+    #[test]
+    fn drop_at_one_venue_is_an_error() {
+        let issues = validate_sources(&southbank(20, 2), Some(&southbank(20, 12)), &Source::ALL);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Number of concerts from Southbank Centre at Queen Elizabeth Hall dropped from 12 to 2"
+        );
+    }
+
+    #[test]
+    fn small_drops_are_fine() {
+        let issues = validate_sources(&southbank(15, 5), Some(&southbank(20, 8)), &Source::ALL);
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn disabled_sources_are_skipped() {
+        let enabled: Vec<_> = Source::ALL
+            .into_iter()
+            .filter(|&s| s != Source::Southbank)
+            .collect();
+        assert!(validate_sources(&[], Some(&southbank(20, 20)), &enabled).is_empty());
+        assert_eq!(
+            validate_sources(&[], Some(&southbank(20, 20)), &Source::ALL).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn mostly_midnight_is_an_error() {
+        let current = concerts("www.wigmore-hall.org.uk", "Wigmore Hall", 4, 0);
+        let issues = validate_sources(&current, None, &Source::ALL);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(
+            issues[0].message,
+            "4 of 4 concerts from Wigmore Hall are at midnight"
+        );
+    }
+
+    #[test]
+    fn concert_checks() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let mut concert = concerts("www.wigmore-hall.org.uk", "Wigmore Hall", 1, 19).remove(0);
+        assert!(validate_concert(&concert, now).is_empty());
+
+        concert.concert.min_price = Some(5000);
+        concert.concert.venue = "Elsewhere".to_string();
+        let messages: Vec<_> = validate_concert(&concert, now)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Minimum price (5000) is greater than maximum price (2000)",
+                "Unknown venue 'Elsewhere' for Wigmore Hall",
+            ]
+        );
+
+        let later = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        assert_eq!(validate_concert(&concert, later).len(), 3);
+    }
+}
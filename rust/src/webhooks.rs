@@ -0,0 +1,349 @@
+use crate::core::Concert;
+use crate::diff::ConcertsDiff;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::path::Path;
+use std::time::Duration;
+
+/// Maximum number of times delivery of an event is attempted
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Header containing the HMAC-SHA256 signature of the request body, as `sha256=<hex digest>`
+pub const SIGNATURE_HEADER: &str = "X-London-Classical-Signature";
+/// Header containing the event type, e.g. `concert.added`
+pub const EVENT_HEADER: &str = "X-London-Classical-Event";
+
+/// Configuration for webhooks, read from a TOML file. See `webhooks.example.toml` for an example.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhooksConfig {
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    /// If given, each request is signed with this secret
+    pub secret: Option<String>,
+    /// Events to send to this webhook. If empty, all events are sent.
+    #[serde(default)]
+    pub events: Vec<EventKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventKind {
+    #[serde(rename = "concert.added")]
+    ConcertAdded,
+    #[serde(rename = "concert.changed")]
+    ConcertChanged,
+    #[serde(rename = "concert.removed")]
+    ConcertRemoved,
+    #[serde(rename = "run.failed")]
+    RunFailed,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::ConcertAdded => "concert.added",
+            EventKind::ConcertChanged => "concert.changed",
+            EventKind::ConcertRemoved => "concert.removed",
+            EventKind::RunFailed => "run.failed",
+        }
+    }
+}
+
+/// A single event, which is the JSON body of a webhook request
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+    pub sent_at: DateTime<Utc>,
+    pub data: Value,
+}
+
+impl WebhooksConfig {
+    pub fn read(path: &Path) -> WebhooksConfig {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        toml::from_str(&text)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e))
+    }
+}
+
+/// Turn the differences between two runs into events. `current` is the list of concerts the diff
+/// was computed against, and is used to include the full details of changed concerts. Concerts
+/// which were removed but are before `now` have dropped out of the listings because they have
+/// happened, not because they were cancelled, so they don't get `concert.removed` events.
+pub fn events_from_diff(
+    concerts_diff: &ConcertsDiff,
+    current: &[Concert],
+    now: DateTime<Utc>,
+) -> Vec<Event> {
+    let mut events = vec![];
+    for concert in &concerts_diff.added {
+        events.push(Event {
+            event: EventKind::ConcertAdded,
+            sent_at: now,
+            data: json!({ "concert": concert }),
+        });
+    }
+    for change in &concerts_diff.changed {
+        let concert = current.iter().find(|c| c.id == change.new_id);
+        events.push(Event {
+            event: EventKind::ConcertChanged,
+            sent_at: now,
+            data: json!({
+                "old_id": change.old_id,
+                "concert": concert,
+                "changes": change.changes,
+            }),
+        });
+    }
+    for concert in concerts_diff
+        .removed
+        .iter()
+        .filter(|c| c.concert.datetime >= now)
+    {
+        events.push(Event {
+            event: EventKind::ConcertRemoved,
+            sent_at: now,
+            data: json!({ "concert": concert }),
+        });
+    }
+    events
+}
+
+pub fn run_failed_event(message: &str, now: DateTime<Utc>) -> Event {
+    Event {
+        event: EventKind::RunFailed,
+        sent_at: now,
+        data: json!({ "error": message }),
+    }
+}
+
+/// Compute the signature of a request body, in the form `sha256=<hex digest>`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex_digest: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex_digest)
+}
+
+/// POST a single event to a webhook, retrying on failure. Returns whether delivery succeeded.
+async fn deliver_one(client: &reqwest::Client, webhook: &Webhook, event: &Event) -> bool {
+    let body = serde_json::to_vec(event).unwrap();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event.event.name())
+            .body(body.clone());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => return true,
+            Err(e) => {
+                warn!(
+                    "Failed to deliver {} event to {} (attempt {} of {}): {}",
+                    event.event.name(),
+                    webhook.url,
+                    attempt,
+                    MAX_ATTEMPTS,
+                    e
+                );
+                if attempt < MAX_ATTEMPTS {
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Send the events which a webhook is subscribed to, in order. If one of them can't be delivered
+/// even after retrying, the endpoint is taken to be down and the rest aren't sent, since each of
+/// them would only fail slowly too.
+async fn deliver_to(client: &reqwest::Client, webhook: &Webhook, events: &[Event]) {
+    let subscribed: Vec<&Event> = events
+        .iter()
+        .filter(|event| webhook.events.is_empty() || webhook.events.contains(&event.event))
+        .collect();
+    let mut n_sent = 0;
+    for event in &subscribed {
+        if !deliver_one(client, webhook, event).await {
+            warn!(
+                "Giving up on webhook {}: {} events not delivered",
+                webhook.url,
+                subscribed.len() - n_sent
+            );
+            return;
+        }
+        n_sent += 1;
+    }
+    info!("Webhook {}: {} events delivered", webhook.url, n_sent);
+}
+
+/// Send events to all configured webhooks which are subscribed to them. Each webhook is sent to at
+/// the same time, so that one which is down doesn't hold up the others. Failures are logged but
+/// otherwise ignored.
+pub async fn deliver(config: &WebhooksConfig, events: &[Event]) {
+    let client = reqwest::Client::builder()
+        .user_agent("penelopeysm/london-classical/0.1")
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap();
+    join_all(
+        config
+            .webhooks
+            .iter()
+            .map(|webhook| deliver_to(&client, webhook, events)),
+    )
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{add_id_to_concert, ConcertData};
+    use crate::diff::diff;
+    use axum::body::Bytes;
+    use axum::http::StatusCode;
+    use chrono::TimeZone;
+    use std::sync::{Arc, Mutex};
+
+    fn concert(title: &str, day: u32) -> Concert {
+        add_id_to_concert(ConcertData {
+            datetime: Utc.with_ymd_and_hms(2026, 3, day, 19, 30, 0).unwrap(),
+            url: format!("https://example.com/{}", title.to_lowercase()),
+            title: title.to_string(),
+            venue: "Wigmore Hall".to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn kinds(events: &[Event]) -> Vec<EventKind> {
+        events.iter().map(|event| event.event).collect()
+    }
+
+    #[test]
+    fn events_for_each_difference() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+        let old = vec![concert("Schubert", 10), concert("Brahms", 11)];
+        let new = vec![concert("Schubert", 12), concert("Ravel", 13)];
+        let events = events_from_diff(&diff(&old, &new), &new, now);
+        assert_eq!(
+            kinds(&events),
+            vec![
+                EventKind::ConcertAdded,
+                EventKind::ConcertChanged,
+                EventKind::ConcertRemoved
+            ]
+        );
+        assert_eq!(events[1].data["old_id"], json!(old[0].id));
+        assert_eq!(events[1].data["concert"]["id"], json!(new[0].id));
+        assert_eq!(events[2].data["concert"]["title"], json!("Brahms"));
+    }
+
+    #[test]
+    fn concerts_which_have_happened_are_not_removed() {
+        // The day after the Brahms concert, it's no longer listed
+        let now = Utc.with_ymd_and_hms(2026, 3, 12, 6, 0, 0).unwrap();
+        let old = vec![concert("Brahms", 11), concert("Ravel", 13)];
+        let new = vec![concert("Ravel", 13)];
+        assert!(events_from_diff(&diff(&old, &new), &new, now).is_empty());
+    }
+
+    /// A request received by `start_receiver`
+    struct Received {
+        path: String,
+        headers: axum::http::HeaderMap,
+        body: Vec<u8>,
+    }
+
+    /// Receive webhooks on a free port, recording every request. `/down` always fails, and
+    /// `/flaky` fails the first time only. Returns the base URL.
+    async fn start_receiver() -> (String, Arc<Mutex<Vec<Received>>>) {
+        let received: Arc<Mutex<Vec<Received>>> = Arc::default();
+        let record = received.clone();
+        let handler = move |uri: axum::http::Uri, headers: axum::http::HeaderMap, body: Bytes| async move {
+            let mut received = record.lock().unwrap();
+            let path = uri.path().to_string();
+            let n_before = received.iter().filter(|r| r.path == path).count();
+            let status = match path.as_str() {
+                "/down" => StatusCode::INTERNAL_SERVER_ERROR,
+                "/flaky" if n_before == 0 => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::OK,
+            };
+            received.push(Received {
+                path,
+                headers,
+                body: body.to_vec(),
+            });
+            status
+        };
+        let router = axum::Router::new().fallback(axum::routing::post(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (format!("http://{}", addr), received)
+    }
+
+    #[tokio::test]
+    async fn delivery() {
+        let (base, received) = start_receiver().await;
+        let webhook = |path: &str, secret: Option<&str>| Webhook {
+            url: format!("{}{}", base, path),
+            secret: secret.map(|s| s.to_string()),
+            events: vec![],
+        };
+        let config = WebhooksConfig {
+            webhooks: vec![webhook("/flaky", Some("change-me")), webhook("/down", None)],
+        };
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+        let events: Vec<Event> = ["Schubert", "Brahms", "Ravel"]
+            .iter()
+            .map(|title| Event {
+                event: EventKind::ConcertAdded,
+                sent_at: now,
+                data: json!({ "concert": concert(title, 10) }),
+            })
+            .collect();
+        deliver(&config, &events).await;
+
+        let received = received.lock().unwrap();
+        // The first event is retried, and then all of them are delivered in order
+        let flaky: Vec<&Received> = received.iter().filter(|r| r.path == "/flaky").collect();
+        assert_eq!(flaky.len(), 4);
+        assert_eq!(flaky[0].body, flaky[1].body);
+        for (request, event) in flaky[1..].iter().zip(&events) {
+            assert_eq!(request.body, serde_json::to_vec(event).unwrap());
+            assert_eq!(request.headers[EVENT_HEADER], "concert.added");
+            assert_eq!(
+                request.headers[SIGNATURE_HEADER],
+                sign("change-me", &request.body).as_str()
+            );
+        }
+        // The endpoint which is down is given up on after the first event
+        let down: Vec<&Received> = received.iter().filter(|r| r.path == "/down").collect();
+        assert_eq!(down.len(), MAX_ATTEMPTS as usize);
+        assert!(down
+            .iter()
+            .all(|r| !r.headers.contains_key(SIGNATURE_HEADER)));
+    }
+
+    #[test]
+    fn signatures() {
+        // Test vector from RFC 4231
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -1,5 +1,8 @@
+use crate::classify;
 use crate::core;
-use chrono::{DateTime, Utc};
+use crate::http;
+use crate::media;
+use chrono::{DateTime, Duration, Utc};
 use futures::future::join_all;
 use futures::stream::{self, StreamExt};
 use html_escape::decode_html_entities;
@@ -7,30 +10,171 @@ use log::{info, warn};
 use regex::Regex;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
 
-/// Fetch full data for all Wigmore concerts, up to a maximum of `max` if specified
-pub async fn get_concerts(client: &reqwest::Client, max: Option<usize>) -> Vec<core::ConcertData> {
+/// Version of the cache format. Bump this whenever `parse_concert_json` changes, so that concerts
+/// which were cached with the old parser are scraped again.
+const CACHE_VERSION: u32 = 1;
+
+/// Cached concerts are scraped again after this long, even if their listing entry hasn't changed,
+/// in case something which isn't in the listing (e.g. the performers) has changed
+const MAX_CACHE_AGE_DAYS: i64 = 7;
+
+/// Concerts scraped in previous runs, keyed by URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    version: u32,
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Fingerprint of the listing entry the concert was scraped from
+    fingerprint: String,
+    fetched_at: DateTime<Utc>,
+    concert: core::ConcertData,
+}
+
+impl Cache {
+    /// Read the cache from a file. If the file doesn't exist, can't be parsed, or was written by a
+    /// different version, an empty cache is returned.
+    fn read(path: &Path) -> Cache {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return Cache::default();
+        };
+        match serde_json::from_str::<Cache>(&text) {
+            Ok(cache) if cache.version == CACHE_VERSION => cache,
+            Ok(_) => {
+                info!("Ignoring Wigmore Hall cache from a different version");
+                Cache::default()
+            }
+            Err(e) => {
+                warn!(
+                    "Ignoring invalid Wigmore Hall cache at {}: {}",
+                    path.display(),
+                    e
+                );
+                Cache::default()
+            }
+        }
+    }
+
+    fn write(&self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, serde_json::to_string(self).unwrap()).unwrap();
+    }
+
+    /// The cache after a run. Concerts which are still in `listing` are kept: either as they were
+    /// just `fetched`, or from this cache. If a concert couldn't be fetched again (because it
+    /// went over the limit, or its page failed), its old entry is kept so that it doesn't
+    /// disappear from the output; as the old entry is out of date, it is fetched again next time.
+    /// Concerts which are no longer listed are dropped.
+    fn updated<'a>(
+        &self,
+        listing: &[WigmoreFrontPageConcert],
+        fetched: impl IntoIterator<Item = (&'a WigmoreFrontPageConcert, core::ConcertData)>,
+        now: DateTime<Utc>,
+    ) -> Cache {
+        let mut entries: HashMap<String, CacheEntry> = fetched
+            .into_iter()
+            .map(|(fp_entry, concert)| {
+                let entry = CacheEntry {
+                    fingerprint: fp_entry.fingerprint.clone(),
+                    fetched_at: now,
+                    concert,
+                };
+                (fp_entry.url.clone(), entry)
+            })
+            .collect();
+        for fp_entry in listing {
+            if !entries.contains_key(&fp_entry.url) {
+                if let Some(entry) = self.entries.get(&fp_entry.url) {
+                    entries.insert(fp_entry.url.clone(), entry.clone());
+                }
+            }
+        }
+        Cache {
+            version: CACHE_VERSION,
+            entries,
+        }
+    }
+
+    /// Get the cached concert for a listing entry, if it's still up to date
+    fn get(&self, fp_entry: &WigmoreFrontPageConcert, now: DateTime<Utc>) -> Option<&CacheEntry> {
+        self.entries.get(&fp_entry.url).filter(|entry| {
+            entry.fingerprint == fp_entry.fingerprint
+                && now - entry.fetched_at < Duration::days(MAX_CACHE_AGE_DAYS)
+        })
+    }
+}
+
+/// Fetch full data for all Wigmore concerts. Concerts whose listing entry hasn't changed since
+/// they were last scraped are taken from the cache at `cache_path` (if given), so only new or
+/// changed concerts need to be fetched; at most `max` of these are fetched, if specified. Concerts
+/// which aren't fetched (because of `max`, or because their page failed) keep their old details
+/// from the cache.
+pub async fn get_concerts(
+    client: &http::Client,
+    max: Option<usize>,
+    cache_path: Option<&Path>,
+) -> Vec<core::ConcertData> {
     let max_text = match max {
-        Some(n) => format!("first {}", n),
+        Some(n) => format!("at most {}", n),
         None => "all".to_string(),
     };
-    info!("Scraping {max_text} Wigmore Hall concerts");
+    info!("Scraping Wigmore Hall concerts (fetching {max_text} new or changed concerts)");
 
-    let wigmore_intermediate_concerts = get_api(client).await;
-    let slice = match max {
-        Some(n) => &wigmore_intermediate_concerts[..n.min(wigmore_intermediate_concerts.len())],
-        None => &wigmore_intermediate_concerts,
+    let Some(wigmore_intermediate_concerts) = get_api(client).await else {
+        // Leave the cache alone, as nothing can be said about which concerts are still listed
+        warn!("Failed to fetch the Wigmore Hall listing");
+        return vec![];
     };
+    let cache = cache_path.map(Cache::read).unwrap_or_default();
+    let now = Utc::now();
+
+    let mut to_fetch = vec![];
+    for fp_entry in &wigmore_intermediate_concerts {
+        match cache.get(fp_entry, now) {
+            Some(_) => client.record_cache_hit(),
+            None => to_fetch.push(fp_entry),
+        }
+    }
+    info!(
+        "Reusing {} cached Wigmore Hall concerts; {} are new or changed",
+        wigmore_intermediate_concerts.len() - to_fetch.len(),
+        to_fetch.len()
+    );
+    if let Some(n) = max {
+        to_fetch.truncate(n);
+    }
 
-    let mut wigmore_concerts = stream::iter(slice)
-        .map(|concert| get_full_concert(concert, client))
+    let fetched = stream::iter(to_fetch)
+        .map(|fp_entry| async move {
+            get_full_concert(fp_entry, client)
+                .await
+                .map(|concert| (fp_entry, concert))
+        })
         .buffer_unordered(10)
-        .collect::<Vec<Option<core::ConcertData>>>()
-        .await
-        .into_iter()
-        .flatten()
-        .collect::<Vec<core::ConcertData>>();
+        .collect::<Vec<Option<(&WigmoreFrontPageConcert, core::ConcertData)>>>()
+        .await;
+    let new_cache = cache.updated(
+        &wigmore_intermediate_concerts,
+        fetched.into_iter().flatten(),
+        now,
+    );
+    if let Some(path) = cache_path {
+        new_cache.write(path);
+    }
 
+    let mut wigmore_concerts = new_cache
+        .entries
+        .into_values()
+        .map(|entry| entry.concert)
+        .collect::<Vec<core::ConcertData>>();
     wigmore_concerts.sort_by_key(|concert| concert.datetime);
     info!("Scraped {} Wigmore Hall concerts", wigmore_concerts.len());
     wigmore_concerts
@@ -40,106 +184,128 @@ pub async fn get_concerts(client: &reqwest::Client, max: Option<usize>) -> Vec<c
 /// contain all the information we need. To get the remainder of the information we need to perform
 /// a separate API query.
 #[derive(Debug, Serialize, Deserialize)]
-struct WigmoreFrontPageConcert {
-    datetime: DateTime<Utc>,
-    url: String,
-    title: String,
-    subtitle: Option<String>,
+pub struct WigmoreFrontPageConcert {
+    pub datetime: DateTime<Utc>,
+    pub url: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Hash of the whole listing entry, used to tell whether it has changed since the concert was
+    /// last scraped
+    pub fingerprint: String,
 }
 
 /// Retrieve a list of all upcoming concerts via the Wigmore Hall API. This function only returns
-/// front-page information, which does not include full details.
+/// front-page information, which does not include full details. Returns `None` if the page can't
+/// be fetched.
 async fn get_api_page(
-    client: &reqwest::Client,
+    client: &http::Client,
     page_number: u64,
-) -> (Vec<WigmoreFrontPageConcert>, u64) {
+) -> Option<(Vec<WigmoreFrontPageConcert>, u64)> {
     let url = format!(
         "https://www.wigmore-hall.org.uk/api/v1/listings/whats-on?page={}",
         page_number
     );
-    let json: serde_json::Value = client.get(url).send().await.unwrap().json().await.unwrap();
+    let json: serde_json::Value = client.try_get_json(&url).await?;
     let concerts = parse_api(json["items"].as_array().unwrap());
-    (concerts, json["totalPages"].as_u64().unwrap())
+    Some((concerts, json["totalPages"].as_u64().unwrap()))
 }
 
-/// Parse the JSON response from the Wigmore Hall API
-fn parse_api(json_items: &Vec<serde_json::Value>) -> Vec<WigmoreFrontPageConcert> {
+/// Parse the items in a JSON response from the Wigmore Hall API
+pub fn parse_api(json_items: &[serde_json::Value]) -> Vec<WigmoreFrontPageConcert> {
     let mut concerts = Vec::new();
 
     for item in json_items {
-        let datetime_str = item["node"]["date"].as_str().unwrap();
-        let datetime = DateTime::parse_from_rfc3339(datetime_str).unwrap().into();
         let url: String = format!(
             "https://wigmore-hall.org.uk{}",
             item["node"]["url"].as_str().unwrap()
         );
+        // Listings whose date can't be read are skipped
+        let Some(datetime_str) = item["node"]["date"].as_str() else {
+            warn!("Skipping {}, which has no date", url);
+            continue;
+        };
+        let datetime = match DateTime::parse_from_rfc3339(datetime_str) {
+            Ok(datetime) => datetime.into(),
+            Err(e) => {
+                warn!("Skipping {}: invalid date {}: {}", url, datetime_str, e);
+                continue;
+            }
+        };
         let title: String = item["node"]["titleOverrideText"]
             .as_str()
             .unwrap()
             .replace("<sub>", "")
             .replace("</sub>", "");
         let subtitle: Option<String> = item["node"]["subtitleText"].as_str().map(|s| s.to_string());
+        let fingerprint = Sha256::digest(item["node"].to_string().as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
 
         concerts.push(WigmoreFrontPageConcert {
             datetime,
             url,
             title,
             subtitle,
+            fingerprint,
         });
     }
 
     concerts
 }
 
-/// Retrieve all upcoming concerts via the Wigmore Hall API
-async fn get_api(client: &reqwest::Client) -> Vec<WigmoreFrontPageConcert> {
+/// Retrieve all upcoming concerts via the Wigmore Hall API. Returns `None` if any page can't be
+/// fetched, since then it isn't known which concerts are still listed.
+async fn get_api(client: &http::Client) -> Option<Vec<WigmoreFrontPageConcert>> {
     let mut concerts = Vec::new();
 
     // Scrape the first page and determine how many pages there are
-    let (first_page, npages) = get_api_page(client, 1).await;
+    let (first_page, npages) = get_api_page(client, 1).await?;
     concerts.extend(first_page);
     // Then scrape the remaining pages
     let futures = (2..=npages).map(|i| get_api_page(client, i));
-    let remaining_concerts: Vec<WigmoreFrontPageConcert> = join_all(futures)
-        .await
-        .into_iter()
-        .flat_map(|(concerts, _)| concerts)
-        .collect();
-    concerts.extend(remaining_concerts);
-    concerts
+    for page in join_all(futures).await {
+        let (page_concerts, _) = page?;
+        concerts.extend(page_concerts);
+    }
+    Some(concerts)
 }
 
 /// Retrieve details of an individual concert by scraping the URL
 async fn get_full_concert(
     fp_entry: &WigmoreFrontPageConcert,
-    client: &reqwest::Client,
+    client: &http::Client,
 ) -> Option<core::ConcertData> {
     // Wigmore's website actually seems to give us all the data in JSON format, but curiously, it's
     // in a script tag in the HTML. Not complaining though as it is still so much easier than
     // parsing the HTML itself.
-    let html = client
-        .get(&fp_entry.url)
-        .send()
-        .await
-        .unwrap()
-        .text()
-        .await
-        .unwrap();
-    let doc = Html::parse_document(&html);
+    let html = client.try_get_text(&fp_entry.url).await?;
+    match parse_concert_page(fp_entry, &html) {
+        Ok(concert) => Some(concert),
+        Err(e) => {
+            warn!("Error parsing concert at {}: {}", fp_entry.url, e);
+            client.record_error();
+            None
+        }
+    }
+}
+
+/// Parse a concert's page, given its entry in the API listing. This fails if the page has no JSON
+/// props, or they can't be parsed.
+pub fn parse_concert_page(
+    fp_entry: &WigmoreFrontPageConcert,
+    html: &str,
+) -> Result<core::ConcertData, String> {
+    let doc = Html::parse_document(html);
     let json_script_text = doc
         .select(&Selector::parse("script#props").unwrap())
         .next()
-        .unwrap()
+        .ok_or("No script#props on the page")?
         .inner_html()
         .replace("&lt;\\!--", "&lt;!--"); // Fix invalid escape sequence
-    let json_result = serde_json::from_str::<serde_json::Value>(&json_script_text);
-    match json_result {
-        Ok(json) => Some(parse_concert_json(fp_entry, json)),
-        Err(e) => {
-            warn!("Error parsing JSON for concert at {}: {}", fp_entry.url, e);
-            None
-        }
-    }
+    serde_json::from_str::<serde_json::Value>(&json_script_text)
+        .map(|json| parse_concert_json(fp_entry, json))
+        .map_err(|e| format!("Invalid JSON in script#props: {}", e))
 }
 
 fn parse_concert_json(
@@ -163,7 +329,8 @@ fn parse_concert_json(
                 };
                 let opt_composer = piece["composers"]
                     .as_array()
-                    .and_then(|arr| arr[0]["title"].as_str())
+                    .and_then(|arr| arr.first())
+                    .and_then(|composer| composer["title"].as_str())
                     .or(Some(""))
                     .map(|s| decode_html_entities(s).to_string());
                 if let (Some(title), Some(composer)) = (opt_title, opt_composer) {
@@ -215,6 +382,69 @@ fn parse_concert_json(
             )
         }
     };
+    // Free events say whether they need to be booked in the booking information
+    let availability = core::parse_availability(
+        &format!("{}\n{}", prices_text, booking_text),
+        min_price,
+        max_price,
+    );
+
+    // Livestreams, on-demand recordings and broadcasts are in the page's props. Older pages
+    // only mention them in the text, e.g. "This concert will be broadcast live on BBC Radio 3",
+    // and link to livestreams if they are on YouTube (otherwise they are shown on the concert
+    // page itself).
+    let media = match media::media_from_props(&json["data"]["page"], fp_entry.datetime) {
+        Some(media) => media,
+        None => {
+            let media_text = [
+                json["data"]["page"]["overviewText"].as_str().unwrap_or(""),
+                json["data"]["page"]["bookingInformationText"]
+                    .as_str()
+                    .unwrap_or(""),
+            ]
+            .join("\n");
+            let mut media = media::find_media(
+                &decode_html_entities(&media_text),
+                fp_entry.datetime,
+                &fp_entry.url,
+            );
+            if media.livestream_url.is_some() {
+                let youtube_re =
+                    Regex::new(r#"href="(https://(?:www\.)?(?:youtube\.com|youtu\.be)/[^"]+)""#)
+                        .unwrap();
+                if let Some(caps) = youtube_re.captures(&media_text) {
+                    media.livestream_url = Some(caps[1].to_string());
+                }
+            }
+            media
+        }
+    };
+
+    // Wigmore Hall puts concerts into series, e.g. artist residencies or "Wigmore Hall Learning"
+    let series: Vec<core::Series> = json["data"]["page"]["series"]
+        .as_array()
+        .map(|series| {
+            series
+                .iter()
+                .filter_map(|s| {
+                    let name = s["title"].as_str()?;
+                    Some(core::Series {
+                        name: decode_html_entities(name).to_string(),
+                        organiser: "Wigmore Hall".to_string(),
+                        start_date: None,
+                        end_date: None,
+                        url: s["url"].as_str().map(|url| {
+                            if url.starts_with("http") {
+                                url.to_string()
+                            } else {
+                                format!("https://wigmore-hall.org.uk{}", url)
+                            }
+                        }),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
 
     fn clean_up_description(s: &str) -> String {
         // Split paragraphs
@@ -235,7 +465,7 @@ fn parse_concert_json(
         cleaned
     }
 
-    let concert = core::ConcertData {
+    let mut concert = core::ConcertData {
         datetime: fp_entry.datetime,
         url: fp_entry.url.clone(),
         title: fp_entry.title.clone(),
@@ -246,15 +476,116 @@ fn parse_concert_json(
         programme_pdf_url: json["data"]["page"]["programmeDocument"]["url"]
             .as_str()
             .map(|s| s.to_string()),
+        programme_text: None,
         venue: "Wigmore Hall".to_string(),
+        category: core::Category::Other,
         is_wigmore_u35,
         performers,
         pieces,
         min_price,
         max_price,
-        is_prom: false,
+        availability,
+        media,
+        series,
+        production: None,
     };
 
+    // The series names (e.g. "Chamber Music" or "Learning") say a lot about what kind of event
+    // it is
+    let tags: Vec<String> = concert.series.iter().map(|s| s.name.clone()).collect();
+    concert.category = classify::classify(&concert, &tags);
+
     core::report_concert(&concert);
     concert
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fp_entry(n: u32, fingerprint: &str) -> WigmoreFrontPageConcert {
+        WigmoreFrontPageConcert {
+            datetime: Utc.with_ymd_and_hms(2026, 4, n, 19, 30, 0).unwrap(),
+            url: format!("https://wigmore-hall.org.uk/whats-on/{}", n),
+            title: format!("Concert {}", n),
+            subtitle: None,
+            fingerprint: fingerprint.to_string(),
+        }
+    }
+
+    fn concert(title: &str) -> core::ConcertData {
+        core::ConcertData {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn cache(entries: &[(u32, &str, DateTime<Utc>)]) -> Cache {
+        Cache {
+            version: CACHE_VERSION,
+            entries: entries
+                .iter()
+                .map(|&(n, fingerprint, fetched_at)| {
+                    let entry = CacheEntry {
+                        fingerprint: fingerprint.to_string(),
+                        fetched_at,
+                        concert: concert(&format!("Cached {}", n)),
+                    };
+                    (fp_entry(n, fingerprint).url, entry)
+                })
+                .collect(),
+        }
+    }
+
+    fn cached_title(cache: &Cache, n: u32) -> Option<&str> {
+        cache
+            .entries
+            .get(&fp_entry(n, "").url)
+            .map(|entry| entry.concert.title.as_str())
+    }
+
+    #[test]
+    fn out_of_date_entries_are_refetched() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let cache = cache(&[
+            (1, "a", now - Duration::days(1)),
+            (2, "b", now - Duration::days(1)),
+            (3, "c", now - Duration::days(MAX_CACHE_AGE_DAYS + 1)),
+        ]);
+        assert!(cache.get(&fp_entry(1, "a"), now).is_some());
+        assert!(cache.get(&fp_entry(2, "changed"), now).is_none());
+        assert!(cache.get(&fp_entry(3, "c"), now).is_none());
+        assert!(cache.get(&fp_entry(4, "d"), now).is_none());
+    }
+
+    #[test]
+    fn concerts_which_are_not_fetched_keep_their_old_entry() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let yesterday = now - Duration::days(1);
+        let old_cache = cache(&[
+            (1, "a", yesterday),
+            (2, "b", yesterday),
+            (3, "c", yesterday),
+            (5, "e", yesterday),
+        ]);
+        // 1 is unchanged, 2 and 3 have changed but only 2 could be fetched, 4 is new but couldn't
+        // be fetched, and 5 is no longer listed
+        let listing = vec![
+            fp_entry(1, "a"),
+            fp_entry(2, "b2"),
+            fp_entry(3, "c2"),
+            fp_entry(4, "d"),
+        ];
+        let new_cache = old_cache.updated(&listing, vec![(&listing[1], concert("New 2"))], now);
+
+        assert_eq!(cached_title(&new_cache, 1), Some("Cached 1"));
+        assert_eq!(cached_title(&new_cache, 2), Some("New 2"));
+        assert_eq!(cached_title(&new_cache, 3), Some("Cached 3"));
+        assert_eq!(cached_title(&new_cache, 4), None);
+        assert_eq!(cached_title(&new_cache, 5), None);
+        // The old entry is still out of date, so it's fetched next time
+        assert!(new_cache.get(&listing[2], now).is_none());
+        assert!(new_cache.get(&listing[1], now).is_some());
+    }
+}
@@ -0,0 +1,61 @@
+use london_classical::core::{parse_availability, parse_prices, Availability};
+
+#[test]
+fn free_events_which_do_not_need_booking() {
+    for text in [
+        "Free, no booking required",
+        "Free – no booking",
+        "FREE. No tickets needed, just turn up",
+        "Free entry, unticketed",
+    ] {
+        assert_eq!(
+            parse_availability(text, Some(0), Some(0)),
+            Availability::FreeNoBooking,
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn free_events_which_need_booking() {
+    assert_eq!(
+        parse_availability("Free, booking required", Some(0), Some(0)),
+        Availability::Free
+    );
+    assert_eq!(
+        parse_availability("Free", Some(0), Some(0)),
+        Availability::Free
+    );
+}
+
+#[test]
+fn paid_events() {
+    // Not needing to book doesn't make a concert free
+    assert_eq!(
+        parse_availability(
+            "£10 on the door, no booking required",
+            Some(1000),
+            Some(1000)
+        ),
+        Availability::Ticketed
+    );
+    assert_eq!(parse_availability("", None, None), Availability::Ticketed);
+}
+
+#[test]
+fn retiring_collections() {
+    // A retiring collection on its own means the concert is free
+    assert_eq!(parse_prices("Retiring collection"), vec![0]);
+    for text in [
+        "Free, retiring collection",
+        "Free entry – there will be a retiring collection",
+    ] {
+        assert_eq!(
+            parse_availability(text, Some(0), Some(0)),
+            Availability::RetiringCollection,
+            "{}",
+            text
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use london_classical::churches::{smith_square, southwark_cathedral, st_pauls, westminster_abbey};
+use london_classical::core::{self, Availability, Category};
+
+#[test]
+fn smith_square_json_ld() {
+    let concerts = smith_square::parse_listing(
+        include_str!("fixtures/churches/smith_square.html"),
+        "https://www.sjss.org.uk/whats-on",
+    );
+    // The organisation's own JSON-LD isn't an event
+    assert_eq!(concerts.len(), 2);
+
+    let c = &concerts[0];
+    assert_eq!(c.title, "Bach & Handel: Lunchtime Recital");
+    assert_eq!(
+        c.url,
+        "https://www.sjss.org.uk/whats-on/bach-handel-lunchtime-recital"
+    );
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 2, 10, 13, 0).unwrap());
+    assert_eq!(c.venue, "St John's Smith Square");
+    assert_eq!(
+        c.description.as_deref(),
+        Some("Music for harpsichord by Bach and Handel.")
+    );
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    assert_eq!(c.availability, Availability::RetiringCollection);
+    assert_eq!(c.category, Category::Recital);
+
+    // No UTC offset means London time
+    let c = &concerts[1];
+    assert_eq!(
+        c.datetime,
+        core::ymd_hm_to_utc(2026, 3, 28, 19, 30).unwrap()
+    );
+    assert_eq!((c.min_price, c.max_price), (Some(1500), Some(3500)));
+    assert_eq!(c.availability, Availability::Ticketed);
+    assert_eq!(c.category, Category::Choral);
+}
+
+#[test]
+fn westminster_abbey_recitals_and_services() {
+    let concerts = westminster_abbey::parse_listing(
+        include_str!("fixtures/churches/westminster_abbey.html"),
+        "https://www.westminster-abbey.org/whats-on/music",
+    );
+    assert_eq!(concerts.len(), 2);
+
+    let recital = &concerts[0];
+    assert_eq!(recital.category, Category::Recital);
+    assert_eq!(recital.performers.len(), 1);
+    assert_eq!(recital.performers[0].name, "Peter Holder");
+    assert_eq!(recital.performers[0].instrument.as_deref(), Some("organ"));
+    assert_eq!(
+        (recital.min_price, recital.max_price),
+        (Some(1000), Some(1000))
+    );
+    assert_eq!(recital.availability, Availability::Ticketed);
+
+    let evensong = &concerts[1];
+    assert_eq!(evensong.category, Category::Service);
+    assert_eq!(evensong.venue, "Westminster Abbey");
+    assert_eq!(
+        evensong.url,
+        "https://www.westminster-abbey.org/whats-on/worship/choral-evensong-2026-02-16"
+    );
+    assert_eq!((evensong.min_price, evensong.max_price), (Some(0), Some(0)));
+    assert_eq!(evensong.availability, Availability::FreeNoBooking);
+}
+
+#[test]
+fn southwark_cathedral_music_list() {
+    let page_url = "https://cathedral.southwark.anglican.org/worship-and-music/music/music-list/";
+    let concerts = southwark_cathedral::parse_listing(
+        include_str!("fixtures/churches/southwark_cathedral.html"),
+        page_url,
+    );
+    assert_eq!(concerts.len(), 3);
+
+    let organ = &concerts[0];
+    assert_eq!(
+        organ.datetime,
+        core::ymd_hm_to_utc(2026, 2, 2, 13, 10).unwrap()
+    );
+    // Entries without a link of their own point to the music list
+    assert_eq!(organ.url, page_url);
+    assert_eq!(organ.venue, "Southwark Cathedral");
+    assert_eq!(organ.performers[0].name, "Ourania Gassiou");
+    assert_eq!(
+        organ.description.as_deref(),
+        Some("Assistant Organist, Southwark Cathedral.\nBach, Franck and Duruflé.")
+    );
+    assert_eq!(organ.availability, Availability::RetiringCollection);
+    assert_eq!(organ.category, Category::Recital);
+
+    let quartet = &concerts[1];
+    assert_eq!(
+        quartet.url,
+        "https://cathedral.southwark.anglican.org/worship-and-music/music/lunchtime-recitals/"
+    );
+    assert_eq!(quartet.availability, Availability::FreeNoBooking);
+
+    let evensong = &concerts[2];
+    assert_eq!(evensong.category, Category::Service);
+    assert_eq!((evensong.min_price, evensong.max_price), (None, None));
+}
+
+#[test]
+fn st_pauls_calendar() {
+    let concerts = st_pauls::parse_listing(
+        include_str!("fixtures/churches/st_pauls.html"),
+        "https://www.stpauls.co.uk/whats-on?type=music",
+    );
+    assert_eq!(concerts.len(), 3);
+
+    let organ = &concerts[0];
+    assert_eq!(
+        organ.datetime,
+        core::ymd_hm_to_utc(2026, 3, 1, 16, 45).unwrap()
+    );
+    assert_eq!(organ.venue, "St Paul's Cathedral");
+    assert_eq!(organ.performers[0].name, "Simon Johnson");
+    assert_eq!(organ.availability, Availability::RetiringCollection);
+    assert_eq!(core::price_string(organ), "Free entry, retiring collection");
+
+    let crypt = &concerts[1];
+    assert_eq!(
+        crypt.datetime,
+        core::ymd_hm_to_utc(2026, 4, 2, 19, 30).unwrap()
+    );
+    assert_eq!(crypt.venue, "St Paul's Cathedral (Crypt)");
+    assert_eq!((crypt.min_price, crypt.max_price), (Some(2000), Some(4000)));
+
+    // Labelled as worship by the cathedral
+    let eucharist = &concerts[2];
+    assert_eq!(eucharist.category, Category::Service);
+    assert_eq!(eucharist.availability, Availability::Free);
+}
+
+#[test]
+fn calendar_entries_without_a_title_are_skipped() {
+    let html = r#"
+        <article class="event"><time class="event__date" datetime="2026-03-01T16:45">1 March</time></article>
+        <article class="event">
+            <h2 class="event__title">Organ Recital: Simon Johnson</h2>
+            <time class="event__date" datetime="2026-03-08T16:45">8 March</time>
+        </article>"#;
+    let concerts = st_pauls::parse_listing(html, "https://www.stpauls.co.uk/whats-on?type=music");
+    assert_eq!(concerts.len(), 1);
+    assert_eq!(concerts[0].title, "Organ Recital: Simon Johnson");
+}
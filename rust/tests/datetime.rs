@@ -0,0 +1,223 @@
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use london_classical::core::ymd_hm_to_utc;
+use london_classical::datetime::{
+    london_to_utc, parse_date, parse_london, parse_time, parse_with_formats, DatetimeError,
+};
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+#[test]
+fn times() {
+    for (text, expected) in [
+        ("7.30pm", hm(19, 30)),
+        ("7:30 PM", hm(19, 30)),
+        ("7.30 p.m.", hm(19, 30)),
+        ("7pm", hm(19, 0)),
+        ("11am", hm(11, 0)),
+        ("12.05pm", hm(12, 5)),
+        ("12am", hm(0, 0)),
+        ("19:30", hm(19, 30)),
+        ("19.30", hm(19, 30)),
+        ("noon", hm(12, 0)),
+        ("12 noon", hm(12, 0)),
+        ("Midday", hm(12, 0)),
+        ("midnight", hm(0, 0)),
+    ] {
+        assert_eq!(parse_time(text), Ok(expected), "{}", text);
+    }
+}
+
+#[test]
+fn ranges_start_at_the_start() {
+    assert_eq!(parse_time("7.30pm – 9.30pm"), Ok(hm(19, 30)));
+    assert_eq!(parse_time("19:30-21:30"), Ok(hm(19, 30)));
+    assert_eq!(parse_time("7–9pm"), Ok(hm(19, 0)));
+    assert_eq!(parse_time("7.30 to 9.30pm"), Ok(hm(19, 30)));
+    assert_eq!(parse_time("11–1pm"), Ok(hm(11, 0)));
+    assert_eq!(parse_time("Starts 1pm, ends 2pm"), Ok(hm(13, 0)));
+}
+
+#[test]
+fn doors_are_before_the_start() {
+    assert_eq!(parse_time("Doors 6.30pm, concert 7.30pm"), Ok(hm(19, 30)));
+    assert_eq!(parse_time("7.30pm (doors 7pm)"), Ok(hm(19, 30)));
+    // If that's all there is, it's better than nothing
+    assert_eq!(parse_time("doors 7pm"), Ok(hm(19, 0)));
+}
+
+#[test]
+fn things_which_are_not_times() {
+    assert!(matches!(
+        parse_time("Tickets £12.50"),
+        Err(DatetimeError::Unrecognised(_))
+    ));
+    assert!(matches!(
+        parse_time("TBC"),
+        Err(DatetimeError::Unrecognised(_))
+    ));
+    assert!(matches!(parse_time("13pm"), Err(DatetimeError::Invalid(_))));
+    assert!(matches!(
+        parse_time("19:75"),
+        Err(DatetimeError::Invalid(_))
+    ));
+}
+
+#[test]
+fn dates() {
+    let date = NaiveDate::from_ymd_opt(2026, 2, 4).unwrap();
+    for text in [
+        "Wednesday 4 February 2026",
+        "Wed 4 Feb 2026",
+        "Wed 4th Feb 2026",
+        "4 February 2026",
+        "Wednesday, 4 February, 2026",
+        "February 4, 2026",
+        "2026-02-04",
+        "04/02/2026",
+    ] {
+        assert_eq!(parse_date(text), Ok(date), "{}", text);
+    }
+    assert_eq!(
+        parse_date("Tue 22 Sept 2026"),
+        Ok(NaiveDate::from_ymd_opt(2026, 9, 22).unwrap())
+    );
+    // Wrong day of the week
+    assert!(matches!(
+        parse_date("Thursday 4 February 2026"),
+        Err(DatetimeError::Invalid(_))
+    ));
+    assert!(matches!(
+        parse_date("30 February 2026"),
+        Err(DatetimeError::Invalid(_))
+    ));
+    assert!(matches!(
+        parse_date("Sometime soon"),
+        Err(DatetimeError::Unrecognised(_))
+    ));
+}
+
+#[test]
+fn dates_and_times() {
+    for (text, expected) in [
+        (
+            "Wednesday 4 February 2026, 7.30pm",
+            ymd_hm_to_utc(2026, 2, 4, 19, 30).unwrap(),
+        ),
+        (
+            "Friday 6 March 2026, 1pm",
+            ymd_hm_to_utc(2026, 3, 6, 13, 0).unwrap(),
+        ),
+        (
+            "Friday 6 March 2026, 12 noon",
+            ymd_hm_to_utc(2026, 3, 6, 12, 0).unwrap(),
+        ),
+        (
+            "Sat 7 Feb 2026 at 19:30",
+            ymd_hm_to_utc(2026, 2, 7, 19, 30).unwrap(),
+        ),
+        (
+            "Fri 6 Mar 2026, doors 7pm",
+            ymd_hm_to_utc(2026, 3, 6, 19, 0).unwrap(),
+        ),
+        (
+            "Fri 6 Mar 2026 | 7.30pm – 9.30pm",
+            ymd_hm_to_utc(2026, 3, 6, 19, 30).unwrap(),
+        ),
+        (
+            "2026-02-04 7.30pm",
+            ymd_hm_to_utc(2026, 2, 4, 19, 30).unwrap(),
+        ),
+        // No time means midnight
+        ("Sat 7 Feb 2026", ymd_hm_to_utc(2026, 2, 7, 0, 0).unwrap()),
+    ] {
+        assert_eq!(parse_london(text), Ok(expected), "{}", text);
+    }
+    // British Summer Time
+    assert_eq!(
+        parse_london("Saturday 11 April 2026, 11am"),
+        Ok(Utc.with_ymd_and_hms(2026, 4, 11, 10, 0, 0).unwrap())
+    );
+    assert!(parse_london("Sometime soon, 7pm").is_err());
+}
+
+#[test]
+fn dates_and_times_which_do_not_exist() {
+    assert!(matches!(
+        ymd_hm_to_utc(2026, 2, 30, 19, 30),
+        Err(DatetimeError::Invalid(_))
+    ));
+    assert!(matches!(
+        ymd_hm_to_utc(2026, 3, 6, 25, 0),
+        Err(DatetimeError::Invalid(_))
+    ));
+}
+
+#[test]
+fn iso_8601() {
+    assert_eq!(
+        parse_london("2026-02-02T13:10:00+00:00"),
+        Ok(Utc.with_ymd_and_hms(2026, 2, 2, 13, 10, 0).unwrap())
+    );
+    assert_eq!(
+        parse_london("2026-06-02T13:10:00+01:00"),
+        Ok(Utc.with_ymd_and_hms(2026, 6, 2, 12, 10, 0).unwrap())
+    );
+    // Without an offset, it's in London
+    assert_eq!(
+        parse_london("2026-06-02T13:10"),
+        Ok(Utc.with_ymd_and_hms(2026, 6, 2, 12, 10, 0).unwrap())
+    );
+}
+
+#[test]
+fn clocks_going_forward() {
+    // 1.30am on 29 March 2026 doesn't exist in London, so it's taken to be 2.30am BST
+    let naive = NaiveDate::from_ymd_opt(2026, 3, 29)
+        .unwrap()
+        .and_hms_opt(1, 30, 0)
+        .unwrap();
+    assert_eq!(
+        london_to_utc(naive),
+        Utc.with_ymd_and_hms(2026, 3, 29, 1, 30, 0).unwrap()
+    );
+    assert_eq!(
+        parse_london("Sun 29 Mar 2026, 1.30am"),
+        Ok(Utc.with_ymd_and_hms(2026, 3, 29, 1, 30, 0).unwrap())
+    );
+}
+
+#[test]
+fn clocks_going_back() {
+    // 1.30am on 25 October 2026 happens twice in London; the first time (in BST) is used
+    let naive = NaiveDate::from_ymd_opt(2026, 10, 25)
+        .unwrap()
+        .and_hms_opt(1, 30, 0)
+        .unwrap();
+    assert_eq!(
+        london_to_utc(naive),
+        Utc.with_ymd_and_hms(2026, 10, 25, 0, 30, 0).unwrap()
+    );
+}
+
+#[test]
+fn formats() {
+    let formats = vec!["%d.%m.%Y %H.%M".to_string(), "%d.%m.%Y".to_string()];
+    assert_eq!(
+        parse_with_formats("04.02.2026 19.30", &formats),
+        Ok(ymd_hm_to_utc(2026, 2, 4, 19, 30).unwrap())
+    );
+    assert_eq!(
+        parse_with_formats("04.02.2026", &formats),
+        Ok(ymd_hm_to_utc(2026, 2, 4, 0, 0).unwrap())
+    );
+    assert_eq!(
+        parse_with_formats("Sun 8 Feb 2026, 3pm", &["%a %e %b %Y, %l%P".to_string()]),
+        Ok(ymd_hm_to_utc(2026, 2, 8, 15, 0).unwrap())
+    );
+    assert!(matches!(
+        parse_with_formats("4 Feb", &formats),
+        Err(DatetimeError::Unrecognised(_))
+    ));
+}
@@ -0,0 +1,95 @@
+use london_classical::core::{self, Category};
+use london_classical::eno;
+
+const PRODUCTION_URL: &str = "https://www.eno.org/whats-on/the-marriage-of-figaro/";
+
+#[test]
+fn listing_links_are_resolved_and_deduplicated() {
+    let urls = eno::parse_listing(
+        include_str!("fixtures/eno/listing.html"),
+        "https://www.eno.org/whats-on/",
+    );
+    assert_eq!(
+        urls,
+        vec![PRODUCTION_URL, "https://www.eno.org/whats-on/peter-grimes/"]
+    );
+}
+
+#[test]
+fn shared_roles_are_assigned_to_the_right_performances() {
+    let concerts =
+        eno::parse_production(PRODUCTION_URL, include_str!("fixtures/eno/production.html"));
+    assert_eq!(concerts.len(), 3);
+    for c in &concerts {
+        assert_eq!(c.venue, "London Coliseum");
+        assert_eq!(c.category, Category::Opera);
+        assert_eq!(c.title, "The Marriage of Figaro");
+        assert_eq!(c.subtitle.as_deref(), Some("A day of madness, in English"));
+        // Only a minimum price is given
+        assert_eq!((c.min_price, c.max_price), (Some(1000), None));
+    }
+    assert_eq!(
+        concerts[0].datetime,
+        core::ymd_hm_to_utc(2026, 2, 5, 19, 30).unwrap()
+    );
+    assert_eq!(
+        concerts[2].datetime,
+        core::ymd_hm_to_utc(2026, 2, 19, 13, 0).unwrap()
+    );
+
+    let first = concerts[0].production.as_ref().unwrap();
+    assert_eq!(first.composer, "Wolfgang Amadeus Mozart");
+    assert_eq!(first.director.as_deref(), Some("Joe Hill-Gibbins"));
+    assert_eq!(first.conductor.as_deref(), Some("Martyn Brabbins"));
+    // The length of the interval isn't part of the running time
+    assert_eq!(first.running_time_minutes, Some(200));
+    assert_eq!(first.n_intervals, Some(1));
+    assert_eq!(first.language.as_deref(), Some("English"));
+    assert_eq!(first.surtitles.as_deref(), Some("English"));
+    let cast: Vec<(&str, &str)> = first
+        .cast
+        .iter()
+        .map(|m| (m.role.as_str(), m.name.as_str()))
+        .collect();
+    assert_eq!(
+        cast,
+        vec![
+            ("Figaro", "Božidar Smiljanić"),
+            ("Susanna", "Elena Tsallagova"),
+        ]
+    );
+
+    let last = concerts[2].production.as_ref().unwrap();
+    assert_eq!(last.conductor.as_deref(), Some("Olivia Clarke"));
+    let cast: Vec<&str> = last.cast.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(cast, vec!["Božidar Smiljanić", "Rowan Pierce"]);
+    assert_eq!(concerts[2].performers[0].name, "Olivia Clarke");
+}
+
+#[test]
+fn malformed_productions_are_skipped() {
+    let performances = r#"<table class="performances"><tbody><tr><td>
+        <time datetime="2026-02-01T19:30:00+00:00">1 February</time></td></tr></tbody></table>"#;
+    // No composer
+    let html = format!(
+        r#"<h1 class="production-hero__title">Tosca</h1>{}"#,
+        performances
+    );
+    assert!(eno::parse_production(PRODUCTION_URL, &html).is_empty());
+
+    // A cast member without a role is left out, rather than the whole production
+    let html = format!(
+        r#"<h1 class="production-hero__title">Tosca</h1>
+        <p class="production-hero__composer">Puccini</p>
+        <ul class="cast">
+            <li><span class="cast__name">Understudy</span></li>
+            <li><span class="cast__role">Tosca</span><span class="cast__name">Sinéad Campbell-Wallace</span></li>
+        </ul>{}"#,
+        performances
+    );
+    let concerts = eno::parse_production(PRODUCTION_URL, &html);
+    assert_eq!(concerts.len(), 1);
+    let cast = &concerts[0].production.as_ref().unwrap().cast;
+    assert_eq!(cast.len(), 1);
+    assert_eq!(cast[0].role, "Tosca");
+}
@@ -0,0 +1,88 @@
+use london_classical::core::{self, Availability, Category};
+use london_classical::guildhall;
+use serde_json::Value;
+
+fn read_fixture(json: &str) -> Value {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn ticketing_types() {
+    let page = guildhall::parse_events(&read_fixture(include_str!(
+        "fixtures/guildhall/events.json"
+    )));
+    assert_eq!(
+        page.next_page_url.as_deref(),
+        Some("https://www.gsmd.ac.uk/api/events?category=music&page=2")
+    );
+    assert_eq!(page.concerts.len(), 3);
+
+    let lunchtime = &page.concerts[0];
+    assert_eq!(
+        lunchtime.url,
+        "https://www.gsmd.ac.uk/events/guildhall-chamber-music-lunchtime-recital-10-february-2026"
+    );
+    assert_eq!(
+        lunchtime.datetime,
+        core::ymd_hm_to_utc(2026, 2, 10, 13, 0).unwrap()
+    );
+    assert_eq!(lunchtime.venue, "Milton Court Concert Hall");
+    assert_eq!(lunchtime.subtitle, None);
+    assert_eq!(lunchtime.availability, Availability::FreeNoBooking);
+    assert_eq!(
+        (lunchtime.min_price, lunchtime.max_price),
+        (Some(0), Some(0))
+    );
+    assert_eq!(lunchtime.performers[0].instrument, None);
+    assert_eq!(lunchtime.category, Category::Chamber);
+
+    let orchestra = &page.concerts[1];
+    assert_eq!(
+        orchestra.datetime,
+        core::ymd_hm_to_utc(2026, 3, 31, 19, 0).unwrap()
+    );
+    assert_eq!(orchestra.venue, "Barbican Hall");
+    assert_eq!(orchestra.subtitle.as_deref(), Some("Sibelius and Nielsen"));
+    assert_eq!(orchestra.availability, Availability::Ticketed);
+    assert_eq!(
+        (orchestra.min_price, orchestra.max_price),
+        (Some(800), Some(1800))
+    );
+    assert_eq!(orchestra.category, Category::Orchestral);
+    assert_eq!(
+        orchestra.description.as_deref(),
+        Some("The orchestra plays Sibelius's Fifth Symphony.")
+    );
+
+    let songs = &page.concerts[2];
+    assert_eq!(songs.availability, Availability::Free);
+    assert_eq!(core::price_string(songs), "Free entry");
+    assert_eq!(songs.category, Category::Recital);
+}
+
+#[test]
+fn last_page() {
+    let page = guildhall::parse_events(&read_fixture(include_str!(
+        "fixtures/guildhall/events_last_page.json"
+    )));
+    assert!(page.concerts.is_empty());
+    assert_eq!(page.next_page_url, None);
+}
+
+#[test]
+fn unexpected_events_are_skipped() {
+    let page = guildhall::parse_events(&read_fixture(
+        r#"{"events": [
+            {"url": "/events/masterclass", "title": "Masterclass", "venue": "Silk Street",
+             "start": "2026-03-06T14:00:00+00:00", "ticketing": {"type": "invitation_only"}},
+            {"url": "/events/recital", "venue": "Silk Street", "start": "2026-03-06T19:30:00+00:00",
+             "ticketing": {"type": "free_booking"}},
+            {"url": "/events/concert", "title": "Concert", "venue": "Milton Court",
+             "start": "2026-03-07T19:30:00+00:00", "ticketing": {"type": "free_booking"},
+             "performers": [{"role": "piano"}, {"name": "Guildhall Symphony Orchestra"}]}
+        ]}"#,
+    ));
+    assert_eq!(page.concerts.len(), 1);
+    assert_eq!(page.concerts[0].title, "Concert");
+    assert_eq!(page.concerts[0].performers.len(), 1);
+}
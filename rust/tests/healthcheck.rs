@@ -0,0 +1,222 @@
+use london_classical::healthcheck::{self, DetailLink, Probe, VenueCheck};
+use london_classical::source::Source;
+
+fn check(source: Source) -> VenueCheck {
+    let mut checks = healthcheck::checks(source);
+    assert_eq!(checks.len(), 1);
+    checks.remove(0)
+}
+
+/// Names of required probes which match nothing on a page
+fn missing(body: &str, probes: &[Probe]) -> Vec<String> {
+    healthcheck::check_page(body, probes)
+        .into_iter()
+        .filter(|result| !result.is_ok() && result.probe.is_required())
+        .map(|result| result.probe.to_string())
+        .collect()
+}
+
+/// Check the saved listing and event page for a source, which the scrapers' own tests use, so the
+/// healthcheck shouldn't complain about them
+fn assert_healthy(source: Source, listing: &str, event: Option<&str>) {
+    let check = check(source);
+    assert_eq!(
+        missing(listing, &check.listing_probes),
+        Vec::<String>::new()
+    );
+    match (&check.detail, event) {
+        (Some(detail), Some(event)) => {
+            assert!(
+                healthcheck::find_detail_url(listing, &check.listing_url, &detail.link).is_some()
+            );
+            assert_eq!(missing(event, &detail.probes), Vec::<String>::new());
+        }
+        (None, None) => {}
+        _ => panic!(
+            "{:?} should have an event page iff it has a detail check",
+            source
+        ),
+    }
+}
+
+#[test]
+fn saved_pages_are_healthy() {
+    assert_healthy(
+        Source::Wigmore,
+        include_str!("fixtures/wigmore/listing.json"),
+        Some(include_str!("fixtures/wigmore/concert.html")),
+    );
+    assert_healthy(
+        Source::Proms,
+        include_str!("fixtures/proms/calendar.html"),
+        None,
+    );
+    assert_healthy(
+        Source::Southbank,
+        include_str!("fixtures/southbank/listing.html"),
+        Some(include_str!("fixtures/southbank/event.html")),
+    );
+    assert_healthy(
+        Source::KingsPlace,
+        include_str!("fixtures/kingsplace/listing.html"),
+        Some(include_str!("fixtures/kingsplace/event.html")),
+    );
+    assert_healthy(
+        Source::LsoStLukes,
+        include_str!("fixtures/lso_st_lukes/listing.html"),
+        Some(include_str!("fixtures/lso_st_lukes/event.html")),
+    );
+    assert_healthy(
+        Source::RoyalOperaHouse,
+        include_str!("fixtures/royal_opera_house/listing.html"),
+        Some(include_str!("fixtures/royal_opera_house/production.html")),
+    );
+    assert_healthy(
+        Source::Eno,
+        include_str!("fixtures/eno/listing.html"),
+        Some(include_str!("fixtures/eno/production.html")),
+    );
+    assert_healthy(
+        Source::Ram,
+        include_str!("fixtures/ram/listing.html"),
+        Some(include_str!("fixtures/ram/event_paid.html")),
+    );
+    assert_healthy(
+        Source::Rcm,
+        include_str!("fixtures/rcm/listing.html"),
+        Some(include_str!("fixtures/rcm/event_paid.html")),
+    );
+    assert_healthy(
+        Source::Guildhall,
+        include_str!("fixtures/guildhall/events.json"),
+        None,
+    );
+    assert_healthy(
+        Source::SmithSquare,
+        include_str!("fixtures/churches/smith_square.html"),
+        None,
+    );
+    assert_healthy(
+        Source::SouthwarkCathedral,
+        include_str!("fixtures/churches/southwark_cathedral.html"),
+        None,
+    );
+    assert_healthy(
+        Source::StPauls,
+        include_str!("fixtures/churches/st_pauls.html"),
+        None,
+    );
+    assert_healthy(
+        Source::WestminsterAbbey,
+        include_str!("fixtures/churches/westminster_abbey.html"),
+        None,
+    );
+}
+
+#[test]
+fn every_source_has_checks() {
+    for source in Source::ALL {
+        assert!(!healthcheck::checks(source).is_empty(), "{:?}", source);
+    }
+}
+
+#[test]
+fn renamed_class_is_reported_with_surrounding_html() {
+    // As if Kings Place renamed the title class
+    let event = include_str!("fixtures/kingsplace/event.html")
+        .replace("event-header__title", "event-header__heading");
+    let detail = check(Source::KingsPlace).detail.unwrap();
+    let results = healthcheck::check_page(&event, &detail.probes);
+    let title = results
+        .iter()
+        .find(|result| result.probe == Probe::css("h1.event-header__title"))
+        .unwrap();
+    assert_eq!(title.n_matches, 0);
+    let context = title.context.as_deref().unwrap();
+    assert!(context.contains("event-header__heading"), "{}", context);
+    assert!(context.chars().count() <= 301);
+}
+
+#[test]
+fn missing_descendant_shows_its_ancestor() {
+    let html = r#"<ul class="event-dates"><li><span>Fri 6 March 7.30pm</span></li></ul>"#;
+    let results = healthcheck::check_page(html, &[Probe::css("ul.event-dates time")]);
+    assert_eq!(results[0].n_matches, 0);
+    assert_eq!(
+        results[0].context.as_deref(),
+        Some(r#"<ul class="event-dates"><li><span>Fri 6 March 7.30pm</span></li></ul>"#)
+    );
+}
+
+#[test]
+fn missing_json_path_shows_deepest_existing_keys() {
+    let json = r#"{"events": [{"title": "Bach", "begins": "2026-03-06T19:30:00Z"}]}"#;
+    let results = healthcheck::check_page(
+        json,
+        &[
+            Probe::json(None, "events.0.title"),
+            Probe::json(None, "events.0.start"),
+            Probe::optional_json(None, "next"),
+        ],
+    );
+    assert!(results[0].is_ok());
+    assert!(!results[1].is_ok());
+    assert_eq!(
+        results[1].context.as_deref(),
+        Some("events.0 has keys begins, title")
+    );
+    assert!(!results[2].is_ok());
+    assert!(!results[2].probe.is_required());
+}
+
+#[test]
+fn detail_urls_are_resolved() {
+    let listing =
+        r#"<div class="event-card"><h3><a href="/whats-on/recital/">Recital</a></h3></div>"#;
+    assert_eq!(
+        healthcheck::find_detail_url(
+            listing,
+            "https://www.rcm.ac.uk/events/",
+            &DetailLink::Css("div.event-card h3 a".to_string())
+        )
+        .as_deref(),
+        Some("https://www.rcm.ac.uk/whats-on/recital/")
+    );
+
+    let json = r#"{"items": [{"node": {"url": "/whats-on/202603061930"}}]}"#;
+    assert_eq!(
+        healthcheck::find_detail_url(
+            json,
+            "https://www.wigmore-hall.org.uk/api/v1/listings/whats-on?page=1",
+            &DetailLink::Json {
+                script: None,
+                path: "items.0.node.url".to_string(),
+                base: Some("https://wigmore-hall.org.uk".to_string()),
+            }
+        )
+        .as_deref(),
+        Some("https://wigmore-hall.org.uk/whats-on/202603061930")
+    );
+}
+
+#[test]
+fn schema_org_sites_are_checked() {
+    let checks = healthcheck::checks(Source::CadoganHall);
+    assert_eq!(checks.len(), 1);
+    let cadogan = &checks[0];
+    assert_eq!(cadogan.name, "Cadogan Hall");
+    let listing = include_str!("fixtures/schema_org/cadogan_listing.html");
+    assert_eq!(
+        missing(listing, &cadogan.listing_probes),
+        Vec::<String>::new()
+    );
+    let detail = cadogan.detail.as_ref().unwrap();
+    assert!(healthcheck::find_detail_url(listing, &cadogan.listing_url, &detail.link).is_some());
+    assert_eq!(
+        missing(
+            include_str!("fixtures/schema_org/cadogan_event.html"),
+            &detail.probes
+        ),
+        Vec::<String>::new()
+    );
+}
@@ -0,0 +1,150 @@
+use london_classical::core::{self, Availability, Category, Performer, Piece};
+use london_classical::kingsplace;
+
+const LISTING_URL: &str = "https://www.kingsplace.co.uk/whats-on/?genre=classical";
+const EVENT_URL: &str =
+    "https://www.kingsplace.co.uk/whats-on/classical/oae-bach-the-universe-and-everything/";
+
+#[test]
+fn listing_links_are_resolved_and_deduplicated() {
+    let listing = kingsplace::parse_listing(
+        include_str!("fixtures/kingsplace/listing.html"),
+        LISTING_URL,
+    );
+    assert_eq!(
+        listing.event_urls,
+        vec![
+            EVENT_URL,
+            "https://www.kingsplace.co.uk/whats-on/classical/aurora-orchestra-chamber-series/",
+            "https://www.kingsplace.co.uk/whats-on/classical/venus-unwrapped-clara-schumann/",
+        ]
+    );
+    assert_eq!(
+        listing.next_page_url.as_deref(),
+        Some("https://www.kingsplace.co.uk/whats-on/?genre=classical&page=2")
+    );
+}
+
+#[test]
+fn last_listing_page_has_no_next_page() {
+    let listing = kingsplace::parse_listing(
+        include_str!("fixtures/kingsplace/listing_last_page.html"),
+        "https://www.kingsplace.co.uk/whats-on/?genre=classical&page=2",
+    );
+    assert_eq!(listing.event_urls.len(), 1);
+    assert_eq!(listing.next_page_url, None);
+}
+
+#[test]
+fn event_gives_one_concert_per_performance() {
+    let concerts =
+        kingsplace::parse_event(EVENT_URL, include_str!("fixtures/kingsplace/event.html"));
+    assert_eq!(concerts.len(), 2);
+    // Before and after the clocks go forward
+    assert_eq!(
+        concerts[0].datetime,
+        core::ymd_hm_to_utc(2026, 2, 8, 11, 30).unwrap()
+    );
+    assert_eq!(
+        concerts[1].datetime,
+        core::ymd_hm_to_utc(2026, 3, 29, 19, 30).unwrap()
+    );
+
+    let c = &concerts[0];
+    assert_eq!(c.url, EVENT_URL);
+    assert_eq!(c.title, "OAE: Bach, the Universe and Everything");
+    assert_eq!(
+        c.subtitle.as_deref(),
+        Some("Cantatas for the end of winter")
+    );
+    assert_eq!(c.venue, "Kings Place (Hall One)");
+    // The concession for under 18s doesn't make the concert free
+    assert_eq!((c.min_price, c.max_price), (Some(1250), Some(2950)));
+    assert_eq!(
+        c.performers[1],
+        Performer {
+            name: "Zoë Brown".to_string(),
+            instrument: Some("director, violin".to_string()),
+        }
+    );
+    assert_eq!(c.performers.len(), 3);
+    assert_eq!(
+        c.pieces,
+        vec![
+            Piece {
+                composer: "J.S. Bach".to_string(),
+                title: "Cantata BWV 55, Ich armer Mensch, ich Sündenknecht".to_string(),
+            },
+            Piece {
+                composer: "J.S. Bach".to_string(),
+                title: "Orchestral Suite No. 3 in D, BWV 1068".to_string(),
+            },
+        ]
+    );
+    assert_eq!(c.series.len(), 1);
+    assert_eq!(c.series[0].name, "Time Unwrapped");
+    assert_eq!(c.series[0].organiser, "Kings Place");
+    assert_eq!(
+        c.series[0].url.as_deref(),
+        Some("https://www.kingsplace.co.uk/series/time-unwrapped/")
+    );
+    assert_eq!(c.category, Category::Orchestral);
+    assert_eq!(c.media.broadcasts.len(), 1);
+    assert!(!c.media.broadcasts[0].is_live);
+    assert!(c
+        .description
+        .as_deref()
+        .unwrap()
+        .starts_with("The Orchestra of the Age of Enlightenment return"));
+}
+
+#[test]
+fn free_event() {
+    let concerts = kingsplace::parse_event(
+        "https://www.kingsplace.co.uk/whats-on/classical/venus-unwrapped-clara-schumann/",
+        include_str!("fixtures/kingsplace/event_free.html"),
+    );
+    assert_eq!(concerts.len(), 1);
+    let c = &concerts[0];
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 2, 21, 18, 0).unwrap());
+    assert_eq!(c.venue, "Kings Place (Hall Two)");
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    // Kings Place doesn't say whether free events need booking
+    assert_eq!(c.availability, Availability::Free);
+    assert_eq!(c.subtitle, None);
+    assert_eq!(c.series[0].name, "Venus Unwrapped");
+    assert_eq!(c.category, Category::Recital);
+    assert_eq!(c.pieces.len(), 2);
+}
+
+#[test]
+fn malformed_events_are_skipped() {
+    // No title
+    let html = r#"<ul class="event-performances"><li><time datetime="2026-02-21T18:00">21 Feb</time></li></ul>"#;
+    assert!(
+        kingsplace::parse_event("https://www.kingsplace.co.uk/whats-on/untitled/", html).is_empty()
+    );
+
+    // A performer without a name is left out, rather than the whole event
+    let html = r#"<h1 class="event-header__title">Recital</h1>
+        <ul class="event-performers">
+            <li><span class="event-performers__role">piano</span></li>
+            <li><span class="event-performers__name">Imogen Cooper</span></li>
+        </ul>
+        <ul class="event-performances"><li><time datetime="2026-02-21T18:00">21 Feb</time></li></ul>"#;
+    let concerts = kingsplace::parse_event("https://www.kingsplace.co.uk/whats-on/recital/", html);
+    assert_eq!(concerts.len(), 1);
+    assert_eq!(concerts[0].performers.len(), 1);
+    assert_eq!(concerts[0].performers[0].name, "Imogen Cooper");
+}
+
+#[test]
+fn listing_links_without_href_are_left_out() {
+    let html = r#"<article class="event-card"><a class="event-card__link">No link</a></article>
+        <article class="event-card"><a class="event-card__link" href="/whats-on/recital/">Recital</a></article>"#;
+    let listing = kingsplace::parse_listing(html, kingsplace::KINGS_PLACE_URL);
+    assert_eq!(
+        listing.event_urls,
+        vec!["https://www.kingsplace.co.uk/whats-on/recital/"]
+    );
+}
@@ -0,0 +1,111 @@
+use london_classical::core::{self, Availability, Category, Piece};
+use london_classical::lso_st_lukes;
+
+const LISTING_URL: &str = "https://www.lso.co.uk/whats-on/?venue=lso-st-lukes";
+const EVENT_URL: &str =
+    "https://www.lso.co.uk/whats-on/bbc-radio-3-lunchtime-concert-elias-quartet/";
+
+#[test]
+fn listing_links_are_resolved() {
+    let urls = lso_st_lukes::parse_listing(
+        include_str!("fixtures/lso_st_lukes/listing.html"),
+        LISTING_URL,
+    );
+    assert_eq!(
+        urls,
+        vec![
+            EVENT_URL,
+            "https://www.lso.co.uk/whats-on/lso-discovery-free-lunchtime-concert/",
+            "https://www.lso.co.uk/whats-on/mahler-symphony-no-9/",
+        ]
+    );
+}
+
+#[test]
+fn radio_3_lunchtime_concert() {
+    let concerts =
+        lso_st_lukes::parse_event(EVENT_URL, include_str!("fixtures/lso_st_lukes/event.html"));
+    assert_eq!(concerts.len(), 1);
+    let c = &concerts[0];
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 1, 15, 13, 0).unwrap());
+    assert_eq!(
+        c.title,
+        "BBC Radio 3 Lunchtime Concert: Elias String Quartet"
+    );
+    assert_eq!(
+        c.subtitle.as_deref(),
+        Some("Beethoven Quartet Cycle, part 2")
+    );
+    assert_eq!(c.venue, "LSO St Luke's");
+    assert_eq!((c.min_price, c.max_price), (Some(550), Some(2000)));
+    assert_eq!(c.availability, Availability::Ticketed);
+    assert_eq!(c.performers.len(), 1);
+    assert_eq!(c.performers[0].name, "Elias String Quartet");
+    assert_eq!(c.performers[0].instrument, None);
+    assert_eq!(
+        c.pieces,
+        vec![
+            Piece {
+                composer: "Beethoven".to_string(),
+                title: "String Quartet in F, Op 18 No 1".to_string(),
+            },
+            Piece {
+                composer: "Beethoven".to_string(),
+                title: "String Quartet in C sharp minor, Op 131".to_string(),
+            },
+        ]
+    );
+    assert_eq!(c.series[0].name, "BBC Radio 3 Lunchtime Concerts");
+    assert_eq!(c.category, Category::Chamber);
+    assert_eq!(c.media.broadcasts.len(), 1);
+    assert_eq!(c.media.broadcasts[0].channel, "BBC Radio 3");
+    assert!(c.media.broadcasts[0].is_live);
+}
+
+#[test]
+fn events_at_other_venues_are_left_out() {
+    let concerts = lso_st_lukes::parse_event(
+        "https://www.lso.co.uk/whats-on/mahler-symphony-no-9/",
+        include_str!("fixtures/lso_st_lukes/event_barbican.html"),
+    );
+    assert!(concerts.is_empty());
+}
+
+#[test]
+fn free_event_with_several_dates() {
+    let concerts = lso_st_lukes::parse_event(
+        "https://www.lso.co.uk/whats-on/lso-discovery-free-lunchtime-concert/",
+        include_str!("fixtures/lso_st_lukes/event_free.html"),
+    );
+    assert_eq!(concerts.len(), 2);
+    assert_eq!(
+        concerts[0].datetime,
+        core::ymd_hm_to_utc(2026, 3, 30, 12, 30).unwrap()
+    );
+    assert_eq!(
+        concerts[1].datetime,
+        core::ymd_hm_to_utc(2026, 3, 31, 12, 30).unwrap()
+    );
+    let c = &concerts[0];
+    assert_eq!(c.venue, "LSO St Luke's");
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    assert_eq!(c.availability, Availability::FreeNoBooking);
+    // "Interval" has no piece after it
+    assert_eq!(c.pieces.len(), 1);
+    assert_eq!(c.pieces[0].title, "Clarinet Quintet in A, K581");
+    assert_eq!(c.performers[0].instrument.as_deref(), Some("clarinet"));
+    assert_eq!(c.performers[1].instrument, None);
+    assert_eq!(c.category, Category::Chamber);
+}
+
+#[test]
+fn events_without_a_venue_or_title_are_skipped() {
+    let dates = r#"<ul class="event-dates"><li><time datetime="2026-03-30T12:30:00+01:00">30 March</time></li></ul>"#;
+    let without_venue = format!(r#"<h1 class="event-hero__title">Recital</h1>{}"#, dates);
+    let without_title = format!(r#"<p class="event-hero__venue">LSO St Luke's</p>{}"#, dates);
+    for html in [without_venue, without_title] {
+        assert!(
+            lso_st_lukes::parse_event("https://www.lso.co.uk/whats-on/recital/", &html).is_empty()
+        );
+    }
+}
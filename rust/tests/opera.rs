@@ -0,0 +1,53 @@
+use london_classical::core::ConcertData;
+use london_classical::opera::{parse_language, parse_running_time};
+
+#[test]
+fn running_times() {
+    assert_eq!(
+        parse_running_time("Approximately 3 hours 10 minutes, including two intervals"),
+        (Some(190), Some(2))
+    );
+    assert_eq!(
+        parse_running_time("2h 30m with an interval"),
+        (Some(150), Some(1))
+    );
+    assert_eq!(
+        parse_running_time("90 minutes without interval"),
+        (Some(90), Some(0))
+    );
+    assert_eq!(parse_running_time("To be confirmed"), (None, None));
+}
+
+#[test]
+fn languages() {
+    assert_eq!(
+        parse_language("Sung in German with English surtitles"),
+        (Some("German".to_string()), Some("English".to_string()))
+    );
+    assert_eq!(
+        parse_language("Sung in English with surtitles"),
+        (Some("English".to_string()), None)
+    );
+    assert_eq!(parse_language("In English"), (None, None));
+}
+
+#[test]
+fn concerts_without_production_still_parse() {
+    // A recital as written before productions were added
+    let json = r#"{
+        "datetime": "2026-01-10T19:30:00Z",
+        "url": "https://www.wigmore-hall.org.uk/whats-on/202601101930",
+        "performers": [{"name": "Mark Padmore", "instrument": "tenor"}],
+        "title": "Mark Padmore",
+        "subtitle": null,
+        "description": null,
+        "programme_pdf_url": null,
+        "pieces": [{"composer": "Schubert", "title": "Winterreise D911"}],
+        "venue": "Wigmore Hall",
+        "min_price": 1800,
+        "max_price": 4500,
+        "is_wigmore_u35": true
+    }"#;
+    let concert: ConcertData = serde_json::from_str(json).unwrap();
+    assert_eq!(concert.production, None);
+}
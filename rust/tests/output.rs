@@ -0,0 +1,143 @@
+use chrono::{DateTime, TimeZone, Utc};
+use london_classical::core::{self, Concert};
+use london_classical::daemon::DaemonState;
+use london_classical::output::{self, ConcertsFile, Envelope, SourceMetadata, SCHEMA_VERSION};
+use london_classical::rcm;
+use london_classical::source::Source;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// A fresh directory for a test to write to
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("london_classical_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn concerts() -> Vec<Concert> {
+    vec![
+        core::add_id_to_concert(
+            rcm::parse_event(
+                "https://www.rcm.ac.uk/events/2026/02/04-ravel-piano-trio/",
+                include_str!("fixtures/rcm/event_free.html"),
+            )
+            .unwrap(),
+        ),
+        core::add_id_to_concert(
+            rcm::parse_event(
+                "https://www.rcm.ac.uk/events/2026/03/06-rcm-symphony-orchestra/",
+                include_str!("fixtures/rcm/event_paid.html"),
+            )
+            .unwrap(),
+        ),
+    ]
+}
+
+fn at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 20, hour, 0, 0).unwrap()
+}
+
+#[test]
+fn schema_is_up_to_date() {
+    let schema = output::json_schema();
+    let path = output::json_schema_path();
+    if std::env::var("LDNCLS_UPDATE_SNAPSHOTS").is_ok_and(|value| !value.is_empty()) {
+        std::fs::write(&path, &schema).unwrap();
+    }
+    assert!(
+        std::fs::read_to_string(&path).is_ok_and(|saved| saved == schema),
+        "{} is out of date; run with LDNCLS_UPDATE_SNAPSHOTS=1 to update it",
+        path.display()
+    );
+}
+
+#[test]
+fn envelope_has_metadata_for_each_source() {
+    let scraped_at = BTreeMap::from([(Source::Rcm, at(6)), (Source::Wigmore, at(5))]);
+    let envelope = Envelope::new(concerts(), &scraped_at, at(7));
+    assert_eq!(envelope.schema_version, SCHEMA_VERSION);
+    assert_eq!(envelope.generated_at, at(7));
+    assert_eq!(
+        envelope.sources,
+        vec![
+            SourceMetadata {
+                source: Source::Wigmore,
+                name: "Wigmore Hall".to_string(),
+                scraped_at: at(5),
+                n_concerts: 0,
+            },
+            SourceMetadata {
+                source: Source::Rcm,
+                name: "Royal College of Music".to_string(),
+                scraped_at: at(6),
+                n_concerts: 2,
+            },
+        ]
+    );
+
+    let dir = temp_dir("envelope");
+    let path = dir.join("concerts.json");
+    envelope.write(&path, false);
+    let json: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    assert_eq!(json["sources"][1]["source"], "Rcm");
+    assert_eq!(
+        json["concerts"][0]["id"].as_str(),
+        Some(envelope.concerts[0].id.as_str())
+    );
+    assert_eq!(output::read(&path), Some(ConcertsFile::Envelope(envelope)));
+}
+
+#[test]
+fn bare_array_can_still_be_written_and_read() {
+    let envelope = Envelope::new(concerts(), &BTreeMap::from([(Source::Rcm, at(6))]), at(7));
+    let dir = temp_dir("bare");
+    let path = dir.join("concerts.json");
+    envelope.write(&path, true);
+    let json: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json.as_array().map(|concerts| concerts.len()), Some(2));
+
+    let file = output::read(&path).unwrap();
+    assert_eq!(file, ConcertsFile::Bare(envelope.concerts.clone()));
+    assert_eq!(file.sources(), &[]);
+    assert_eq!(core::read_concerts(&path), Some(envelope.concerts));
+    assert_eq!(output::read(&dir.join("missing.json")), None);
+}
+
+#[test]
+#[should_panic(expected = "schema version 99")]
+fn newer_schema_version_is_refused() {
+    let mut envelope = Envelope::new(concerts(), &BTreeMap::new(), at(7));
+    envelope.schema_version = 99;
+    let dir = temp_dir("newer");
+    let path = dir.join("concerts.json");
+    envelope.write(&path, false);
+    output::read(&path);
+}
+
+#[test]
+fn daemon_falls_back_on_previous_scrape_times() {
+    let mut state = DaemonState::default();
+    state.record(Source::Rcm, at(6), vec![]);
+    let previous = vec![
+        SourceMetadata {
+            source: Source::Rcm,
+            name: "Royal College of Music".to_string(),
+            scraped_at: at(1),
+            n_concerts: 2,
+        },
+        SourceMetadata {
+            source: Source::Wigmore,
+            name: "Wigmore Hall".to_string(),
+            scraped_at: at(2),
+            n_concerts: 10,
+        },
+    ];
+    assert_eq!(
+        state.scraped_at(&[Source::Wigmore, Source::Rcm, Source::Proms], &previous),
+        BTreeMap::from([(Source::Wigmore, at(2)), (Source::Rcm, at(6))])
+    );
+}
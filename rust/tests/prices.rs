@@ -0,0 +1,34 @@
+use london_classical::core::{format_pennies, parse_prices};
+
+#[test]
+fn pounds_and_pence() {
+    assert_eq!(parse_prices("£15"), vec![1500]);
+    assert_eq!(parse_prices("£15.50"), vec![1550]);
+    // Only two digits after the point are pence
+    assert_eq!(parse_prices("£5.5"), vec![500]);
+    assert_eq!(
+        parse_prices("Tickets £12.50–£45, concessions £6"),
+        vec![1250, 4500, 600]
+    );
+    assert_eq!(parse_prices("£1,000"), vec![100]);
+}
+
+#[test]
+fn free_events() {
+    assert_eq!(parse_prices("Free"), vec![0]);
+    assert_eq!(parse_prices("FREE, booking required"), vec![0]);
+    assert_eq!(parse_prices("Admission free, retiring collection"), vec![0]);
+    // Concessions which are free don't make the event free
+    assert_eq!(parse_prices("£10, under 18s free"), vec![1000]);
+    // Words which only contain "free" don't count
+    assert_eq!(parse_prices("Freedom and Fate"), Vec::<u32>::new());
+    assert_eq!(parse_prices("Sold out"), Vec::<u32>::new());
+}
+
+#[test]
+fn formatting() {
+    assert_eq!(format_pennies(1500), "£15");
+    assert_eq!(format_pennies(1550), "£15.50");
+    assert_eq!(format_pennies(1505), "£15.05");
+    assert_eq!(format_pennies(0), "£0");
+}
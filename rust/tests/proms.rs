@@ -0,0 +1,97 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use london_classical::core::{Category, Performer, Piece};
+use london_classical::proms;
+use scraper::{Html, Selector};
+
+fn parse_piece(html: &str) -> Option<Piece> {
+    let fragment = Html::parse_fragment(html);
+    let slc = Selector::parse("li").unwrap();
+    proms::parse_piece(fragment.select(&slc).next().unwrap())
+}
+
+#[test]
+fn pieces() {
+    assert_eq!(
+        parse_piece(
+            r#"<ul><li class="ev-act-schedule__performance-composer-segments"><span>Maurice Ravel</span><span>Piano Concerto in G major</span></li></ul>"#
+        ),
+        Some(Piece {
+            composer: "Maurice Ravel".to_string(),
+            title: "Piano Concerto in G major".to_string(),
+        })
+    );
+    // Nicknames are separate pieces of text
+    assert_eq!(
+        parse_piece(
+            r#"<ul><li><span>Franz Schubert</span><span>String Quartet No. 14 in D minor</span><span>'Death and the Maiden'</span></li></ul>"#
+        ),
+        Some(Piece {
+            composer: "Franz Schubert".to_string(),
+            title: "String Quartet No. 14 in D minor 'Death and the Maiden'".to_string(),
+        })
+    );
+    assert_eq!(parse_piece("<ul><li><span>interval</span></li></ul>"), None);
+    assert_eq!(parse_piece("<ul><li></li></ul>"), None);
+}
+
+#[test]
+fn calendar() {
+    let today = NaiveDate::from_ymd_opt(2025, 7, 18).unwrap();
+    let concerts = proms::parse_calendar(include_str!("fixtures/proms/calendar.html"), today);
+    // Concerts before today are left out, but still count towards the dates of the season
+    assert_eq!(concerts.len(), 3);
+    assert_eq!(
+        concerts[0].series[0].start_date,
+        NaiveDate::from_ymd_opt(2025, 7, 17)
+    );
+    assert_eq!(
+        concerts[0].series[0].end_date,
+        NaiveDate::from_ymd_opt(2025, 7, 19)
+    );
+
+    let first_night = &concerts[0];
+    assert_eq!(first_night.title, "Prom 1: First Night of the Proms 2025");
+    assert_eq!(first_night.url, "https://bbc.co.uk/events/e4r7kb");
+    assert_eq!(
+        first_night.datetime,
+        Utc.with_ymd_and_hms(2025, 7, 18, 18, 0, 0).unwrap()
+    );
+    assert_eq!(first_night.pieces.len(), 3);
+    assert_eq!(
+        first_night.performers,
+        vec![
+            Performer {
+                name: "Yunchan Lim".to_string(),
+                instrument: Some("piano".to_string()),
+            },
+            Performer {
+                name: "BBC Symphony Orchestra".to_string(),
+                instrument: None,
+            },
+            Performer {
+                name: "Sakari Oramo".to_string(),
+                instrument: Some("conductor".to_string()),
+            },
+        ]
+    );
+    // Promming tickets at the Royal Albert Hall are cheaper than anything listed
+    assert_eq!(
+        (first_night.min_price, first_night.max_price),
+        (Some(800), Some(12000))
+    );
+    assert_eq!(first_night.category, Category::Orchestral);
+    assert!(!first_night.media.broadcasts.is_empty());
+
+    // Only Proms at the Royal Albert Hall have promming tickets
+    let relaxed = &concerts[1];
+    assert_eq!(
+        (relaxed.min_price, relaxed.max_price),
+        (Some(1200), Some(1200))
+    );
+    let kings_place = &concerts[2];
+    assert_eq!(kings_place.venue, "Kings Place");
+    assert_eq!(
+        (kings_place.min_price, kings_place.max_price),
+        (Some(1500), Some(1500))
+    );
+}
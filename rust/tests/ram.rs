@@ -0,0 +1,84 @@
+use london_classical::core::{self, Availability, Category, Performer, Piece};
+use london_classical::ram;
+
+#[test]
+fn listing_links_and_next_page() {
+    let listing = ram::parse_listing(
+        include_str!("fixtures/ram/listing.html"),
+        "https://www.ram.ac.uk/whats-on?type=concerts",
+    );
+    assert_eq!(
+        listing.event_urls,
+        vec![
+            "https://www.ram.ac.uk/whats-on/lunchtime-concert-brahms-piano-quintet",
+            "https://www.ram.ac.uk/whats-on/academy-symphony-orchestra-edward-gardner",
+        ]
+    );
+    assert_eq!(
+        listing.next_page_url.as_deref(),
+        Some("https://www.ram.ac.uk/whats-on?type=concerts&page=1")
+    );
+}
+
+#[test]
+fn free_lunchtime_concert_without_booking() {
+    let c = ram::parse_event(
+        "https://www.ram.ac.uk/whats-on/lunchtime-concert-brahms-piano-quintet",
+        include_str!("fixtures/ram/event_free.html"),
+    )
+    .unwrap();
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 2, 3, 13, 5).unwrap());
+    assert_eq!(c.venue, "Royal Academy of Music (Duke's Hall)");
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    assert_eq!(c.availability, Availability::FreeNoBooking);
+    assert_eq!(core::price_string(&c), "Free entry, no booking required");
+    assert_eq!(
+        c.performers,
+        vec![
+            Performer {
+                name: "Academy Soloists".to_string(),
+                instrument: None,
+            },
+            Performer {
+                name: "Mariam Batsashvili".to_string(),
+                instrument: Some("piano".to_string()),
+            },
+        ]
+    );
+    // "Programme to include" isn't a piece
+    assert_eq!(
+        c.pieces,
+        vec![Piece {
+            composer: "Brahms".to_string(),
+            title: "Piano Quintet in F minor, Op. 34".to_string(),
+        }]
+    );
+    assert_eq!(c.category, Category::Chamber);
+}
+
+#[test]
+fn paid_concert() {
+    let c = ram::parse_event(
+        "https://www.ram.ac.uk/whats-on/academy-symphony-orchestra-edward-gardner",
+        include_str!("fixtures/ram/event_paid.html"),
+    )
+    .unwrap();
+    assert_eq!(
+        c.datetime,
+        core::ymd_hm_to_utc(2026, 3, 27, 19, 30).unwrap()
+    );
+    assert_eq!(c.subtitle.as_deref(), Some("Mahler's Sixth"));
+    assert_eq!((c.min_price, c.max_price), (Some(1000), Some(1500)));
+    assert_eq!(c.availability, Availability::Ticketed);
+    assert_eq!(c.category, Category::Orchestral);
+}
+
+#[test]
+fn event_with_invalid_datetime_is_skipped() {
+    let html = r#"<h1 class="event-title">Lunchtime concert</h1>
+        <div class="event-meta__date"><time datetime="2026-02-30T13:05">30 February</time></div>"#;
+    assert_eq!(
+        ram::parse_event("https://www.ram.ac.uk/whats-on/lunchtime-concert", html),
+        None
+    );
+}
@@ -0,0 +1,87 @@
+use london_classical::core::{self, Availability, Category};
+use london_classical::datetime;
+use london_classical::rcm;
+
+#[test]
+fn listing_links() {
+    let urls = rcm::parse_listing(
+        include_str!("fixtures/rcm/listing.html"),
+        "https://www.rcm.ac.uk/events/?type=concert",
+    );
+    assert_eq!(
+        urls,
+        vec![
+            "https://www.rcm.ac.uk/events/2026/02/04-ravel-piano-trio/",
+            "https://www.rcm.ac.uk/events/2026/03/06-rcm-symphony-orchestra/",
+        ]
+    );
+}
+
+#[test]
+fn datetimes() {
+    assert_eq!(
+        datetime::parse_london("Wednesday 4 February 2026, 7.30pm"),
+        Ok(core::ymd_hm_to_utc(2026, 2, 4, 19, 30).unwrap())
+    );
+    assert_eq!(
+        datetime::parse_london("Friday 6 March 2026, 1pm"),
+        Ok(core::ymd_hm_to_utc(2026, 3, 6, 13, 0).unwrap())
+    );
+    assert_eq!(
+        datetime::parse_london("Saturday 11 April 2026, 11am"),
+        Ok(core::ymd_hm_to_utc(2026, 4, 11, 11, 0).unwrap())
+    );
+    assert_eq!(
+        datetime::parse_london("Friday 6 March 2026, 12 noon"),
+        Ok(core::ymd_hm_to_utc(2026, 3, 6, 12, 0).unwrap())
+    );
+    assert_eq!(
+        datetime::parse_london("Friday 6 March 2026, 12.30pm"),
+        Ok(core::ymd_hm_to_utc(2026, 3, 6, 12, 30).unwrap())
+    );
+}
+
+#[test]
+fn free_concert_without_booking() {
+    let c = rcm::parse_event(
+        "https://www.rcm.ac.uk/events/2026/02/04-ravel-piano-trio/",
+        include_str!("fixtures/rcm/event_free.html"),
+    )
+    .unwrap();
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 2, 4, 12, 0).unwrap());
+    assert_eq!(
+        c.venue,
+        "Royal College of Music (Amaryllis Fleming Concert Hall)"
+    );
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    assert_eq!(c.availability, Availability::FreeNoBooking);
+    assert_eq!(c.performers.len(), 3);
+    assert_eq!(c.performers[0].name, "Jâms Coleman");
+    assert_eq!(c.performers[0].instrument.as_deref(), Some("piano"));
+    assert_eq!(c.pieces[0].composer, "Ravel");
+    assert_eq!(c.pieces[0].title, "Piano Trio in A minor");
+    assert_eq!(c.series[0].name, "RCM Chamber Music Festival");
+    assert_eq!(c.category, Category::Chamber);
+}
+
+#[test]
+fn paid_concert() {
+    let c = rcm::parse_event(
+        "https://www.rcm.ac.uk/events/2026/03/06-rcm-symphony-orchestra/",
+        include_str!("fixtures/rcm/event_paid.html"),
+    )
+    .unwrap();
+    assert_eq!((c.min_price, c.max_price), (Some(500), Some(1500)));
+    assert_eq!(c.availability, Availability::Ticketed);
+    assert_eq!(c.pieces.len(), 1);
+    assert_eq!(c.category, Category::Orchestral);
+}
+
+#[test]
+fn event_without_title_is_skipped() {
+    let html = r#"<p>Wednesday 4 February 2026, 1pm</p>"#;
+    assert_eq!(
+        rcm::parse_event("https://www.rcm.ac.uk/events/2026/02/04-recital/", html),
+        None
+    );
+}
@@ -0,0 +1,155 @@
+use chrono::Utc;
+use london_classical::fixtures::{self, Fixture, Recording, Refresh};
+use std::path::{Path, PathBuf};
+
+/// A fresh directory for a test to write to
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("london_classical_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn record(dir: &Path, url: &str, body: &str) {
+    Recording {
+        url: url.to_string(),
+        fetched_at: Utc::now(),
+        body: body.to_string(),
+    }
+    .write(dir)
+    .unwrap();
+}
+
+fn fixture(path: &str, url: &str, url_prefix: Option<&str>) -> Fixture {
+    Fixture {
+        path: path.to_string(),
+        url: url.to_string(),
+        parser: "ram".to_string(),
+        url_prefix: url_prefix.map(|prefix| prefix.to_string()),
+    }
+}
+
+#[test]
+fn recordings_are_read_back() {
+    let dir = temp_dir("recordings");
+    record(
+        &dir,
+        "https://www.ram.ac.uk/whats-on?type=concerts",
+        "<html>listing</html>",
+    );
+    record(
+        &dir,
+        "https://www.ram.ac.uk/whats-on/recital",
+        "<html>recital</html>",
+    );
+    let recordings = fixtures::read_recordings(&dir);
+    assert_eq!(recordings.len(), 2);
+    assert_eq!(
+        recordings["https://www.ram.ac.uk/whats-on/recital"].body,
+        "<html>recital</html>"
+    );
+}
+
+#[test]
+fn fixtures_are_refreshed_from_recordings() {
+    let record_dir = temp_dir("record");
+    record(
+        &record_dir,
+        "https://www.ram.ac.uk/whats-on?type=concerts",
+        r#"<a href="/whats-on/new-recital">New recital</a>"#,
+    );
+    record(
+        &record_dir,
+        "https://www.ram.ac.uk/whats-on/another-recital",
+        "another",
+    );
+    record(
+        &record_dir,
+        "https://www.ram.ac.uk/whats-on/new-recital",
+        "new",
+    );
+    record(
+        &record_dir,
+        "https://www.gsmd.ac.uk/api/events?category=music",
+        r#"{"next":null,"events":[]}"#,
+    );
+
+    let fixtures_dir = temp_dir("fixtures");
+    std::fs::create_dir_all(fixtures_dir.join("ram")).unwrap();
+    std::fs::create_dir_all(fixtures_dir.join("guildhall")).unwrap();
+    std::fs::write(fixtures_dir.join("ram/event.html"), "old").unwrap();
+    let manifest = vec![
+        fixture(
+            "ram/event.html",
+            "https://www.ram.ac.uk/whats-on/old-recital",
+            Some("https://www.ram.ac.uk/whats-on/"),
+        ),
+        fixture(
+            "ram/listing.html",
+            "https://www.ram.ac.uk/whats-on?type=concerts",
+            None,
+        ),
+        fixture(
+            "ram/event_free.html",
+            "https://www.ram.ac.uk/whats-on/free-recital",
+            None,
+        ),
+        fixture(
+            "guildhall/events.json",
+            "https://www.gsmd.ac.uk/api/events?category=music",
+            None,
+        ),
+    ];
+    fixtures::write_manifest(&fixtures_dir, &manifest);
+
+    let recordings = fixtures::read_recordings(&record_dir);
+    let results = fixtures::refresh(&fixtures_dir, &recordings, &["ram".to_string()]);
+    let result_for = |path: &str| {
+        results
+            .iter()
+            .find(|(fixture, _)| fixture.path == path)
+            .map(|(_, result)| result.clone())
+    };
+    assert_eq!(
+        result_for("ram/listing.html"),
+        Some(Refresh::Updated { new_url: None })
+    );
+    // The event page linked from the new listing is used, rather than the first one recorded
+    assert_eq!(
+        result_for("ram/event.html"),
+        Some(Refresh::Updated {
+            new_url: Some("https://www.ram.ac.uk/whats-on/new-recital".to_string())
+        })
+    );
+    assert_eq!(
+        result_for("ram/event_free.html"),
+        Some(Refresh::NotRecorded)
+    );
+    // Only fixtures under the given paths are refreshed
+    assert_eq!(result_for("guildhall/events.json"), None);
+    assert!(!fixtures_dir.join("guildhall/events.json").exists());
+
+    assert_eq!(
+        std::fs::read_to_string(fixtures_dir.join("ram/event.html")).unwrap(),
+        "new"
+    );
+    let manifest = fixtures::read_manifest(&fixtures_dir);
+    assert_eq!(
+        manifest[0].url,
+        "https://www.ram.ac.uk/whats-on/new-recital"
+    );
+    assert_eq!(manifest.len(), 4);
+
+    // Refreshing again changes nothing, and JSON is pretty-printed
+    let results = fixtures::refresh(&fixtures_dir, &recordings, &[]);
+    let event = results
+        .iter()
+        .find(|(fixture, _)| fixture.path == "ram/event.html")
+        .unwrap();
+    assert_eq!(event.1, Refresh::Unchanged);
+    assert_eq!(
+        std::fs::read_to_string(fixtures_dir.join("guildhall/events.json")).unwrap(),
+        "{\n  \"events\": [],\n  \"next\": null\n}\n"
+    );
+}
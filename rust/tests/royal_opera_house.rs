@@ -0,0 +1,139 @@
+use london_classical::core::{self, CastMember, Category};
+use london_classical::royal_opera_house;
+
+const PRODUCTION_URL: &str = "https://www.rbo.org.uk/tickets-and-events/la-boheme-details";
+
+#[test]
+fn listing_only_has_operas() {
+    let urls =
+        royal_opera_house::parse_listing(include_str!("fixtures/royal_opera_house/listing.html"));
+    assert_eq!(
+        urls,
+        vec![
+            PRODUCTION_URL,
+            "https://www.rbo.org.uk/tickets-and-events/the-cunning-little-vixen-details",
+        ]
+    );
+}
+
+#[test]
+fn every_performance_has_its_own_cast_and_conductor() {
+    let concerts = royal_opera_house::parse_production(
+        PRODUCTION_URL,
+        include_str!("fixtures/royal_opera_house/production.html"),
+    );
+    assert_eq!(concerts.len(), 2);
+
+    let first = &concerts[0];
+    assert_eq!(
+        first.datetime,
+        core::ymd_hm_to_utc(2026, 3, 10, 19, 30).unwrap()
+    );
+    assert_eq!(first.title, "La bohème");
+    assert_eq!(first.venue, "Royal Opera House");
+    assert_eq!(first.category, Category::Opera);
+    assert_eq!(
+        (first.min_price, first.max_price),
+        (Some(1200), Some(25000))
+    );
+    assert_eq!(first.pieces.len(), 1);
+    assert_eq!(first.pieces[0].composer, "Giacomo Puccini");
+    assert_eq!(first.media.broadcasts[0].channel, "BBC Radio 3");
+
+    let production = first.production.as_ref().unwrap();
+    assert_eq!(production.title, "La bohème");
+    assert_eq!(production.composer, "Giacomo Puccini");
+    assert_eq!(production.director.as_deref(), Some("Richard Jones"));
+    assert_eq!(production.conductor.as_deref(), Some("Jakub Hrůša"));
+    assert_eq!(production.running_time_minutes, Some(165));
+    assert_eq!(production.n_intervals, Some(2));
+    assert_eq!(production.language.as_deref(), Some("Italian"));
+    assert_eq!(production.surtitles.as_deref(), Some("English"));
+    assert_eq!(
+        production.cast[0],
+        CastMember {
+            role: "Mimì".to_string(),
+            name: "Anna Netrebko".to_string(),
+        }
+    );
+    // The conductor and cast are also performers, so that they can be searched for
+    assert_eq!(first.performers.len(), 3);
+    assert_eq!(first.performers[0].name, "Jakub Hrůša");
+    assert_eq!(first.performers[0].instrument.as_deref(), Some("conductor"));
+    assert_eq!(first.performers[1].instrument.as_deref(), Some("Mimì"));
+
+    let second = &concerts[1];
+    // 18:30 UTC is 19:30 in London after the clocks go forward
+    assert_eq!(
+        second.datetime,
+        core::ymd_hm_to_utc(2026, 4, 2, 19, 30).unwrap()
+    );
+    assert_eq!(
+        (second.min_price, second.max_price),
+        (Some(950), Some(19500))
+    );
+    let production = second.production.as_ref().unwrap();
+    assert_eq!(production.conductor.as_deref(), Some("Kerem Hasan"));
+    assert_eq!(production.cast[0].name, "Ailyn Pérez");
+}
+
+#[test]
+fn linbury_theatre() {
+    let concerts = royal_opera_house::parse_production(
+        "https://www.rbo.org.uk/tickets-and-events/the-cunning-little-vixen-details",
+        include_str!("fixtures/royal_opera_house/production_linbury.html"),
+    );
+    assert_eq!(concerts.len(), 1);
+    let c = &concerts[0];
+    assert_eq!(c.venue, "Royal Opera House (Linbury Theatre)");
+    assert_eq!(c.subtitle, None);
+    let production = c.production.as_ref().unwrap();
+    assert_eq!(production.conductor, None);
+    assert!(production.cast.is_empty());
+    assert_eq!(production.running_time_minutes, Some(100));
+    assert_eq!(production.n_intervals, Some(0));
+    assert_eq!(production.language.as_deref(), Some("English"));
+    assert_eq!(production.surtitles, None);
+    assert!(c.performers.is_empty());
+}
+
+fn next_data(props: &str) -> String {
+    format!(
+        r#"<script id="__NEXT_DATA__" type="application/json">{{"props": {{"pageProps": {}}}}}</script>"#,
+        props
+    )
+}
+
+#[test]
+fn malformed_productions_are_skipped() {
+    // No __NEXT_DATA__ at all
+    assert!(royal_opera_house::parse_listing("<html></html>").is_empty());
+    assert!(royal_opera_house::parse_production(PRODUCTION_URL, "<html></html>").is_empty());
+    // No composer
+    let html = next_data(
+        r#"{"production": {"title": "Tosca", "performances": [{"dateTime": "2026-03-06T19:00:00Z"}]}}"#,
+    );
+    assert!(royal_opera_house::parse_production(PRODUCTION_URL, &html).is_empty());
+    // No performances
+    let html = next_data(r#"{"production": {"title": "Tosca", "composer": "Puccini"}}"#);
+    assert!(royal_opera_house::parse_production(PRODUCTION_URL, &html).is_empty());
+}
+
+#[test]
+fn cast_members_without_a_role_are_left_out() {
+    let html = next_data(
+        r#"{"production": {"title": "Tosca", "composer": "Puccini", "performances": [
+            {"dateTime": "2026-03-06T19:00:00Z",
+             "cast": [{"name": "Understudy"}, {"role": "Tosca", "name": "Anna Netrebko"}]}
+        ]}}"#,
+    );
+    let concerts = royal_opera_house::parse_production(PRODUCTION_URL, &html);
+    assert_eq!(concerts.len(), 1);
+    assert_eq!(
+        concerts[0].production.as_ref().unwrap().cast,
+        vec![CastMember {
+            role: "Tosca".to_string(),
+            name: "Anna Netrebko".to_string(),
+        }]
+    );
+}
@@ -0,0 +1,164 @@
+use london_classical::core::{self, Availability, Category};
+use london_classical::schema_org;
+use london_classical::source::Source;
+
+#[test]
+fn listing_links() {
+    let urls = schema_org::parse_listing(
+        include_str!("fixtures/schema_org/cadogan_listing.html"),
+        "https://www.cadoganhall.com/whats-on/",
+        "a.event-item__link",
+    );
+    assert_eq!(
+        urls,
+        vec![
+            "https://www.cadoganhall.com/whats-on/royal-philharmonic-orchestra-beethoven/",
+            "https://www.cadoganhall.com/whats-on/chamber-sundays-castalian-quartet/",
+        ]
+    );
+}
+
+#[test]
+fn music_event() {
+    let url = "https://www.cadoganhall.com/whats-on/royal-philharmonic-orchestra-beethoven/";
+    let concerts = schema_org::parse_page(
+        include_str!("fixtures/schema_org/cadogan_event.html"),
+        url,
+        "Cadogan Hall",
+    );
+    assert_eq!(concerts.len(), 1);
+    let c = &concerts[0];
+    assert_eq!(c.title, "Royal Philharmonic Orchestra: Beethoven");
+    // Events without a URL of their own are on the page they were found on
+    assert_eq!(c.url, url);
+    assert_eq!(
+        c.datetime,
+        core::ymd_hm_to_utc(2026, 5, 14, 19, 30).unwrap()
+    );
+    assert_eq!(c.venue, "Cadogan Hall");
+    assert_eq!(
+        c.description.as_deref(),
+        Some("Beethoven's Emperor Concerto and Seventh Symphony.")
+    );
+
+    let performers: Vec<(&str, Option<&str>)> = c
+        .performers
+        .iter()
+        .map(|p| (p.name.as_str(), p.instrument.as_deref()))
+        .collect();
+    assert_eq!(
+        performers,
+        vec![
+            ("Royal Philharmonic Orchestra", None),
+            ("Vasily Petrenko", Some("conductor")),
+            ("Benjamin Grosvenor", Some("piano")),
+        ]
+    );
+
+    assert_eq!(c.pieces.len(), 2);
+    assert_eq!(c.pieces[0].composer, "Ludwig van Beethoven");
+    assert_eq!(
+        c.pieces[0].title,
+        "Piano Concerto No. 5 in E flat, Op. 73 'Emperor'"
+    );
+    assert_eq!(c.pieces[1].composer, "Ludwig van Beethoven");
+
+    assert_eq!((c.min_price, c.max_price), (Some(1800), Some(6500)));
+    assert_eq!(c.availability, Availability::Ticketed);
+    assert_eq!(c.category, Category::Orchestral);
+}
+
+#[test]
+fn graph_with_cancelled_event() {
+    let concerts = schema_org::parse_page(
+        include_str!("fixtures/schema_org/st_martin_event.html"),
+        "https://www.stmartin-in-the-fields.org/whats-on/?type=concerts",
+        "St Martin-in-the-Fields",
+    );
+    // The cancelled event and the page itself are left out
+    assert_eq!(concerts.len(), 1);
+    let c = &concerts[0];
+    assert_eq!(
+        c.url,
+        "https://www.stmartin-in-the-fields.org/whats-on/lunchtime-concert-chloe-hanslip/"
+    );
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 6, 2, 13, 0).unwrap());
+    // No location, so the site's own venue is used
+    assert_eq!(c.venue, "St Martin-in-the-Fields");
+    assert_eq!(c.performers[1].name, "Danny Driver");
+    assert_eq!(c.performers[1].instrument.as_deref(), Some("piano"));
+    assert_eq!(c.pieces[0].composer, "Fritz Kreisler & Sergei Rachmaninoff");
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    assert_eq!(c.availability, Availability::RetiringCollection);
+    assert_eq!(c.category, Category::Recital);
+}
+
+#[test]
+fn box_office_links_are_replaced_with_the_page() {
+    let html = r#"<script type="application/ld+json">
+        {"@type": "MusicEvent", "name": "Vivaldi: The Four Seasons",
+         "startDate": "2026-06-05T19:30:00+01:00",
+         "url": "https://tickets.example.com/events/4521"}
+    </script>"#;
+    let url = "https://www.stmartin-in-the-fields.org/whats-on/vivaldi-four-seasons/";
+    let concerts = schema_org::parse_page(html, url, "St Martin-in-the-Fields");
+    assert_eq!(concerts[0].url, url);
+    assert_eq!(
+        Source::from_url(&concerts[0].url),
+        Some(Source::StMartinInTheFields)
+    );
+}
+
+#[test]
+fn events_without_a_name_are_skipped() {
+    let html = r#"<script type="application/ld+json">
+        [{"@type": "MusicEvent", "startDate": "2026-06-05T19:30:00+01:00"},
+         {"@type": "MusicEvent", "name": "Bach by Candlelight",
+          "startDate": "2026-06-06T19:30:00+01:00"}]
+    </script>"#;
+    let url = "https://www.stmartin-in-the-fields.org/whats-on/bach-by-candlelight/";
+    let concerts = schema_org::parse_page(html, url, "St Martin-in-the-Fields");
+    assert_eq!(concerts.len(), 1);
+    assert_eq!(concerts[0].title, "Bach by Candlelight");
+}
+
+#[test]
+fn each_site_is_its_own_source() {
+    assert_eq!(
+        Source::from_url(
+            "https://www.cadoganhall.com/whats-on/royal-philharmonic-orchestra-beethoven/"
+        ),
+        Some(Source::CadoganHall)
+    );
+    assert_eq!(
+        Source::from_url("https://stmartin-in-the-fields.org/whats-on/"),
+        Some(Source::StMartinInTheFields)
+    );
+    assert_eq!(Source::from_url("https://example.com/concert"), None);
+}
+
+#[test]
+fn site_definitions() {
+    for (name, source) in [
+        ("cadogan_hall", Source::CadoganHall),
+        ("st_martin_in_the_fields", Source::StMartinInTheFields),
+    ] {
+        let site = schema_org::Site::load(name);
+        assert_eq!(site.name, source.name());
+        assert_eq!(Source::from_url(&site.listing_url), Some(source));
+        assert_eq!(source.venues(), Some(&[site.venue.as_str()][..]));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Invalid link selector")]
+fn invalid_link_selectors_are_rejected() {
+    schema_org::Site::from_toml(
+        r#"
+        name = "Cadogan Hall"
+        listing_url = "https://www.cadoganhall.com/whats-on/"
+        link = "a.event-item__link["
+        venue = "Cadogan Hall"
+        "#,
+    );
+}
@@ -0,0 +1,188 @@
+//! Every saved page in `fixtures/fixtures.toml` is run through its parser, and the result is
+//! compared with the snapshot of it in `snapshots/`. When a parser or a page changes on purpose,
+//! run with `LDNCLS_UPDATE_SNAPSHOTS=1` to write new snapshots, and check the changes to them.
+
+use chrono::NaiveDate;
+use london_classical::churches::{smith_square, southwark_cathedral, st_pauls, westminster_abbey};
+use london_classical::declarative::{self, Definition};
+use london_classical::fixtures::{self, Fixture};
+use london_classical::{
+    eno, guildhall, kingsplace, lso_st_lukes, proms, ram, rcm, royal_opera_house, schema_org,
+    wigmore,
+};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+fn snapshots_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn to_json<T: serde::Serialize>(value: T) -> Value {
+    serde_json::to_value(value).unwrap()
+}
+
+/// The Wigmore Hall listing entry for a concert page, which is where its date and title come from
+fn wigmore_entry(url: &str) -> wigmore::WigmoreFrontPageConcert {
+    let listing =
+        std::fs::read_to_string(fixtures::fixtures_dir().join("wigmore/listing.json")).unwrap();
+    let json: Value = serde_json::from_str(&listing).unwrap();
+    wigmore::parse_api(json["items"].as_array().unwrap())
+        .into_iter()
+        .find(|entry| entry.url == url)
+        .unwrap_or_else(|| panic!("{} isn't in wigmore/listing.json", url))
+}
+
+/// Parse a saved page with the parser named in the manifest
+fn parse(fixture: &Fixture, body: &str) -> Value {
+    let url = fixture.url.as_str();
+    match fixture.parser.as_str() {
+        "eno" => to_json(eno::parse_production(url, body)),
+        "eno_listing" => to_json(eno::parse_listing(body, url)),
+        "guildhall" => {
+            let page = guildhall::parse_events(&serde_json::from_str(body).unwrap());
+            json!({"concerts": page.concerts, "next_page_url": page.next_page_url})
+        }
+        "kingsplace" => to_json(kingsplace::parse_event(url, body)),
+        "kingsplace_listing" => {
+            let listing = kingsplace::parse_listing(body, url);
+            json!({"event_urls": listing.event_urls, "next_page_url": listing.next_page_url})
+        }
+        "lso_st_lukes" => to_json(lso_st_lukes::parse_event(url, body)),
+        "lso_st_lukes_listing" => to_json(lso_st_lukes::parse_listing(body, url)),
+        // Nothing is left out for being in the past
+        "proms" => to_json(proms::parse_calendar(body, NaiveDate::MIN)),
+        "ram" => to_json(ram::parse_event(url, body)),
+        "ram_listing" => {
+            let listing = ram::parse_listing(body, url);
+            json!({"event_urls": listing.event_urls, "next_page_url": listing.next_page_url})
+        }
+        "rcm" => to_json(rcm::parse_event(url, body)),
+        "rcm_listing" => to_json(rcm::parse_listing(body, url)),
+        "royal_opera_house" => to_json(royal_opera_house::parse_production(url, body)),
+        "royal_opera_house_listing" => to_json(royal_opera_house::parse_listing(body)),
+        "cadogan_hall" | "st_martin_in_the_fields" => {
+            let site = schema_org::Site::load(&fixture.parser);
+            to_json(schema_org::parse_page(body, url, &site.venue))
+        }
+        "cadogan_hall_listing" => {
+            let site = schema_org::Site::load("cadogan_hall");
+            to_json(schema_org::parse_listing(
+                body,
+                url,
+                site.link.as_deref().unwrap(),
+            ))
+        }
+        "smith_square" => to_json(smith_square::parse_listing(body, url)),
+        "southbank" => to_json(declarative::parse_event(
+            &Definition::load("southbank"),
+            url,
+            body,
+        )),
+        "southbank_listing" => {
+            let listing = declarative::parse_listing(&Definition::load("southbank"), body, url);
+            json!({"event_urls": listing.event_urls, "next_page_url": listing.next_page_url})
+        }
+        "southwark_cathedral" => to_json(southwark_cathedral::parse_listing(body, url)),
+        "st_pauls" => to_json(st_pauls::parse_listing(body, url)),
+        "westminster_abbey" => to_json(westminster_abbey::parse_listing(body, url)),
+        "wigmore" => to_json(wigmore::parse_concert_page(&wigmore_entry(url), body).unwrap()),
+        "wigmore_listing" => {
+            let json: Value = serde_json::from_str(body).unwrap();
+            to_json(wigmore::parse_api(json["items"].as_array().unwrap()))
+        }
+        parser => panic!("Unknown parser {} for {}", parser, fixture.path),
+    }
+}
+
+/// Lines which differ between two snapshots, to show what changed without printing the whole of
+/// both of them
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let first = expected
+        .iter()
+        .zip(&actual)
+        .position(|(e, a)| e != a)
+        .unwrap_or(expected.len().min(actual.len()));
+    let context = |lines: &[&str]| -> String {
+        lines
+            .iter()
+            .skip(first.saturating_sub(3))
+            .take(8)
+            .map(|line| format!("    {}", line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    format!(
+        "first difference at line {}\n  expected:\n{}\n  actual:\n{}",
+        first + 1,
+        context(&expected),
+        context(&actual)
+    )
+}
+
+#[test]
+fn snapshots() {
+    let update = std::env::var("LDNCLS_UPDATE_SNAPSHOTS").is_ok_and(|value| !value.is_empty());
+    let mut failures = vec![];
+
+    for fixture in fixtures::read_manifest(&fixtures::fixtures_dir()) {
+        let body = std::fs::read_to_string(fixtures::fixtures_dir().join(&fixture.path))
+            .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", fixture.path, e));
+        let actual = serde_json::to_string_pretty(&parse(&fixture, &body)).unwrap() + "\n";
+
+        let snapshot_path = snapshots_dir().join(Path::new(&fixture.path).with_extension("json"));
+        match std::fs::read_to_string(&snapshot_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) if !update => failures.push(format!(
+                "{} doesn't match {}: {}",
+                fixture.path,
+                snapshot_path.display(),
+                diff_lines(&expected, &actual)
+            )),
+            Err(_) if !update => failures.push(format!(
+                "{} has no snapshot at {}",
+                fixture.path,
+                snapshot_path.display()
+            )),
+            _ => {
+                std::fs::create_dir_all(snapshot_path.parent().unwrap()).unwrap();
+                std::fs::write(&snapshot_path, actual).unwrap();
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\n\nIf these changes are intended, run with LDNCLS_UPDATE_SNAPSHOTS=1 to update the \
+         snapshots",
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn every_fixture_is_in_the_manifest() {
+    let manifest: Vec<String> = fixtures::read_manifest(&fixtures::fixtures_dir())
+        .into_iter()
+        .map(|fixture| fixture.path)
+        .collect();
+    for dir in std::fs::read_dir(fixtures::fixtures_dir()).unwrap() {
+        let dir = dir.unwrap().path();
+        if !dir.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(&dir).unwrap() {
+            let path = file.unwrap().path();
+            let relative = path
+                .strip_prefix(fixtures::fixtures_dir())
+                .unwrap()
+                .to_string_lossy()
+                .to_string();
+            assert!(
+                manifest.contains(&relative),
+                "{} isn't in fixtures.toml",
+                relative
+            );
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use london_classical::core::{self, Availability, BroadcastMedium, Category};
+use london_classical::datetime;
+use london_classical::declarative::{self, Definition};
+
+fn definition() -> Definition {
+    Definition::from_toml(include_str!("../scrapers/southbank.toml"))
+}
+
+#[test]
+fn listing_links() {
+    let listing = declarative::parse_listing(
+        &definition(),
+        include_str!("fixtures/southbank/listing.html"),
+        "https://www.southbankcentre.co.uk/whats-on/page/1/?artform-filter=classical-music",
+    );
+    assert_eq!(
+        listing.event_urls,
+        vec![
+            "https://www.southbankcentre.co.uk/whats-on/philharmonia-orchestra-mahler-2/",
+            "https://www.southbankcentre.co.uk/whats-on/free-foyer-music-royal-academy-of-music/",
+        ]
+    );
+    assert_eq!(listing.next_page_url, None);
+}
+
+#[test]
+fn datetimes() {
+    assert_eq!(
+        datetime::parse_london("Sat 7 Feb 2026, 7.30pm"),
+        Ok(core::ymd_hm_to_utc(2026, 2, 7, 19, 30).unwrap())
+    );
+    assert_eq!(
+        datetime::parse_london("Sun 8 Feb 2026, 3pm"),
+        Ok(core::ymd_hm_to_utc(2026, 2, 8, 15, 0).unwrap())
+    );
+    assert_eq!(
+        datetime::parse_london("Tue 17 Feb 2026, 12.05pm"),
+        Ok(core::ymd_hm_to_utc(2026, 2, 17, 12, 5).unwrap())
+    );
+    // British Summer Time
+    assert_eq!(
+        datetime::parse_london("Sat 4 Apr 2026, 11am"),
+        Ok(core::ymd_hm_to_utc(2026, 4, 4, 11, 0).unwrap())
+    );
+    // No time means midnight
+    assert_eq!(
+        datetime::parse_london("Sat 7 Feb 2026"),
+        Ok(core::ymd_hm_to_utc(2026, 2, 7, 0, 0).unwrap())
+    );
+    assert!(datetime::parse_london("Sometime soon").is_err());
+}
+
+#[test]
+fn event() {
+    let url = "https://www.southbankcentre.co.uk/whats-on/philharmonia-orchestra-mahler-2/";
+    let c = declarative::parse_event(
+        &definition(),
+        url,
+        include_str!("fixtures/southbank/event.html"),
+    )
+    .unwrap();
+    assert_eq!(c.title, "Philharmonia Orchestra: Mahler 2");
+    assert_eq!(
+        c.datetime,
+        core::ymd_hm_to_utc(2026, 3, 12, 19, 30).unwrap()
+    );
+    assert_eq!(c.venue, "Royal Festival Hall");
+    // Southbank only gives "from" prices
+    assert_eq!((c.min_price, c.max_price), (Some(1500), None));
+    assert_eq!(c.availability, Availability::Ticketed);
+
+    assert_eq!(c.performers.len(), 3);
+    assert_eq!(c.performers[0].instrument, None);
+    assert_eq!(c.performers[1].name, "Santtu-Matias Rouvali");
+    assert_eq!(c.performers[1].instrument.as_deref(), Some("conductor"));
+
+    // Several works by one composer are separated by semicolons, and the interval isn't a work
+    let pieces: Vec<(&str, &str)> = c
+        .pieces
+        .iter()
+        .map(|p| (p.composer.as_str(), p.title.as_str()))
+        .collect();
+    assert_eq!(
+        pieces,
+        vec![
+            ("Mahler", "Blumine"),
+            ("Mahler", "Symphony No.2 'Resurrection'"),
+        ]
+    );
+
+    assert_eq!(c.series.len(), 1);
+    assert_eq!(c.series[0].organiser, "Southbank Centre");
+    assert_eq!(
+        c.series[0].url.as_deref(),
+        Some("https://www.southbankcentre.co.uk/whats-on/festivals-series/philharmonia-season")
+    );
+    assert_eq!(c.media.broadcasts.len(), 1);
+    assert_eq!(c.media.broadcasts[0].medium, BroadcastMedium::Radio);
+    assert_eq!(c.category, Category::Orchestral);
+}
+
+#[test]
+fn free_event() {
+    let c = declarative::parse_event(
+        &definition(),
+        "https://www.southbankcentre.co.uk/whats-on/free-foyer-music-royal-academy-of-music/",
+        include_str!("fixtures/southbank/event_free.html"),
+    )
+    .unwrap();
+    assert_eq!(c.datetime, core::ymd_hm_to_utc(2026, 3, 13, 17, 0).unwrap());
+    assert_eq!(c.venue, "Queen Elizabeth Hall");
+    assert_eq!((c.min_price, c.max_price), (Some(0), Some(0)));
+    assert_eq!(c.availability, Availability::FreeNoBooking);
+}
+
+#[test]
+fn events_without_a_title_or_venue_are_skipped() {
+    let datetime = r#"<div class="c-event-masthead__event-datetime">Sat 7 Feb 2026, 7.30pm</div>"#;
+    let without_title = format!(
+        r#"<span class="c-event-masthead__event-location-label-text">Royal Festival Hall</span>{}"#,
+        datetime
+    );
+    let without_venue = format!(
+        r#"<h1 class="c-event-masthead__title">Philharmonia Orchestra</h1>{}"#,
+        datetime
+    );
+    for html in [without_title, without_venue] {
+        assert_eq!(
+            declarative::parse_event(
+                &definition(),
+                "https://www.southbankcentre.co.uk/whats-on/philharmonia-orchestra/",
+                &html,
+            ),
+            None
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "Invalid selector")]
+fn invalid_selectors_are_caught_when_loading() {
+    let broken = include_str!("../scrapers/southbank.toml")
+        .replace("a.c-event-card__cover-link", "a.c-event-card__cover-link[");
+    Definition::from_toml(&broken);
+}
@@ -0,0 +1,101 @@
+use chrono::{TimeZone, Utc};
+use london_classical::core::{Availability, Category, Performer, Piece};
+use london_classical::wigmore;
+use serde_json::Value;
+
+fn listing() -> Vec<wigmore::WigmoreFrontPageConcert> {
+    let json: Value = serde_json::from_str(include_str!("fixtures/wigmore/listing.json")).unwrap();
+    wigmore::parse_api(json["items"].as_array().unwrap())
+}
+
+#[test]
+fn api_listing() {
+    let concerts = listing();
+    assert_eq!(concerts.len(), 2);
+    assert_eq!(
+        concerts[0].datetime,
+        Utc.with_ymd_and_hms(2026, 3, 6, 19, 30, 0).unwrap()
+    );
+    assert_eq!(
+        concerts[0].url,
+        "https://wigmore-hall.org.uk/whats-on/202603061930"
+    );
+    assert_eq!(concerts[0].subtitle.as_deref(), Some("Dvořák and Janáček"));
+    // British Summer Time
+    assert_eq!(
+        concerts[1].datetime,
+        Utc.with_ymd_and_hms(2026, 3, 29, 10, 30, 0).unwrap()
+    );
+    assert_eq!(
+        concerts[1].title,
+        "Sunday Morning Coffee Concert: Carolyn Sampson"
+    );
+    assert_eq!(concerts[1].subtitle, None);
+    // Any change to the listing entry means the concert is fetched again
+    assert_ne!(concerts[0].fingerprint, concerts[1].fingerprint);
+}
+
+#[test]
+fn concert_page() {
+    let listing = listing();
+    let c = wigmore::parse_concert_page(&listing[0], include_str!("fixtures/wigmore/concert.html"))
+        .unwrap();
+    assert_eq!(c.title, "Pavel Haas Quartet");
+    assert_eq!(c.venue, "Wigmore Hall");
+    assert_eq!(
+        c.pieces,
+        vec![
+            Piece {
+                composer: "Antonín Dvořák".to_string(),
+                title: "String Quartet No. 12 in F 'American'".to_string(),
+            },
+            Piece {
+                composer: "Leoš Janáček".to_string(),
+                title: "Intimate Letters: String Quartet No. 2".to_string(),
+            },
+            Piece {
+                composer: "".to_string(),
+                title: "Encore".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        c.performers[1],
+        Performer {
+            name: "Boris Giltburg".to_string(),
+            instrument: Some("piano".to_string()),
+        }
+    );
+    assert_eq!((c.min_price, c.max_price), (Some(1800), Some(4200)));
+    assert_eq!(c.availability, Availability::Ticketed);
+    assert!(c.is_wigmore_u35);
+    assert_eq!(c.category, Category::Chamber);
+    assert_eq!(
+        c.series[0].url.as_deref(),
+        Some("https://wigmore-hall.org.uk/series/chamber-music")
+    );
+    assert_eq!(
+        c.programme_pdf_url.as_deref(),
+        Some("https://wigmore-hall.org.uk/media/programmes/202603061930.pdf")
+    );
+    // Paragraphs are split and the (invalidly escaped) comment is dropped
+    assert_eq!(
+        c.description.as_deref(),
+        Some("The Pavel Haas Quartet return with two of the great Czech quartets.\nThis concert will be broadcast live on BBC Radio 3.")
+    );
+    assert_eq!(c.media.broadcasts.len(), 1);
+}
+
+#[test]
+fn invalid_json_is_an_error() {
+    let listing = listing();
+    let html = r#"<script id="props" type="application/json">{"data":</script>"#;
+    assert!(wigmore::parse_concert_page(&listing[0], html).is_err());
+}
+
+#[test]
+fn page_without_props_is_an_error() {
+    let listing = listing();
+    let html = "<html><body><h1>Service unavailable</h1></body></html>";
+    assert!(wigmore::parse_concert_page(&listing[0], html).is_err());
+}
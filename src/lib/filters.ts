@@ -27,7 +27,22 @@ export const allBooleanFilters: BooleanFilter[] = [
     {
         tagName: "BBC Proms",
         tagColor: "#c462f5",
-        filterFunc: (concert: Concert) => concert.is_prom,
+        filterFunc: (concert: Concert) => concert.series.some((s) => s.organiser === "BBC" && s.name.startsWith("BBC Proms")),
+    },
+    {
+        tagName: "Free, no booking",
+        tagColor: "#3a9d5d",
+        filterFunc: (concert: Concert) => concert.availability === "free_no_booking" || concert.availability === "retiring_collection",
+    },
+    {
+        tagName: "Livestream",
+        tagColor: "#2f9e5b",
+        filterFunc: (concert: Concert) => concert.media.livestream_url !== null,
+    },
+    {
+        tagName: "Broadcast",
+        tagColor: "#d08a1e",
+        filterFunc: (concert: Concert) => concert.media.broadcasts.length > 0,
     },
     {
         tagName: "Royal Albert Hall",
@@ -38,7 +53,88 @@ export const allBooleanFilters: BooleanFilter[] = [
         tagName: "Southbank Centre",
         tagColor: "#b84954",
         filterFunc: (concert: Concert) => concert.venue.includes("Royal Festival Hall") || concert.venue.includes("Queen Elizabeth Hall") || concert.venue.includes("Purcell Room"),
-    }
+    },
+    {
+        tagName: "Kings Place",
+        tagColor: "#4a6fa5",
+        filterFunc: (concert: Concert) => concert.venue.includes("Kings Place"),
+    },
+    {
+        tagName: "Conservatoires",
+        tagColor: "#7b5ea7",
+        // Milton Court and Silk Street are the Guildhall School's halls
+        filterFunc: (concert: Concert) => concert.venue.includes("Royal Academy of Music") || concert.venue.includes("Royal College of Music") || concert.venue.startsWith("Milton Court") || concert.venue.startsWith("Silk Street"),
+    },
+    {
+        tagName: "LSO St Luke's",
+        tagColor: "#8a6d3b",
+        filterFunc: (concert: Concert) => concert.venue === "LSO St Luke's",
+    },
+    {
+        tagName: "Royal Opera House",
+        tagColor: "#a3213a",
+        filterFunc: (concert: Concert) => concert.venue.startsWith("Royal Opera House"),
+    },
+    {
+        tagName: "ENO",
+        tagColor: "#1f6f8b",
+        filterFunc: (concert: Concert) => concert.venue === "London Coliseum",
+    },
+    {
+        tagName: "Cadogan Hall",
+        tagColor: "#b5651d",
+        filterFunc: (concert: Concert) => concert.venue === "Cadogan Hall",
+    },
+    {
+        tagName: "St Martin-in-the-Fields",
+        tagColor: "#4f7942",
+        filterFunc: (concert: Concert) => concert.venue === "St Martin-in-the-Fields",
+    },
+    {
+        tagName: "Churches",
+        tagColor: "#5c6b73",
+        filterFunc: (concert: Concert) => concert.venue.includes("Cathedral") || concert.venue.includes("Westminster Abbey") || concert.venue === "St John's Smith Square",
+    },
+    {
+        tagName: "Recital",
+        tagColor: "#6d7fcc",
+        filterFunc: (concert: Concert) => concert.category === "recital",
+    },
+    {
+        tagName: "Chamber",
+        tagColor: "#5a9e9e",
+        filterFunc: (concert: Concert) => concert.category === "chamber",
+    },
+    {
+        tagName: "Orchestral",
+        tagColor: "#a35d3b",
+        filterFunc: (concert: Concert) => concert.category === "orchestral",
+    },
+    {
+        tagName: "Opera",
+        tagColor: "#9c2f5f",
+        filterFunc: (concert: Concert) => concert.category === "opera",
+    },
+    {
+        tagName: "Choral",
+        tagColor: "#7a8f3a",
+        filterFunc: (concert: Concert) => concert.category === "choral",
+    },
+    {
+        tagName: "Family",
+        tagColor: "#e07b39",
+        filterFunc: (concert: Concert) => concert.category === "family",
+    },
+    {
+        tagName: "Talk",
+        tagColor: "#777777",
+        filterFunc: (concert: Concert) => concert.category === "talk",
+    },
+    {
+        tagName: "Service",
+        tagColor: "#8c7b5a",
+        filterFunc: (concert: Concert) => concert.category === "service",
+    },
 ];
 
 // Check if a concert satisfies the filters
@@ -1,13 +1,21 @@
 import { get, writable, type Writable } from 'svelte/store';
 import { type Concert } from "src/lib/bindings/Concert";
+import { type Envelope } from "src/lib/bindings/Envelope";
 import { initialFilters, type FiltersType } from "src/lib/filters";
 
 // Concerts and their views
 type ConcertViews = Map<string, Concert[]>;
-import allConcertsIncludingPast from "src/assets/concerts.json";
+import concertsJson from "src/assets/concerts.json";
 export const defaultViewName = "All";
 const localStorageKey = "local_views";
 
+// concerts.json is usually wrapped in an envelope with its schema version and where the concerts
+// came from, but older copies of it (or ones written with $LDNCLS_BARE_ARRAY) are just the array
+const concertsFile = concertsJson as unknown as Envelope | Concert[];
+const allConcertsIncludingPast: Concert[] = Array.isArray(concertsFile)
+    ? concertsFile
+    : concertsFile.concerts;
+
 // Filter out concerts that are in the past
 const now = new Date();
 export let allConcerts = allConcertsIncludingPast.filter((concert: Concert) => {
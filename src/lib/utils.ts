@@ -16,6 +16,12 @@ function toPounds(price: number): string {
 }
 
 export function getPriceString(concert: Concert): string {
+    if (concert.availability === "free_no_booking") {
+        return "Free entry, no booking required";
+    }
+    if (concert.availability === "retiring_collection") {
+        return "Free entry, retiring collection";
+    }
     if (concert.min_price !== null && concert.max_price !== null) {
         if (concert.min_price === concert.max_price) {
             if (concert.min_price === 0) {
@@ -36,6 +42,61 @@ export function getPriceString(concert: Concert): string {
     }
 }
 
+export function getBroadcastString(concert: Concert): string {
+    const broadcasts = concert.media.broadcasts.map((b) =>
+        b.is_live ? `live on ${b.channel}` : `on ${b.channel}`
+    );
+    return `Broadcast ${broadcasts.join(", ")}`;
+}
+
+export function getOnDemandString(concert: Concert): string {
+    const onDemand = concert.media.on_demand;
+    if (onDemand === null) {
+        return "";
+    }
+    if (onDemand.until === null) {
+        return "Available on demand afterwards";
+    }
+    const until = new Date(onDemand.until).toLocaleString(undefined, { day: 'numeric', month: 'long', year: 'numeric' });
+    return `Available on demand until ${until}`;
+}
+
+export function getRunningTimeString(concert: Concert): string {
+    const production = concert.production;
+    if (production === null || production.running_time_minutes === null) {
+        return "";
+    }
+    const hours = Math.floor(production.running_time_minutes / 60);
+    const minutes = production.running_time_minutes % 60;
+    let runningTime;
+    if (hours === 0) {
+        runningTime = `${minutes}m`;
+    } else if (minutes === 0) {
+        runningTime = `${hours}h`;
+    } else {
+        runningTime = `${hours}h ${minutes}m`;
+    }
+    if (production.n_intervals === 0) {
+        runningTime += ", no interval";
+    } else if (production.n_intervals === 1) {
+        runningTime += ", including 1 interval";
+    } else if (production.n_intervals !== null) {
+        runningTime += `, including ${production.n_intervals} intervals`;
+    }
+    return runningTime;
+}
+
+export function getLanguageString(concert: Concert): string {
+    const production = concert.production;
+    if (production === null || production.language === null) {
+        return "";
+    }
+    if (production.surtitles === null) {
+        return `Sung in ${production.language}`;
+    }
+    return `Sung in ${production.language} with ${production.surtitles} surtitles`;
+}
+
 export function notUndefined<T>(x: T | undefined): T {
     if (x === undefined) {
         throw new Error("Unexpected undefined value");